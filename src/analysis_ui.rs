@@ -0,0 +1,957 @@
+use chaos_lab::{dynamical_system::{DynamicalSystem, Map}, embedding::{average_mutual_information, delay_embed, embedding_dimension, false_nearest_neighbours, first_minimum}, dimension::{box_counting_dimension, correlation_dimension, diameter, information_dimension, ScalingFit}, double_pendulum::DoublePendulum, heatmap::Heatmap, henon::Henon, import::{read_csv_headers, read_csv_trajectory_from_path}, logistic_map::LogisticMap, lorenz::Lorenz, lyapunov::{divergence_fit, kantz, rosenstein}, periodic_orbits::{find_flow_orbits, find_map_orbits, PeriodicOrbit, Section}, recurrence::{RecurrencePlot, Rqa, Threshold}, spectrum::{spectrogram, welch, Spectrum, Window}, sweep::ramp_map, trajectory::Trajectory, zero_one::{translation, zero_one_test, ZeroOne}};
+use eframe::egui;
+use egui_plot::{Line, Plot, PlotPoints, Points};
+
+use crate::{show_image_plot, worker::Worker};
+
+// recurrence plots are n × n, only this many samples from the start of the trajectory are used
+const RECURRENCE_SAMPLES: usize = 2000;
+
+// segment lengths offered for the spectra, powers of two for the fft
+const SEGMENTS: [usize; 7] = [64, 128, 256, 512, 1024, 2048, 4096];
+// iterations of the logistic ramp, and the spectrogram's segment and hop
+const RAMP_ITERATIONS: usize = 1 << 16;
+const RAMP_SEGMENT: usize = 256;
+const RAMP_HOP: usize = 128;
+
+// largest delay (in samples) and dimension the embedding is searched over
+const MAX_DELAY: usize = 50;
+const MAX_EMBEDDING_DIMENSION: usize = 8;
+// false nearest neighbours are brute force, only this many samples are used
+const FNN_SAMPLES: usize = 2000;
+
+// in place of a scaling fit when the curve has fewer than two points
+const NOT_ENOUGH_POINTS: &str = "not enough points to fit, record more samples";
+
+/// where the analysed trajectory comes from
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Source {
+    Lorenz,
+    DoublePendulum,
+    Henon,
+    Logistic,
+    /// measured data loaded from a csv
+    Imported,
+    /// a delay embedding of an earlier trajectory, re: `ui_embedding`
+    Embedded
+}
+
+impl Source {
+    fn is_map(&self) -> bool {
+        matches!(self, Source::Henon | Source::Logistic)
+    }
+}
+
+/// window with the analysis tools, all working on one recorded trajectory
+pub struct AnalysisPanel {
+    pub open: bool,
+    pub source: Source,
+    pub samples: usize,
+    // the app's seed, for the tools that draw random numbers
+    pub seed: u64,
+
+    // systems the trajectory is recorded from. lorenz and the double pendulum
+    // are kept in sync with the simulations, so the analysis follows the sliders
+    pub lorenz: Lorenz,
+    pub dp: DoublePendulum,
+    pub henon: Henon,
+    pub logistic: LogisticMap,
+
+    // csv path, columns (names or positions, comma separated), time column (none if empty)
+    // and the sample spacing when there is no time column
+    pub import_path: String,
+    pub import_columns: String,
+    pub import_time: String,
+    pub import_dt: f64,
+    imported: Option<Trajectory>,
+    import_status: String,
+    // the last reconstruction handed to the other tools, and what it was reconstructed from
+    embedded: Option<Trajectory>,
+    embedded_from: Source,
+
+    pub trajectory: Option<Trajectory>,
+    // None inside once computed if there were too few points to fit
+    correlation: Option<Option<ScalingFit>>,
+    correlation_job: Option<Worker<Option<ScalingFit>>>,
+    box_counting: Option<Option<(ScalingFit, ScalingFit)>>,
+    box_counting_job: Option<Worker<Option<(ScalingFit, ScalingFit)>>>,
+    // (source, [D0, D1, D2]) for every source, re: `ui_box_counting`
+    comparison: Vec<(Source, [Option<f64>; 3])>,
+    comparison_job: Option<Worker<(Source, [Option<f64>; 3])>>,
+    pub recurrence_threshold: Threshold,
+    // the heatmap is kept so it isn't rebuilt every frame
+    recurrence: Option<(RecurrencePlot, Rqa, Heatmap)>,
+    recurrence_job: Option<Worker<(RecurrencePlot, Rqa, Heatmap)>>,
+    recurrence_texture: Option<egui::TextureHandle>,
+
+    // state component, segment length and window for the power spectrum
+    pub spectrum_component: usize,
+    pub spectrum_segment: usize,
+    pub spectrum_window: Window,
+    spectrum: Option<Spectrum>,
+    // logistic r ramped from .0 to .1 for the spectrogram
+    pub ramp: (f64, f64),
+    // (r at the first and last segment, log10 power heatmap)
+    spectrogram: Option<((f64, f64), Heatmap)>,
+    spectrogram_texture: Option<egui::TextureHandle>,
+
+    // component, every how many samples and how many c for the 0–1 test
+    pub zero_one_component: usize,
+    pub zero_one_every: usize,
+    pub zero_one_cs: usize,
+    // c of the (p, q) walk shown
+    pub zero_one_c: f64,
+    zero_one: Option<(Vec<f64>, ZeroOne)>,
+    zero_one_job: Option<Worker<(Vec<f64>, ZeroOne)>>,
+
+    // embedded component, delay (samples) and dimension of the reconstruction
+    pub embedding_component: usize,
+    pub embedding_delay: usize,
+    pub embedding_dimension: usize,
+    // (mutual information per delay, false nearest neighbour fraction per dimension)
+    embedding_choice: Option<(Vec<f64>, Vec<f64>)>,
+
+    // kantz (averaging all neighbours within a fraction of the attractor's diameter) instead of
+    // rosenstein (nearest neighbour), samples the pairs are followed for, and the fitted region
+    // (automatic if None)
+    pub divergence_kantz: bool,
+    pub divergence_radius: f64,
+    pub divergence_steps: usize,
+    pub divergence_region: Option<(usize, usize)>,
+    // (sample spacing, divergence curve)
+    divergence: Option<(f64, Vec<f64>)>,
+
+    // longest period looked for (iterations for maps, time for lorenz), close return distance
+    pub orbit_max_period: usize,
+    pub orbit_max_time: f64,
+    pub orbit_epsilon: f64,
+    pub orbit_flow_epsilon: f64,
+    // orbits found, and the one highlighted (all drawn if None)
+    orbits: Vec<PeriodicOrbit>,
+    orbit_job: Option<Worker<Vec<PeriodicOrbit>>>,
+    selected_orbit: Option<usize>
+}
+
+impl Default for AnalysisPanel {
+    fn default() -> Self {
+        AnalysisPanel {
+            open: false,
+            source: Source::Lorenz,
+            samples: 3000,
+            seed: 0,
+            lorenz: Lorenz::default(),
+            dp: DoublePendulum::default(),
+            henon: Henon::default(),
+            logistic: LogisticMap { r: 4.0 },
+            import_path: String::new(),
+            import_columns: String::from("0"),
+            import_time: String::new(),
+            import_dt: 1.0,
+            imported: None,
+            import_status: String::new(),
+            embedded: None,
+            embedded_from: Source::Lorenz,
+            trajectory: None,
+            correlation: None,
+            correlation_job: None,
+            box_counting: None,
+            box_counting_job: None,
+            comparison: vec![],
+            comparison_job: None,
+            recurrence_threshold: Threshold::RecurrenceRate(0.05),
+            recurrence: None,
+            recurrence_job: None,
+            recurrence_texture: None,
+            spectrum_component: 0,
+            spectrum_segment: 256,
+            spectrum_window: Window::Hann,
+            spectrum: None,
+            ramp: (2.8, 3.6),
+            spectrogram: None,
+            spectrogram_texture: None,
+            zero_one_component: 0,
+            zero_one_every: 10,
+            zero_one_cs: 100,
+            zero_one_c: 1.7,
+            zero_one: None,
+            zero_one_job: None,
+            embedding_component: 0,
+            embedding_delay: 3,
+            embedding_dimension: 3,
+            embedding_choice: None,
+            divergence_kantz: false,
+            divergence_radius: 0.01,
+            divergence_steps: 60,
+            divergence_region: None,
+            divergence: None,
+            orbit_max_period: 6,
+            orbit_max_time: 4.0,
+            orbit_epsilon: 0.02,
+            orbit_flow_epsilon: 2.0,
+            orbits: vec![],
+            orbit_job: None,
+            selected_orbit: None
+        }
+    }
+}
+
+impl AnalysisPanel {
+    pub fn show(&mut self, ctx: &egui::Context) {
+        let mut open = self.open;
+        egui::Window::new("analysis")
+        .open(&mut open)
+        .default_width(500.0)
+        .vscroll(true)
+        .show(ctx, |ui| {
+            self.ui_source(ui);
+            ui.separator();
+            ui.collapsing("correlation dimension", |ui| self.ui_correlation_dimension(ui));
+            ui.collapsing("box counting / information dimension", |ui| self.ui_box_counting(ui));
+            ui.collapsing("recurrence plot", |ui| self.ui_recurrence(ui));
+            ui.collapsing("power spectrum", |ui| self.ui_spectrum(ui));
+            ui.collapsing("spectrogram of a parameter ramp", |ui| self.ui_spectrogram(ui));
+            ui.collapsing("0–1 test for chaos", |ui| self.ui_zero_one(ui));
+            ui.collapsing("delay embedding", |ui| self.ui_embedding(ui));
+            ui.collapsing("lyapunov exponent from data", |ui| self.ui_divergence(ui));
+            ui.collapsing("periodic orbits", |ui| self.ui_periodic_orbits(ui));
+        });
+        self.open = open;
+    }
+
+    /// records `samples` points after a transient, flows sampled every 0.05 time units
+    fn record(&self) -> Trajectory {
+        self.record_from(self.source)
+    }
+
+    fn record_from(&self, source: Source) -> Trajectory {
+        match source {
+            Source::Lorenz => Trajectory::integrate(&self.lorenz, &[1.0, 1.0, 1.0], 0.01, 5, self.samples, 200),
+            Source::DoublePendulum => Trajectory::integrate(&self.dp, &[1.0, 1.0, 1.0, 1.0], 0.01, 5, self.samples, 0),
+            Source::Henon => Trajectory::iterate(&self.henon, &[0.1, 0.1], self.samples, 100),
+            Source::Logistic => Trajectory::iterate(&self.logistic, &[0.3], self.samples, 100),
+            // all of it, `samples` is for the simulated sources
+            Source::Imported => self.imported.clone().unwrap_or_default(),
+            Source::Embedded => self.embedded.clone().unwrap_or_default()
+        }
+    }
+
+    // samples pairs closer together in time than this are ignored, re: `correlation_sum`.
+    // An embedding is as correlated in time as what it was embedded from
+    fn theiler_window(&self) -> usize {
+        match self.source {
+            Source::Embedded => theiler_window(self.embedded_from),
+            source => theiler_window(source)
+        }
+    }
+
+    fn ui_source(&mut self, ui: &mut egui::Ui) {
+        let before = (self.source, self.samples);
+        ui.horizontal(|ui| {
+            egui::ComboBox::from_label("source")
+            .selected_text(format!("{:?}", self.source))
+            .show_ui(ui, |ui| {
+                ui.selectable_value(&mut self.source, Source::Lorenz, "Lorenz");
+                ui.selectable_value(&mut self.source, Source::DoublePendulum, "DoublePendulum");
+                ui.selectable_value(&mut self.source, Source::Henon, "Henon");
+                ui.selectable_value(&mut self.source, Source::Logistic, "Logistic");
+                ui.selectable_value(&mut self.source, Source::Imported, "Imported CSV");
+            });
+            ui.label("samples");
+            ui.add(egui::Slider::new(&mut self.samples, 100..=20000).logarithmic(true));
+        });
+
+        match self.source {
+            Source::Henon => {
+                ui.horizontal(|ui| {
+                    ui.label("a");
+                    ui.add(egui::Slider::new(&mut self.henon.a, 0.0..=1.5));
+                    ui.label("b");
+                    ui.add(egui::Slider::new(&mut self.henon.b, -0.5..=0.5));
+                });
+            }
+            Source::Logistic => {
+                ui.horizontal(|ui| {
+                    ui.label("r");
+                    ui.add(egui::Slider::new(&mut self.logistic.r, 2.5..=4.0));
+                });
+            }
+            Source::Imported => self.ui_import(ui),
+            // parameters come from the simulation tabs, or from the embedding below
+            Source::Lorenz | Source::DoublePendulum | Source::Embedded => {}
+        }
+
+        if before != (self.source, self.samples) {
+            self.trajectory = None;
+        }
+
+        ui.horizontal(|ui| {
+            if ui.button("Record").clicked() {
+                // nothing imported yet records nothing
+                self.trajectory = Some(self.record()).filter(|t| !t.is_empty());
+            }
+            match &self.trajectory {
+                Some(t) => ui.label(format!("{} samples of {} dimensions", t.len(), t.dimension())),
+                None => ui.label("nothing recorded yet")
+            };
+        });
+
+        if let Some(trajectory) = &self.trajectory {
+            // first two coordinates, enough to recognise the attractor
+            let pts: PlotPoints<'_> = trajectory.states.iter().map(|s| [s[0], s[s.len().min(2) - 1]]).collect();
+            Plot::new("analysis trajectory")
+            .view_aspect(2.0)
+            .show(ui, |plot_ui| {
+                plot_ui.points(Points::new("trajectory", pts).radius(1.0));
+            });
+        }
+    }
+
+    fn ui_import(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label("csv");
+            ui.text_edit_singleline(&mut self.import_path);
+            if ui.button("Columns").on_hover_text("list the columns of the file").clicked() {
+                self.import_status = match read_csv_headers(&self.import_path) {
+                    Ok(headers) => format!("columns: {}", headers.join(", ")),
+                    Err(e) => format!("couldn't read {}: {}", self.import_path, e)
+                };
+            }
+        });
+        ui.horizontal(|ui| {
+            ui.label("columns").on_hover_text("header names or 0 based positions, separated by commas");
+            ui.text_edit_singleline(&mut self.import_columns);
+            ui.label("time column").on_hover_text("leave empty to space the samples by dt");
+            ui.text_edit_singleline(&mut self.import_time);
+            ui.label("dt");
+            ui.add(egui::DragValue::new(&mut self.import_dt).speed(0.01).range(1e-9..=f64::INFINITY));
+        });
+        ui.horizontal(|ui| {
+            if ui.button("Load").clicked() {
+                let columns: Vec<&str> = self.import_columns.split(',').map(str::trim).collect();
+                let time = Some(self.import_time.trim()).filter(|t| !t.is_empty());
+                match read_csv_trajectory_from_path(&self.import_path, &columns, time, self.import_dt) {
+                    Ok(trajectory) => {
+                        self.import_status = format!("loaded {} samples of {} columns", trajectory.len(), trajectory.dimension());
+                        self.trajectory = Some(trajectory.clone());
+                        self.imported = Some(trajectory);
+                    }
+                    Err(e) => self.import_status = format!("couldn't load {}: {}", self.import_path, e)
+                }
+            }
+            ui.label(&self.import_status);
+        });
+    }
+
+    fn ui_correlation_dimension(&mut self, ui: &mut egui::Ui) {
+        let Some(trajectory) = &self.trajectory else {
+            ui.label("record a trajectory first");
+            return;
+        };
+
+        if ui.button("Compute").clicked() {
+            // all n² pairs, too slow for the ui thread
+            let (points, theiler) = (trajectory.states.clone(), self.theiler_window());
+            self.correlation_job = Some(Worker::spawn(ui.ctx(), 0, move |_, send| send(correlation_dimension(&points, theiler))));
+        }
+
+        if let Some(job) = &self.correlation_job {
+            let finished = job.is_finished();
+            if let Some(fit) = job.poll().pop() {
+                self.correlation = Some(fit);
+            }
+            if finished || job.ui(ui) {
+                self.correlation_job = None;
+            }
+        }
+
+        match &self.correlation {
+            Some(Some(fit)) => {
+                ui.label(format!("D2 ≈ {:.3}", fit.slope));
+                ui_scaling_fit(ui, "correlation sum", fit, "log r", "log C(r)");
+            }
+            Some(None) => {
+                ui.label(NOT_ENOUGH_POINTS);
+            }
+            None => {}
+        }
+    }
+
+    fn ui_box_counting(&mut self, ui: &mut egui::Ui) {
+        if let Some(trajectory) = &self.trajectory {
+            if ui.button("Compute").clicked() {
+                let points = trajectory.states.clone();
+                self.box_counting_job = Some(Worker::spawn(ui.ctx(), 0, move |_, send| {
+                    send(box_counting_dimension(&points).zip(information_dimension(&points)));
+                }));
+            }
+        } else {
+            ui.label("record a trajectory first");
+        }
+
+        if let Some(job) = &self.box_counting_job {
+            let finished = job.is_finished();
+            if let Some(fits) = job.poll().pop() {
+                self.box_counting = Some(fits);
+            }
+            if finished || job.ui(ui) {
+                self.box_counting_job = None;
+            }
+        }
+
+        match &self.box_counting {
+            Some(Some((d0, d1))) => {
+                ui.label(format!("D0 ≈ {:.3}, D1 ≈ {:.3}", d0.slope, d1.slope));
+                ui_scaling_fit(ui, "box counting", d0, "log 1/ε", "log N(ε)");
+                ui_scaling_fit(ui, "information", d1, "log 1/ε", "I(ε)");
+            }
+            Some(None) => {
+                ui.label(NOT_ENOUGH_POINTS);
+            }
+            None => {}
+        }
+
+        ui.separator();
+        // same number of samples from each attractor, side by side
+        if ui.button("Compare Lorenz / Logistic / Henon").clicked() {
+            // recording is quick, the three correlation sums aren't. Each row shows up when it's done
+            let recorded: Vec<(Source, Vec<Vec<f64>>)> = [Source::Lorenz, Source::Logistic, Source::Henon].iter()
+            .map(|source| (*source, self.record_from(*source).states))
+            .collect();
+            self.comparison.clear();
+            self.comparison_job = Some(Worker::spawn(ui.ctx(), recorded.len(), move |progress, send| {
+                for (source, points) in &recorded {
+                    if progress.is_cancelled() {
+                        return;
+                    }
+                    send((*source, [
+                        box_counting_dimension(points).map(|fit| fit.slope),
+                        information_dimension(points).map(|fit| fit.slope),
+                        correlation_dimension(points, theiler_window(*source)).map(|fit| fit.slope)
+                    ]));
+                    progress.tick();
+                }
+            }));
+        }
+
+        if let Some(job) = &self.comparison_job {
+            let finished = job.is_finished();
+            self.comparison.extend(job.poll());
+            if finished || job.ui(ui) {
+                self.comparison_job = None;
+            }
+        }
+
+        if !self.comparison.is_empty() {
+            egui::Grid::new("dimension comparison").striped(true).show(ui, |ui| {
+                ui.label("source");
+                ui.label("D0");
+                ui.label("D1");
+                ui.label("D2");
+                ui.end_row();
+                for (source, dimensions) in &self.comparison {
+                    ui.label(format!("{:?}", source));
+                    for d in dimensions {
+                        // too few samples to fit
+                        ui.label(d.map_or(String::from("-"), |d| format!("{:.3}", d)));
+                    }
+                    ui.end_row();
+                }
+            });
+        }
+    }
+
+    fn ui_recurrence(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            let mut by_rate = matches!(self.recurrence_threshold, Threshold::RecurrenceRate(_));
+            ui.radio_value(&mut by_rate, true, "fixed recurrence rate");
+            ui.radio_value(&mut by_rate, false, "fixed ε");
+            self.recurrence_threshold = match (by_rate, self.recurrence_threshold) {
+                (true, Threshold::Distance(_)) => Threshold::RecurrenceRate(0.05),
+                (false, Threshold::RecurrenceRate(_)) => Threshold::Distance(1.0),
+                (_, threshold) => threshold
+            };
+            match &mut self.recurrence_threshold {
+                Threshold::RecurrenceRate(rate) => ui.add(egui::Slider::new(rate, 0.001..=0.5).logarithmic(true)),
+                Threshold::Distance(epsilon) => ui.add(egui::Slider::new(epsilon, 0.001..=20.0).logarithmic(true))
+            };
+        });
+
+        let Some(trajectory) = &self.trajectory else {
+            ui.label("record a trajectory first");
+            return;
+        };
+
+        if ui.button("Compute").clicked() {
+            // n² distances, too slow for the ui thread
+            let points = trajectory.states[..trajectory.len().min(RECURRENCE_SAMPLES)].to_vec();
+            let threshold = self.recurrence_threshold;
+            self.recurrence_job = Some(Worker::spawn(ui.ctx(), 0, move |progress, send| {
+                let plot = RecurrencePlot::new(&points, threshold);
+                if progress.is_cancelled() {
+                    return;
+                }
+                let rqa = plot.rqa(2, 2, 1);
+                let heatmap = plot.to_heatmap();
+                send((plot, rqa, heatmap));
+            }));
+        }
+
+        if let Some(job) = &self.recurrence_job {
+            let finished = job.is_finished();
+            if let Some(recurrence) = job.poll().pop() {
+                self.recurrence = Some(recurrence);
+                self.recurrence_texture = None;
+            }
+            if finished || job.ui(ui) {
+                self.recurrence_job = None;
+            }
+        }
+
+        if let Some((plot, rqa, heatmap)) = &self.recurrence {
+            ui.label(format!("ε = {:.4} over the first {} samples", plot.epsilon, plot.n));
+            ui.label(rqa.to_string());
+            let n = plot.n as f64;
+            show_image_plot(ui, &mut self.recurrence_texture, "recurrence plot", heatmap, ("i", 0.0, n), ("j", 0.0, n));
+        }
+    }
+
+    fn ui_spectrum(&mut self, ui: &mut egui::Ui) {
+        let Some(trajectory) = &self.trajectory else {
+            ui.label("record a trajectory first");
+            return;
+        };
+
+        ui.horizontal(|ui| {
+            ui.label("component");
+            ui.add(egui::Slider::new(&mut self.spectrum_component, 0..=(trajectory.dimension() - 1)));
+            egui::ComboBox::from_label("segment")
+            .selected_text(self.spectrum_segment.to_string())
+            .show_ui(ui, |ui| {
+                for segment in SEGMENTS {
+                    ui.selectable_value(&mut self.spectrum_segment, segment, segment.to_string());
+                }
+            });
+            egui::ComboBox::from_label("window")
+            .selected_text(format!("{:?}", self.spectrum_window))
+            .show_ui(ui, |ui| {
+                for window in [Window::Rectangular, Window::Hann, Window::Hamming, Window::Blackman] {
+                    ui.selectable_value(&mut self.spectrum_window, window, format!("{:?}", window));
+                }
+            });
+        });
+        // the slider range follows the trajectory, but a new recording may have fewer dimensions
+        let component = self.spectrum_component.min(trajectory.dimension() - 1);
+
+        if ui.button("Compute").clicked() {
+            let signal = trajectory.component(component);
+            let dt = if trajectory.len() > 1 { trajectory.times[1] - trajectory.times[0] } else { 1.0 };
+            self.spectrum = Some(welch(&signal, 1.0 / dt, self.spectrum_segment, self.spectrum_window));
+        }
+
+        let Some(spectrum) = &self.spectrum else { return };
+        ui.columns(2, |columns| {
+            let series: PlotPoints<'_> = trajectory.times.iter().zip(trajectory.component(component)).map(|(t, x)| [*t, x]).collect();
+            Plot::new("spectrum time series")
+            .view_aspect(1.5)
+            .x_axis_label("t")
+            .show(&mut columns[0], |plot_ui| plot_ui.line(Line::new("x", series)));
+
+            // zero power (exact periodicity in a map) would be -inf on the log scale
+            let psd: PlotPoints<'_> = spectrum.frequencies.iter().zip(&spectrum.power)
+            .filter(|(_, p)| **p > 0.0)
+            .map(|(f, p)| [*f, p.log10()])
+            .collect();
+            Plot::new("power spectrum")
+            .view_aspect(1.5)
+            .x_axis_label("frequency")
+            .y_axis_label("log10 power")
+            .show(&mut columns[1], |plot_ui| plot_ui.line(Line::new("psd", psd)));
+        });
+    }
+
+    /// logistic map with r creeping up through the period doubling cascade: every doubling
+    /// adds a new line at half the lowest frequency, and chaos fills in the whole band
+    fn ui_spectrogram(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label("r from");
+            ui.add(egui::Slider::new(&mut self.ramp.0, 2.5..=4.0));
+            ui.label("to");
+            ui.add(egui::Slider::new(&mut self.ramp.1, 2.5..=4.0));
+            if ui.button("Compute").clicked() {
+                let (rs, signal) = ramp_map(&self.logistic, "r", self.ramp, RAMP_ITERATIONS, &[0.3]);
+                let spectrogram = spectrogram(&signal, 1.0, RAMP_SEGMENT, RAMP_HOP, Window::Hann);
+
+                // frequency up the image, time (and r) along it
+                let rows: Vec<Vec<f64>> = (0..spectrogram.frequencies.len()).rev()
+                .map(|f| spectrogram.power.iter().map(|p| if p[f] > 0.0 { p[f].log10() } else { f64::NAN }).collect())
+                .collect();
+                let r_range = (rs[spectrogram.centres[0]], rs[spectrogram.centres[spectrogram.centres.len() - 1]]);
+                // the floor of numerically zero power would stretch the colours over nothing
+                self.spectrogram = Some((r_range, Heatmap::from_rows(&rows, Some((-8.0, 1.0)))));
+                self.spectrogram_texture = None;
+            }
+        });
+
+        if let Some(((r_lo, r_hi), heatmap)) = &self.spectrogram {
+            show_image_plot(ui, &mut self.spectrogram_texture, "spectrogram", heatmap, ("r", *r_lo, *r_hi), ("frequency", 0.0, 0.5));
+        }
+    }
+
+    fn ui_zero_one(&mut self, ui: &mut egui::Ui) {
+        let Some(trajectory) = &self.trajectory else {
+            ui.label("record a trajectory first");
+            return;
+        };
+
+        ui.horizontal(|ui| {
+            ui.label("component");
+            ui.add(egui::Slider::new(&mut self.zero_one_component, 0..=(trajectory.dimension() - 1)));
+            // flows are recorded every 0.05, far finer than the test wants
+            ui.label("every");
+            ui.add(egui::Slider::new(&mut self.zero_one_every, 1..=50));
+            ui.label("values of c");
+            ui.add(egui::Slider::new(&mut self.zero_one_cs, 1..=200));
+        });
+        let component = self.zero_one_component.min(trajectory.dimension() - 1);
+
+        if ui.button("Compute").clicked() {
+            let signal: Vec<f64> = trajectory.component(component).into_iter().step_by(self.zero_one_every.max(1)).collect();
+            // a few hundred points are needed for the n ≤ N/10 fit to mean anything
+            if signal.len() < 100 {
+                self.zero_one = None;
+            } else {
+                // a fit per c over the whole series, too slow for the ui thread
+                let (count, seed) = (self.zero_one_cs, self.seed);
+                self.zero_one_job = Some(Worker::spawn(ui.ctx(), 0, move |progress, send| {
+                    if let Some(result) = zero_one_test(&signal, count, seed, progress) {
+                        send((signal, result));
+                    }
+                }));
+            }
+        }
+
+        if let Some(job) = &self.zero_one_job {
+            let finished = job.is_finished();
+            if let Some(zero_one) = job.poll().pop() {
+                self.zero_one = Some(zero_one);
+            }
+            if finished || job.ui(ui) {
+                self.zero_one_job = None;
+            }
+        }
+
+        let Some((signal, result)) = &self.zero_one else {
+            ui.label("compute the test, it needs at least 100 samples after thinning");
+            return;
+        };
+        ui.label(format!("{}, near 0 is regular and near 1 chaotic", result));
+
+        ui.horizontal(|ui| {
+            ui.label("c");
+            ui.add(egui::Slider::new(&mut self.zero_one_c, (std::f64::consts::PI / 5.0)..=(4.0 * std::f64::consts::PI / 5.0)));
+        });
+        let (p, q) = translation(signal, self.zero_one_c);
+        ui.columns(2, |columns| {
+            let walk: PlotPoints<'_> = p.iter().zip(&q).map(|(p, q)| [*p, *q]).collect();
+            Plot::new("zero one translation")
+            .data_aspect(1.0)
+            .view_aspect(1.0)
+            .x_axis_label("p")
+            .y_axis_label("q")
+            .show(&mut columns[0], |plot_ui| plot_ui.line(Line::new("(p, q)", walk)));
+
+            let ks: PlotPoints<'_> = result.ks.iter().map(|(c, k)| [*c, *k]).collect();
+            Plot::new("zero one k")
+            .view_aspect(1.0)
+            .include_y(0.0)
+            .include_y(1.0)
+            .x_axis_label("c")
+            .y_axis_label("K_c")
+            .show(&mut columns[1], |plot_ui| plot_ui.points(Points::new("K_c", ks).radius(2.0)));
+        });
+    }
+
+    /// reconstructs the attractor from one component: mutual information picks the delay, false
+    /// nearest neighbours the dimension, and the embedded points can replace the trajectory so
+    /// every other tool runs on the reconstruction
+    fn ui_embedding(&mut self, ui: &mut egui::Ui) {
+        if ui.button("Lorenz from x").on_hover_text("record lorenz and reconstruct it from x alone").clicked() {
+            self.source = Source::Lorenz;
+            self.trajectory = Some(self.record());
+            self.embedding_component = 0;
+            self.embedding_choice = None;
+        }
+
+        let Some(trajectory) = &self.trajectory else {
+            ui.label("record a trajectory first");
+            return;
+        };
+
+        ui.horizontal(|ui| {
+            ui.label("component");
+            ui.add(egui::Slider::new(&mut self.embedding_component, 0..=(trajectory.dimension() - 1)));
+            if ui.button("Choose delay and dimension").clicked() {
+                let signal = trajectory.component(self.embedding_component.min(trajectory.dimension() - 1));
+                let ami = average_mutual_information(&signal, MAX_DELAY, 32);
+                // maps are embedded with delay 1, re: `first_minimum`
+                self.embedding_delay = if self.source.is_map() { 1 } else { first_minimum(&ami).unwrap_or(1) };
+                let fnn = false_nearest_neighbours(&signal[..signal.len().min(FNN_SAMPLES)], self.embedding_delay, MAX_EMBEDDING_DIMENSION, theiler_window(self.source));
+                self.embedding_dimension = embedding_dimension(&fnn, 0.01).unwrap_or(MAX_EMBEDDING_DIMENSION);
+                self.embedding_choice = Some((ami, fnn));
+            }
+        });
+        ui.horizontal(|ui| {
+            ui.label("delay");
+            ui.add(egui::Slider::new(&mut self.embedding_delay, 1..=MAX_DELAY));
+            ui.label("dimension");
+            ui.add(egui::Slider::new(&mut self.embedding_dimension, 2..=MAX_EMBEDDING_DIMENSION));
+        });
+
+        if let Some((ami, fnn)) = &self.embedding_choice {
+            ui.columns(2, |columns| {
+                let ami: PlotPoints<'_> = ami.iter().enumerate().map(|(k, i)| [k as f64, *i]).collect();
+                Plot::new("mutual information")
+                .view_aspect(1.5)
+                .x_axis_label("delay (samples)")
+                .y_axis_label("I(τ)")
+                .show(&mut columns[0], |plot_ui| plot_ui.line(Line::new("mutual information", ami)));
+
+                let fnn: PlotPoints<'_> = fnn.iter().enumerate().map(|(k, f)| [(k + 1) as f64, *f]).collect();
+                Plot::new("false nearest neighbours")
+                .view_aspect(1.5)
+                .include_y(0.0)
+                .x_axis_label("dimension")
+                .y_axis_label("false neighbours")
+                .show(&mut columns[1], |plot_ui| plot_ui.line(Line::new("fraction", fnn)));
+            });
+        }
+
+        let component = self.embedding_component.min(trajectory.dimension() - 1);
+        let points = delay_embed(&trajectory.component(component), self.embedding_dimension, self.embedding_delay);
+        let delay = self.embedding_delay;
+        ui.columns(2, |columns| {
+            // the original, in the same projection the trajectory preview uses
+            let original: PlotPoints<'_> = trajectory.states.iter().map(|s| [s[0], s[s.len().min(2) - 1]]).collect();
+            Plot::new("embedding original")
+            .view_aspect(1.0)
+            .show(&mut columns[0], |plot_ui| plot_ui.points(Points::new("original", original).radius(1.0)));
+
+            let reconstructed: PlotPoints<'_> = points.iter().map(|p| [p[0], p[1]]).collect();
+            Plot::new("embedding reconstruction")
+            .view_aspect(1.0)
+            .x_axis_label("s(t)")
+            .y_axis_label(format!("s(t + {}Δt)", delay))
+            .show(&mut columns[1], |plot_ui| plot_ui.points(Points::new("reconstruction", reconstructed).radius(1.0)));
+        });
+
+        // too short a recording for this delay and dimension embeds nothing
+        let analyse = ui.add_enabled(!points.is_empty(), egui::Button::new("Analyse the reconstruction"))
+        .on_hover_text("replace the trajectory with the embedded points")
+        .on_disabled_hover_text("the trajectory is shorter than the embedding window");
+        if analyse.clicked() {
+            let times = trajectory.times[..points.len()].to_vec();
+            let embedded = Trajectory { times, states: points };
+            if self.source != Source::Embedded {
+                self.embedded_from = self.source;
+            }
+            self.source = Source::Embedded;
+            self.embedded = Some(embedded.clone());
+            self.trajectory = Some(embedded);
+        }
+    }
+
+    /// largest exponent from the recorded samples alone, re: `rosenstein`. Works on whatever the
+    /// trajectory holds, so a delay embedding (or imported data) gets the same treatment
+    fn ui_divergence(&mut self, ui: &mut egui::Ui) {
+        let Some(trajectory) = &self.trajectory else {
+            ui.label("record a trajectory first");
+            return;
+        };
+
+        ui.horizontal(|ui| {
+            ui.radio_value(&mut self.divergence_kantz, false, "Rosenstein");
+            ui.radio_value(&mut self.divergence_kantz, true, "Kantz");
+            if self.divergence_kantz {
+                ui.label("radius").on_hover_text("fraction of the attractor's diameter");
+                ui.add(egui::Slider::new(&mut self.divergence_radius, 0.001..=0.1).logarithmic(true));
+            }
+            ui.label("steps");
+            ui.add(egui::Slider::new(&mut self.divergence_steps, 5..=500).logarithmic(true));
+        });
+
+        if ui.button("Compute").clicked() {
+            let theiler = self.theiler_window();
+            let curve = if self.divergence_kantz {
+                kantz(&trajectory.states, theiler, self.divergence_radius * diameter(&trajectory.states), self.divergence_steps)
+            } else {
+                rosenstein(&trajectory.states, theiler, self.divergence_steps)
+            };
+            let dt = if trajectory.len() > 1 { trajectory.times[1] - trajectory.times[0] } else { 1.0 };
+            self.divergence = Some((dt, curve));
+        }
+
+        let Some((dt, curve)) = &self.divergence else { return };
+        // the automatic region, until one is picked by hand
+        let mut manual = self.divergence_region.is_some();
+        let Some(fit) = divergence_fit(curve, *dt, self.divergence_region) else {
+            ui.label(NOT_ENOUGH_POINTS);
+            return;
+        };
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut manual, "pick the linear region");
+            if manual {
+                let (mut start, mut end) = self.divergence_region.unwrap_or((fit.start, fit.end));
+                ui.add(egui::Slider::new(&mut start, 0..=(curve.len() - 2)).text("from"));
+                ui.add(egui::Slider::new(&mut end, (start + 2)..=curve.len()).text("to"));
+                self.divergence_region = Some((start, end.max(start + 2)));
+            } else {
+                self.divergence_region = None;
+            }
+        });
+
+        ui.label(format!("λ1 ≈ {:.4} per unit time", fit.slope));
+        ui_scaling_fit(ui, "divergence", &fit, "t", "⟨ln d⟩");
+    }
+
+    /// unstable periodic orbits of the system the trajectory came from, seeded by the
+    /// trajectory's close returns and drawn over it
+    fn ui_periodic_orbits(&mut self, ui: &mut egui::Ui) {
+        let Some(trajectory) = &self.trajectory else {
+            ui.label("record a trajectory first");
+            return;
+        };
+        let dimension = match self.source {
+            Source::Lorenz => DynamicalSystem::dimension(&self.lorenz),
+            Source::Henon => Map::dimension(&self.henon),
+            Source::Logistic => Map::dimension(&self.logistic),
+            Source::DoublePendulum | Source::Imported | Source::Embedded => {
+                ui.label("needs the equations, pick lorenz, henon or logistic");
+                return;
+            }
+        };
+        // the close returns seed newton in the system's own coordinates
+        if trajectory.dimension() != dimension {
+            ui.label(format!("the trajectory has {} dimensions but the system {}, record it again", trajectory.dimension(), dimension));
+            return;
+        }
+
+        ui.horizontal(|ui| {
+            if self.source == Source::Lorenz {
+                ui.label("max period");
+                ui.add(egui::Slider::new(&mut self.orbit_max_time, 1.0..=8.0));
+                ui.label("ε").on_hover_text("close return distance");
+                ui.add(egui::Slider::new(&mut self.orbit_flow_epsilon, 0.1..=5.0).logarithmic(true));
+            } else {
+                ui.label("max period");
+                ui.add(egui::Slider::new(&mut self.orbit_max_period, 1..=12));
+                ui.label("ε").on_hover_text("close return distance");
+                ui.add(egui::Slider::new(&mut self.orbit_epsilon, 0.001..=0.1).logarithmic(true));
+            }
+            if ui.button("Find").clicked() {
+                let (source, states, henon, logistic, lorenz) = (self.source, trajectory.states.clone(), self.henon, self.logistic, self.lorenz);
+                let (max_period, epsilon, max_time, flow_epsilon) = (self.orbit_max_period, self.orbit_epsilon, self.orbit_max_time, self.orbit_flow_epsilon);
+                self.orbit_job = Some(Worker::spawn(ui.ctx(), 0, move |_, send| send(match source {
+                    Source::Henon => find_map_orbits(&henon, &states, max_period, epsilon),
+                    Source::Logistic => find_map_orbits(&logistic, &states, max_period, epsilon),
+                    // recorded every 0.05, shorter returns are the trajectory just moving on.
+                    // Orbits are shot on the section through lorenz's two equilibria
+                    _ => {
+                        let section = Section { component: 2, value: lorenz.ro - 1.0 };
+                        find_flow_orbits(&lorenz, &states, 10..=((max_time / 0.05) as usize), flow_epsilon, section, 0.01)
+                    }
+                })));
+            }
+        });
+
+        if let Some(job) = &self.orbit_job {
+            let finished = job.is_finished();
+            if let Some(orbits) = job.poll().pop() {
+                self.orbits = orbits;
+                self.selected_orbit = None;
+            }
+            if finished || job.ui(ui) {
+                self.orbit_job = None;
+            }
+        }
+
+        if self.orbits.is_empty() {
+            ui.label("no orbits yet, try a longer trajectory or a bigger ε");
+            return;
+        }
+
+        egui::Grid::new("periodic orbits").striped(true).show(ui, |ui| {
+            ui.label("");
+            ui.label("n");
+            ui.label("period");
+            ui.label("multipliers");
+            ui.label("");
+            ui.end_row();
+            for (k, orbit) in self.orbits.iter().enumerate() {
+                let selected = self.selected_orbit == Some(k);
+                if ui.selectable_label(selected, "show").clicked() {
+                    self.selected_orbit = if selected { None } else { Some(k) };
+                }
+                ui.label(orbit.n.to_string());
+                ui.label(format!("{:.5}", orbit.period));
+                ui.label(orbit.format_multipliers());
+                ui.label(if orbit.is_stable() { "stable" } else { "unstable" });
+                ui.end_row();
+            }
+        });
+
+        // maps show their points, 1d ones as (x_n, x_n+1). Lorenz in the (x, z) plane
+        let one_dimensional = trajectory.dimension() == 1;
+        let project = |p: &[f64]| [p[0], p[p.len().min(3) - 1]];
+        let attractor: PlotPoints<'_> = if one_dimensional {
+            trajectory.states.windows(2).map(|w| [w[0][0], w[1][0]]).collect()
+        } else {
+            trajectory.states.iter().map(|p| project(p)).collect()
+        };
+        let shown: Vec<(usize, &PeriodicOrbit)> = self.orbits.iter().enumerate()
+        .filter(|(k, _)| self.selected_orbit.is_none_or(|s| s == *k))
+        .collect();
+        let is_map = self.source.is_map();
+
+        Plot::new("periodic orbits plot")
+        .view_aspect(1.5)
+        .show(ui, |plot_ui| {
+            plot_ui.points(Points::new("attractor", attractor).radius(1.0).color(egui::Color32::GRAY));
+            for (k, orbit) in shown {
+                let name = format!("orbit {} (n = {})", k + 1, orbit.n);
+                if is_map {
+                    let points: PlotPoints<'_> = if one_dimensional {
+                        (0..orbit.n).map(|i| [orbit.points[i][0], orbit.points[(i + 1) % orbit.n][0]]).collect()
+                    } else {
+                        orbit.points.iter().map(|p| project(p)).collect()
+                    };
+                    plot_ui.points(Points::new(name, points).radius(4.0));
+                } else {
+                    let curve: PlotPoints<'_> = orbit.points.iter().map(|p| project(p)).collect();
+                    plot_ui.line(Line::new(name, curve).width(2.0));
+                }
+            }
+        });
+    }
+}
+
+fn theiler_window(source: Source) -> usize {
+    if source.is_map() { 0 } else { 10 }
+}
+
+/// log-log curve with the fitted scaling region highlighted and the fitted line drawn over it
+pub fn ui_scaling_fit(ui: &mut egui::Ui, name: &str, fit: &ScalingFit, x_label: &str, y_label: &str) {
+    let curve: PlotPoints<'_> = fit.log_x.iter().zip(&fit.log_y).map(|(x, y)| [*x, *y]).collect();
+    let fitted: PlotPoints<'_> = fit.log_x[fit.start..fit.end].iter().zip(&fit.log_y[fit.start..fit.end]).map(|(x, y)| [*x, *y]).collect();
+    let line: PlotPoints<'_> = [fit.log_x[fit.start], fit.log_x[fit.end - 1]].iter().map(|x| [*x, fit.intercept + (fit.slope * x)]).collect();
+
+    Plot::new(name)
+    .view_aspect(2.0)
+    .x_axis_label(x_label)
+    .y_axis_label(y_label)
+    .legend(egui_plot::Legend::default())
+    .show(ui, |plot_ui| {
+        plot_ui.points(Points::new(name, curve).radius(2.0));
+        plot_ui.points(Points::new("scaling region", fitted).radius(3.5));
+        plot_ui.line(Line::new(format!("slope {:.3}", fit.slope), line));
+    });
+}
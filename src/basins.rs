@@ -0,0 +1,66 @@
+use std::f64::consts::PI;
+
+use crate::{double_pendulum::DoublePendulum, duffing::Duffing, integrators::rk4_step, runner::{run_grid, Progress}};
+
+/// which well the duffing oscillator started at `(x0, v0)` ends up in: the sign of x averaged
+/// over one forcing period after `periods` periods have passed. Only meaningful when the
+/// forcing is weak enough for each well to keep its own attractor
+pub fn duffing_well(duffing: &Duffing, x0: f64, v0: f64, periods: usize) -> f64 {
+    // 100 steps per period is plenty for an oscillator this smooth
+    let steps = 100;
+    let dt = duffing.period() / steps as f64;
+    let mut state = [x0, v0];
+    let mut t = 0.0;
+
+    for _ in 0..(periods * steps) {
+        rk4_step(duffing, &mut state, t, dt);
+        t += dt;
+    }
+
+    let mut sum = 0.0;
+    for _ in 0..steps {
+        rk4_step(duffing, &mut state, t, dt);
+        t += dt;
+        sum += state[0];
+    }
+    sum.signum()
+}
+
+/// basins of the duffing oscillator's two wells over initial positions `xs` and velocities `vs`,
+/// re: [`duffing_well`]. -1 is the left well, 1 the right one
+pub fn duffing_basins(duffing: &Duffing, xs: &[f64], vs: &[f64], periods: usize, progress: &Progress) -> Option<Vec<Vec<f64>>> {
+    run_grid(xs, vs, progress, |x, v| duffing_well(duffing, x, v, periods))
+}
+
+/// time until either arm of the double pendulum, released at rest from `(theta1, theta2)`, flips
+/// over the top (an angle passes ±π). NaN when nothing flips within `t_max`.
+///
+/// starts that don't have the energy to lift either arm over the top can never flip and are
+/// skipped without integrating, which is most of the middle of the picture
+pub fn flip_time(dp: &DoublePendulum, theta1: f64, theta2: f64, t_max: f64, dt: f64) -> f64 {
+    let g = 9.81;
+    let potential = |theta1: f64, theta2: f64| {
+        -((dp.m1 + dp.m2) * g * dp.l1 * theta1.cos()) - (dp.m2 * g * dp.l2 * theta2.cos())
+    };
+    // cheapest flips: the upper arm over the top with the lower one hanging, or the other way round
+    let barrier = potential(PI, 0.0).min(potential(0.0, PI));
+    if potential(theta1, theta2) < barrier {
+        return f64::NAN;
+    }
+
+    let mut state = [theta1, theta2, 0.0, 0.0];
+    let mut t = 0.0;
+    while t < t_max {
+        rk4_step(dp, &mut state, t, dt);
+        t += dt;
+        if state[0].abs() > PI || state[1].abs() > PI {
+            return t;
+        }
+    }
+    f64::NAN
+}
+
+/// flip times over a grid of starting angles, re: [`flip_time`]
+pub fn flip_time_map(dp: &DoublePendulum, thetas1: &[f64], thetas2: &[f64], t_max: f64, progress: &Progress) -> Option<Vec<Vec<f64>>> {
+    run_grid(thetas1, thetas2, progress, |theta1, theta2| flip_time(dp, theta1, theta2, t_max, 0.01))
+}
@@ -0,0 +1,233 @@
+use chaos_lab::{
+    basins::{duffing_basins, flip_time_map}, double_pendulum::DoublePendulum, duffing::Duffing, export::write_png, ftle::ftle_field,
+    heatmap::{viridis, Heatmap}, sweep::linspace
+};
+use eframe::egui;
+
+use crate::{show_image_plot, worker::Worker};
+
+/// what the pixels of the basin picture mean
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BasinKind {
+    /// which well of the forced duffing oscillator each (x, x′) ends up in
+    DuffingWells,
+    /// how long the double pendulum released from (θ1, θ2) takes to flip an arm over
+    FlipTime,
+    /// finite-time lyapunov exponent of the duffing oscillator over (x, x′)
+    FtleDuffing,
+    /// finite-time lyapunov exponent of the double pendulum released at rest from (θ1, θ2)
+    FtleDoublePendulum
+}
+
+impl BasinKind {
+    /// (label, lo, hi) for the x and y axes
+    #[allow(clippy::type_complexity)]
+    fn axes(&self) -> ((&'static str, f64, f64), (&'static str, f64, f64)) {
+        match self {
+            BasinKind::DuffingWells | BasinKind::FtleDuffing => (("x", -2.0, 2.0), ("x′", -2.0, 2.0)),
+            BasinKind::FlipTime | BasinKind::FtleDoublePendulum => (("θ1", -3.0, 3.0), ("θ2", -3.0, 3.0))
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            BasinKind::DuffingWells => "duffing wells",
+            BasinKind::FlipTime => "double pendulum flip time",
+            BasinKind::FtleDuffing => "duffing ftle",
+            BasinKind::FtleDoublePendulum => "double pendulum ftle"
+        }
+    }
+}
+
+// rows handed to the worker at a time, so the picture fills in as it goes
+const BASIN_CHUNK: usize = 4;
+
+/// basins of attraction, flip times or ftle fields over a plane of initial states
+pub struct BasinsPanel {
+    pub kind: BasinKind,
+    pub resolution: usize,
+    pub duffing: Duffing,
+    // forcing periods before the well is read off
+    pub periods: usize,
+    pub dp: DoublePendulum,
+    pub t_max: f64,
+    // ftle integration window, run backwards for attracting instead of repelling structures
+    pub horizon: f64,
+    pub backward: bool,
+
+    // what the current picture was computed for (and up to which t max), and its rows (bottom to top)
+    computed: BasinKind,
+    computed_t_max: f64,
+    rows: Vec<Vec<f64>>,
+    heatmap: Heatmap,
+    texture: Option<egui::TextureHandle>,
+    // (first row, rows) chunks
+    job: Option<Worker<(usize, Vec<Vec<f64>>)>>,
+    export_status: String
+}
+
+impl Default for BasinsPanel {
+    fn default() -> Self {
+        BasinsPanel {
+            kind: BasinKind::DuffingWells,
+            resolution: 200,
+            duffing: Duffing::default(),
+            periods: 30,
+            dp: DoublePendulum::default(),
+            t_max: 50.0,
+            horizon: 10.0,
+            backward: false,
+            computed: BasinKind::DuffingWells,
+            computed_t_max: 50.0,
+            rows: vec![],
+            heatmap: Heatmap { width: 0, height: 0, pixels: vec![] },
+            texture: None,
+            job: None,
+            export_status: String::new()
+        }
+    }
+}
+
+impl BasinsPanel {
+    fn compute(&mut self, ctx: &egui::Context) {
+        let kind = self.kind;
+        let ((_, x_lo, x_hi), (_, y_lo, y_hi)) = kind.axes();
+        let xs = linspace(x_lo, x_hi, self.resolution);
+        let ys = linspace(y_lo, y_hi, self.resolution);
+        let (duffing, periods, dp, t_max) = (self.duffing, self.periods, self.dp, self.t_max);
+        let horizon = if self.backward { -self.horizon } else { self.horizon };
+
+        self.computed = kind;
+        self.computed_t_max = t_max;
+        // NaN (black) until a row arrives
+        self.rows = vec![vec![f64::NAN; xs.len()]; ys.len()];
+        self.update_heatmap();
+
+        self.job = Some(Worker::spawn(ctx, xs.len() * ys.len(), move |progress, send| {
+            for (k, ys) in ys.chunks(BASIN_CHUNK).enumerate() {
+                let rows = match kind {
+                    BasinKind::DuffingWells => duffing_basins(&duffing, &xs, ys, periods, progress),
+                    BasinKind::FlipTime => flip_time_map(&dp, &xs, ys, t_max, progress),
+                    BasinKind::FtleDuffing => ftle_field(&duffing, &[0.0, 0.0], (0, 1), &xs, ys, horizon, 0.05, progress),
+                    BasinKind::FtleDoublePendulum => ftle_field(&dp, &[0.0, 0.0, 0.0, 0.0], (0, 1), &xs, ys, horizon, 0.01, progress)
+                };
+                let Some(rows) = rows else { return };
+                send((k * BASIN_CHUNK, rows));
+            }
+        }));
+    }
+
+    fn update_heatmap(&mut self) {
+        // image rows go top to bottom, the y axis bottom to top
+        let flipped: Vec<Vec<f64>> = self.rows.iter().rev().cloned().collect();
+        self.heatmap = match self.computed {
+            BasinKind::DuffingWells => Heatmap::from_rows_with(&flipped, |well| {
+                if well < 0.0 {
+                    [60, 100, 200]
+                } else if well > 0.0 {
+                    [235, 150, 40]
+                } else {
+                    [0, 0, 0]
+                }
+            }),
+            // flip times span orders of magnitude, coloured by log time. Never flipped is black
+            BasinKind::FlipTime => {
+                let (lo, hi) = (0.1_f64.ln(), self.computed_t_max.ln());
+                Heatmap::from_rows_with(&flipped, |t| if t.is_finite() { viridis((t.ln() - lo) / (hi - lo)) } else { [0, 0, 0] })
+            }
+            // ridges are bright
+            BasinKind::FtleDuffing | BasinKind::FtleDoublePendulum => Heatmap::from_rows(&flipped, None)
+        };
+        self.texture = None;
+    }
+
+    fn poll(&mut self) {
+        let Some(job) = &self.job else { return };
+        let finished = job.is_finished();
+        let chunks = job.poll();
+        if !chunks.is_empty() {
+            for (start, rows) in chunks {
+                for (i, row) in rows.into_iter().enumerate() {
+                    self.rows[start + i] = row;
+                }
+            }
+            self.update_heatmap();
+        }
+        if finished {
+            self.job = None;
+        }
+    }
+
+    pub fn ui(&mut self, ui: &mut egui::Ui) {
+        self.poll();
+
+        ui.horizontal(|ui| {
+            egui::ComboBox::from_label("basins")
+            .selected_text(self.kind.name())
+            .show_ui(ui, |ui| {
+                for kind in [BasinKind::DuffingWells, BasinKind::FlipTime, BasinKind::FtleDuffing, BasinKind::FtleDoublePendulum] {
+                    ui.selectable_value(&mut self.kind, kind, kind.name());
+                }
+            });
+            ui.label("resolution");
+            ui.add(egui::Slider::new(&mut self.resolution, 20..=600));
+        });
+
+        ui.horizontal(|ui| {
+            if matches!(self.kind, BasinKind::FtleDuffing | BasinKind::FtleDoublePendulum) {
+                ui.label("horizon");
+                ui.add(egui::Slider::new(&mut self.horizon, 0.5..=50.0).logarithmic(true));
+                ui.checkbox(&mut self.backward, "backward");
+            }
+            match self.kind {
+                BasinKind::DuffingWells | BasinKind::FtleDuffing => {
+                    ui.label("delta");
+                    ui.add(egui::Slider::new(&mut self.duffing.delta, 0.0..=1.0));
+                    ui.label("gamma");
+                    ui.add(egui::Slider::new(&mut self.duffing.gamma, 0.0..=0.5));
+                    ui.label("omega");
+                    ui.add(egui::Slider::new(&mut self.duffing.omega, 0.1..=3.0));
+                    if self.kind == BasinKind::DuffingWells {
+                        ui.label("periods");
+                        ui.add(egui::Slider::new(&mut self.periods, 5..=200));
+                    }
+                }
+                BasinKind::FtleDoublePendulum => {}
+                BasinKind::FlipTime => {
+                    ui.label("t max");
+                    ui.add(egui::Slider::new(&mut self.t_max, 1.0..=1000.0).logarithmic(true));
+                }
+            }
+            if ui.button("Compute").clicked() {
+                self.compute(ui.ctx());
+            }
+        });
+
+        if let Some(job) = &self.job {
+            job.ui(ui);
+        }
+
+        ui.label(match self.computed {
+            BasinKind::DuffingWells => "blue: ends in the left well, orange: the right one",
+            BasinKind::FlipTime => "colour: log of the time until an arm flips, black: never flips (within t max)",
+            BasinKind::FtleDuffing | BasinKind::FtleDoublePendulum => {
+                "colour: ftle, bright ridges repel nearby trajectories forward in time (attract backward)"
+            }
+        });
+
+        if self.rows.is_empty() {
+            return;
+        }
+        let (x_range, y_range) = self.computed.axes();
+        show_image_plot(ui, &mut self.texture, "basins", &self.heatmap, x_range, y_range);
+
+        if ui.button("Export PNG").clicked() {
+            let path = format!("{}.png", self.computed.name().replace(' ', "_"));
+            self.export_status = match write_png(&path, &self.heatmap) {
+                Ok(()) => format!("wrote {}", path),
+                Err(e) => format!("export failed: {}", e)
+            };
+        }
+        ui.label(&self.export_status);
+    }
+}
@@ -0,0 +1,523 @@
+use std::{collections::HashMap, io::Write, str::FromStr};
+
+use crate::{
+    continuation::{continue_equilibria, continue_map_orbits, Branch, Continuation}, dimension::correlation_dimension,
+    double_pendulum::DoublePendulum, dynamical_system::Parameters, feigenbaum::feigenbaum,
+    embedding::{average_mutual_information, delay_embed, embedding_dimension, false_nearest_neighbours, first_minimum}, export::write_png,
+    import::read_csv_trajectory_from_path, heatmap::{lyapunov_colour, Heatmap}, henon::Henon,
+    logistic_map::LogisticMap, lorenz::Lorenz, lorenz96::Lorenz96, lyapunov::{diagnose, diagnose_map, divergence_fit, rosenstein}, periodic_orbits::{find_flow_orbits, find_map_orbits, Section},
+    runner::{with_progress_bar, Progress}, sde::{ensemble_stats, AdditiveNoise, Scheme},
+    sine_map::SineMap, sweep::{flow_exponent, linspace, map_exponent, sweep, sweep_plane}, trajectory::Trajectory, zero_one::zero_one_test
+};
+
+pub const USAGE: &str = "usage: chaos_lab [command] [--option value]...
+
+with no command the gui is started
+
+commands:
+    ensemble    noisy lorenz ensemble vs. the deterministic flow, as csv
+                --members 100 --steps 2000 --dt 0.01 --noise 1.0 --scheme em|milstein --every 10
+    diagnostics lyapunov spectrum, kaplan-yorke dimension, ks entropy and predictability horizon
+                --system lorenz|double-pendulum|lorenz96|henon|logistic --steps 50000 --dt 0.01 --transient 1000
+                any parameter of the system can be set by name, e.g. --ro 28 or --a 1.4
+    sweep       largest lyapunov exponent against one parameter, as csv
+                --system logistic|henon|lorenz --parameter r --from 2.5 --to 4 --values 500
+    plane       largest lyapunov exponent over two parameters, as csv (long format)
+                --system henon|lorenz --x a --x-from 0 --x-to 1.5 --y b --y-from 0 --y-to 0.4
+                --resolution 100 --png plane.png
+    zero-one    gottwald-melbourne 0-1 test for chaos on one component of a trajectory, K near 0
+                is regular and near 1 chaotic. Flows are sampled every --every steps
+                --system lorenz|double-pendulum|henon|logistic --component 0 --samples 5000
+                --every 50 --dt 0.01 --cs 100
+    analyse     delay and dimension of a delay embedding, correlation dimension and 0-1 test
+                of one component, --system as for zero-one (flows sampled --every 5)
+                --component 0 --theiler 10, optionally --delay and --dimension to override.
+                The divergence curve runs --steps 60 samples, --fit-from and --fit-to pick its
+                linear region by hand
+    orbits      periodic orbits (stable or not) seeded from close returns of a trajectory, with
+                their floquet multipliers. Lorenz orbits are found on the section z = ro - 1
+                --system logistic|henon|lorenz --samples 5000 --epsilon 0.01
+                --max-period 6 (maps, iterations) --max-time 4 (lorenz)
+    continuation
+                pseudo-arclength continuation in one parameter, prints the bifurcations found.
+                Lorenz follows the origin up from --from and C+ down from --to, the logistic
+                map follows its cycle of --period 1 from --from
+                --system lorenz|logistic --parameter ro|r --from 0.5 --to 40 --max-step 0.5
+    feigenbaum  feigenbaum's δ and α from the superstable parameters R_n of the period-doubling
+                cascade, as a convergence table with richardson extrapolation
+                --system logistic|sine --max-n 12
+    help        print this message
+
+zero-one and analyse also work on measured data: --csv data.csv --column x (a header name or a
+0 based position, several separated by commas) and either --time t or --dt 1 for the sampling
+
+every command (and the gui) takes --seed <u64>. Without it a random seed is picked and printed,
+so any run can be replayed";
+
+/// parsed command line: `chaos_lab [command] [--option value]...`
+pub struct Args {
+    pub command: Option<String>,
+    options: HashMap<String, String>
+}
+
+impl Args {
+    /// `args` should not include the program name
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Self, String> {
+        let mut command = None;
+        let mut options = HashMap::new();
+        let mut args = args.into_iter();
+
+        while let Some(arg) = args.next() {
+            if let Some(name) = arg.strip_prefix("--") {
+                let value = args.next().ok_or(format!("missing value for --{}", name))?;
+                options.insert(name.to_string(), value);
+            } else if command.is_none() {
+                command = Some(arg);
+            } else {
+                return Err(format!("unexpected argument '{}'", arg));
+            }
+        }
+
+        Ok(Args { command, options })
+    }
+
+    /// value of `--name`, or `default` when it wasn't given
+    pub fn get<T: FromStr>(&self, name: &str, default: T) -> Result<T, String> {
+        match self.options.get(name) {
+            Some(value) => value.parse().map_err(|_| format!("invalid value '{}' for --{}", value, name)),
+            None => Ok(default)
+        }
+    }
+
+    /// sets every parameter of `sys` that was given as `--name value`
+    pub fn apply_parameters(&self, sys: &mut impl Parameters) -> Result<(), String> {
+        for (name, value) in sys.parameters() {
+            let value = self.get(&name, value)?;
+            sys.set_parameter(&name, value);
+        }
+        Ok(())
+    }
+
+    /// the seed from `--seed`, or a fresh random one (kept to u32 so it's easy to type back in)
+    pub fn seed(&self) -> Result<u64, String> {
+        self.get("seed", rand::random::<u32>() as u64)
+    }
+}
+
+/// runs a cli command, writing its report to `out`
+pub fn run(args: &Args, out: &mut impl Write) -> Result<(), String> {
+    match args.command.as_deref() {
+        Some("ensemble") => ensemble(args, out),
+        Some("diagnostics") => diagnostics(args, out),
+        Some("sweep") => lyapunov_sweep(args, out),
+        Some("plane") => lyapunov_plane(args, out),
+        Some("zero-one") => zero_one(args, out),
+        Some("analyse") => analyse(args, out),
+        Some("orbits") => periodic_orbits(args, out),
+        Some("continuation") => continuation(args, out),
+        Some("feigenbaum") => feigenbaum_table(args, out),
+        Some("help") | None => writeln!(out, "{}", USAGE).map_err(|e| e.to_string()),
+        Some(other) => Err(format!("unknown command '{}'\n\n{}", other, USAGE))
+    }
+}
+
+fn ensemble(args: &Args, out: &mut impl Write) -> Result<(), String> {
+    let seed = args.seed()?;
+    let members = args.get("members", 100)?;
+    let steps = args.get("steps", 2000)?;
+    let dt = args.get("dt", 0.01)?;
+    let every = args.get("every", 10_usize)?.max(1);
+    let scheme = match args.get("scheme", String::from("em"))?.as_str() {
+        "em" => Scheme::EulerMaruyama,
+        "milstein" => Scheme::Milstein,
+        other => return Err(format!("unknown scheme '{}', expected em or milstein", other))
+    };
+
+    let sys = AdditiveNoise { system: Lorenz::default(), sigma: args.get("noise", 1.0)? };
+    let progress = Progress::new();
+    let stats = with_progress_bar(&progress, || ensemble_stats(&sys, &[1.0, 1.0, 1.0], members, steps, dt, scheme, seed, &progress))
+    .ok_or("cancelled")?;
+
+    // seed goes first so the output alone is enough to replay the run
+    writeln!(out, "# seed = {}", seed).map_err(|e| e.to_string())?;
+    let mut writer = csv::Writer::from_writer(out);
+    writer.write_record(["t", "mean_x", "mean_y", "mean_z", "std_x", "std_y", "std_z", "det_x", "det_y", "det_z"])
+    .map_err(|e| e.to_string())?;
+
+    for k in (0..stats.times.len()).step_by(every) {
+        let mut row = vec![stats.times[k]];
+        row.extend(&stats.mean[k]);
+        row.extend(&stats.std_dev[k]);
+        row.extend(&stats.deterministic[k]);
+        writer.write_record(row.iter().map(|x| x.to_string())).map_err(|e| e.to_string())?;
+    }
+
+    writer.flush().map_err(|e| e.to_string())
+}
+
+fn diagnostics(args: &Args, out: &mut impl Write) -> Result<(), String> {
+    let system = args.get("system", String::from("lorenz"))?;
+    let steps = args.get("steps", 50000)?;
+    let dt = args.get("dt", 0.01)?;
+    let transient = args.get("transient", 1000)?;
+    // a single renormalisation says nothing about the averages
+    if steps == 0 {
+        return Err(String::from("--steps must be at least 1"));
+    }
+
+    let progress = Progress::new();
+    let (parameters, diagnostics) = match system.as_str() {
+        "lorenz" => {
+            let mut sys = Lorenz::default();
+            args.apply_parameters(&mut sys)?;
+            (sys.parameters(), diagnose(&sys, &[1.0, 1.0, 1.0], dt, steps, transient, &progress))
+        }
+        "double-pendulum" => {
+            let mut sys = DoublePendulum::default();
+            args.apply_parameters(&mut sys)?;
+            (sys.parameters(), diagnose(&sys, &[1.0, 1.0, 1.0, 1.0], dt, steps, transient, &progress))
+        }
+        "lorenz96" => {
+            let mut sys = Lorenz96::default();
+            args.apply_parameters(&mut sys)?;
+            (sys.parameters(), diagnose(&sys, &sys.initial_state(), dt, steps, transient, &progress))
+        }
+        "henon" => {
+            let mut map = Henon::default();
+            args.apply_parameters(&mut map)?;
+            (map.parameters(), diagnose_map(&map, &[0.1, 0.1], steps, transient, &progress))
+        }
+        "logistic" => {
+            let mut map = LogisticMap { r: 4.0 };
+            args.apply_parameters(&mut map)?;
+            (map.parameters(), diagnose_map(&map, &[0.3], steps, transient, &progress))
+        }
+        other => return Err(format!("unknown system '{}'\n\n{}", other, USAGE))
+    };
+
+    let diagnostics = diagnostics.ok_or("cancelled")?;
+    let parameters: Vec<String> = parameters.iter().map(|(name, value)| format!("{} = {}", name, value)).collect();
+    writeln!(out, "{} ({})", system, parameters.join(", ")).map_err(|e| e.to_string())?;
+    writeln!(out, "{}", diagnostics).map_err(|e| e.to_string())
+}
+
+fn lyapunov_sweep(args: &Args, out: &mut impl Write) -> Result<(), String> {
+    let system = args.get("system", String::from("logistic"))?;
+    let (default_name, default_from, default_to) = match system.as_str() {
+        "logistic" => ("r", 2.5, 4.0),
+        "henon" => ("a", 1.0, 1.4),
+        "lorenz" => ("ro", 20.0, 200.0),
+        other => return Err(format!("unknown system '{}', expected logistic, henon or lorenz", other))
+    };
+    let name = args.get("parameter", String::from(default_name))?;
+    let values = linspace(args.get("from", default_from)?, args.get("to", default_to)?, args.get("values", 500)?);
+
+    let progress = Progress::new();
+    let exponents = with_progress_bar(&progress, || match system.as_str() {
+        "logistic" => sweep_parameters(&LogisticMap { r: 4.0 }, args, &name, &values, &progress, |map| map_exponent(map, &[0.3])),
+        "henon" => sweep_parameters(&Henon::default(), args, &name, &values, &progress, |map| map_exponent(map, &[0.1, 0.1])),
+        _ => sweep_parameters(&Lorenz::default(), args, &name, &values, &progress, |sys| flow_exponent(sys, &[1.0, 1.0, 1.0], 0.01))
+    })?;
+
+    let mut writer = csv::Writer::from_writer(out);
+    writer.write_record([name.as_str(), "lambda"]).map_err(|e| e.to_string())?;
+    for (value, lambda) in values.iter().zip(&exponents) {
+        writer.write_record([value.to_string(), lambda.to_string()]).map_err(|e| e.to_string())?;
+    }
+    writer.flush().map_err(|e| e.to_string())
+}
+
+/// the other parameters come from the command line, `name` is swept
+fn sweep_parameters<S: Parameters + Clone + Sync>(system: &S, args: &Args, name: &str, values: &[f64], progress: &Progress, exponent: impl Fn(&S) -> f64 + Sync) -> Result<Vec<f64>, String> {
+    let mut system = system.clone();
+    args.apply_parameters(&mut system)?;
+    if system.parameter(name).is_none() {
+        return Err(format!("no parameter named '{}'", name));
+    }
+    sweep(&system, name, values, progress, exponent).ok_or(String::from("cancelled"))
+}
+
+fn lyapunov_plane(args: &Args, out: &mut impl Write) -> Result<(), String> {
+    let system = args.get("system", String::from("henon"))?;
+    // colour scale, and how close to zero a flow's exponent counts as periodic
+    let (x_default, y_default, scale, threshold) = match system.as_str() {
+        "henon" => (("a", 0.0, 1.5), ("b", 0.0, 0.4), 0.5, 0.0),
+        "lorenz" => (("ro", 10.0, 200.0), ("sigma", 2.0, 20.0), 1.5, 0.01),
+        other => return Err(format!("unknown system '{}', expected henon or lorenz", other))
+    };
+    let resolution = args.get("resolution", 100)?;
+    let x_name = args.get("x", String::from(x_default.0))?;
+    let y_name = args.get("y", String::from(y_default.0))?;
+    let xs = linspace(args.get("x-from", x_default.1)?, args.get("x-to", x_default.2)?, resolution);
+    let ys = linspace(args.get("y-from", y_default.1)?, args.get("y-to", y_default.2)?, resolution);
+
+    let progress = Progress::new();
+    let rows = with_progress_bar(&progress, || {
+        let plane = match system.as_str() {
+            "henon" => {
+                let mut map = Henon::default();
+                args.apply_parameters(&mut map)?;
+                check_parameters(&map, &x_name, &y_name)?;
+                sweep_plane(&map, (&x_name, &xs), (&y_name, &ys), &progress, |map| map_exponent(map, &[0.1, 0.1]))
+            }
+            _ => {
+                let mut lorenz = Lorenz::default();
+                args.apply_parameters(&mut lorenz)?;
+                check_parameters(&lorenz, &x_name, &y_name)?;
+                sweep_plane(&lorenz, (&x_name, &xs), (&y_name, &ys), &progress, |sys| flow_exponent(sys, &[1.0, 1.0, 1.0], 0.01))
+            }
+        };
+        plane.ok_or(String::from("cancelled"))
+    })?;
+
+    if let Some(path) = args.options.get("png") {
+        // top row of the image is the largest y
+        let flipped: Vec<Vec<f64>> = rows.iter().rev().cloned().collect();
+        let heatmap = Heatmap::from_rows_with(&flipped, |lambda| lyapunov_colour(if lambda.abs() < threshold { 0.0 } else { lambda }, scale));
+        write_png(path, &heatmap).map_err(|e| e.to_string())?;
+    }
+
+    let mut writer = csv::Writer::from_writer(out);
+    writer.write_record([x_name.as_str(), y_name.as_str(), "lambda"]).map_err(|e| e.to_string())?;
+    for (y, row) in ys.iter().zip(&rows) {
+        for (x, lambda) in xs.iter().zip(row) {
+            writer.write_record([x.to_string(), y.to_string(), lambda.to_string()]).map_err(|e| e.to_string())?;
+        }
+    }
+    writer.flush().map_err(|e| e.to_string())
+}
+
+fn check_parameters(system: &impl Parameters, x_name: &str, y_name: &str) -> Result<(), String> {
+    for name in [x_name, y_name] {
+        if system.parameter(name).is_none() {
+            return Err(format!("no parameter named '{}'", name));
+        }
+    }
+    Ok(())
+}
+
+/// the trajectory a series analysis runs on: measured data from `--csv`, or a recording of
+/// `--system` with flows sampled every `--every` steps. Also returns a description of where it came from
+fn record_series(args: &Args, default_every: usize) -> Result<(String, Trajectory), String> {
+    if let Some(path) = args.options.get("csv") {
+        let columns = args.get("column", String::from("0"))?;
+        let columns: Vec<&str> = columns.split(',').map(str::trim).collect();
+        let time = args.options.get("time").map(String::as_str);
+        let trajectory = read_csv_trajectory_from_path(path, &columns, time, args.get("dt", 1.0)?).map_err(|e| format!("{}: {}", path, e))?;
+        return Ok((format!("{} ({})", path, columns.join(", ")), trajectory));
+    }
+
+    let system = args.get("system", String::from("lorenz"))?;
+    let samples = args.get("samples", 5000)?;
+    let every = args.get("every", default_every)?;
+    let dt = args.get("dt", 0.01)?;
+
+    let (parameters, trajectory) = match system.as_str() {
+        "lorenz" => {
+            let mut sys = Lorenz::default();
+            args.apply_parameters(&mut sys)?;
+            (sys.parameters(), Trajectory::integrate(&sys, &[1.0, 1.0, 1.0], dt, every, samples, 100))
+        }
+        "double-pendulum" => {
+            let mut sys = DoublePendulum::default();
+            args.apply_parameters(&mut sys)?;
+            (sys.parameters(), Trajectory::integrate(&sys, &[1.0, 1.0, 1.0, 1.0], dt, every, samples, 0))
+        }
+        "henon" => {
+            let mut map = Henon::default();
+            args.apply_parameters(&mut map)?;
+            (map.parameters(), Trajectory::iterate(&map, &[0.1, 0.1], samples, 1000))
+        }
+        "logistic" => {
+            let mut map = LogisticMap { r: 4.0 };
+            args.apply_parameters(&mut map)?;
+            (map.parameters(), Trajectory::iterate(&map, &[0.3], samples, 1000))
+        }
+        other => return Err(format!("unknown system '{}', expected lorenz, double-pendulum, henon or logistic", other))
+    };
+
+    let parameters: Vec<String> = parameters.iter().map(|(name, value)| format!("{} = {}", name, value)).collect();
+    Ok((format!("{} ({})", system, parameters.join(", ")), trajectory))
+}
+
+/// `--component` of the trajectory as a scalar series
+fn component(args: &Args, trajectory: &Trajectory) -> Result<(usize, Vec<f64>), String> {
+    let component = args.get("component", 0_usize)?;
+    if component >= trajectory.dimension() {
+        return Err(format!("--component {} but there are only {} components", component, trajectory.dimension()));
+    }
+    Ok((component, trajectory.component(component)))
+}
+
+fn zero_one(args: &Args, out: &mut impl Write) -> Result<(), String> {
+    let seed = args.seed()?;
+    let count = args.get("cs", 100)?;
+    let (source, trajectory) = record_series(args, 50)?;
+    let (component, signal) = component(args, &trajectory)?;
+
+    let progress = Progress::new();
+    let result = with_progress_bar(&progress, || zero_one_test(&signal, count, seed, &progress))
+    .ok_or("cancelled")?;
+
+    writeln!(out, "{}, component {}, seed = {}", source, component, seed).map_err(|e| e.to_string())?;
+    writeln!(out, "{}", result).map_err(|e| e.to_string())
+}
+
+fn analyse(args: &Args, out: &mut impl Write) -> Result<(), String> {
+    let seed = args.seed()?;
+    let theiler = args.get("theiler", 10)?;
+    let (source, trajectory) = record_series(args, 5)?;
+    let (component, signal) = component(args, &trajectory)?;
+    let write = |out: &mut dyn Write, line: String| writeln!(out, "{}", line).map_err(|e| e.to_string());
+
+    write(out, format!("{}, component {}, {} samples, seed = {}", source, component, signal.len(), seed))?;
+
+    // maps (or anything without a minimum) are embedded with delay 1, re: `first_minimum`
+    let is_map = !args.options.contains_key("csv") && matches!(args.get("system", String::from("lorenz"))?.as_str(), "henon" | "logistic");
+    let ami = average_mutual_information(&signal, 100, 32);
+    let delay = match first_minimum(&ami) {
+        Some(delay) if !is_map => delay,
+        _ => 1
+    };
+    let delay = args.get("delay", delay)?;
+    write(out, format!("delay: {} samples (first minimum of the mutual information, 1 for maps)", delay))?;
+
+    // false nearest neighbours are brute force, a few thousand samples say all there is to say
+    let fnn = false_nearest_neighbours(&signal[..signal.len().min(3000)], delay, 8, theiler);
+    let fractions: Vec<String> = fnn.iter().enumerate().map(|(k, f)| format!("{}: {:.3}", k + 1, f)).collect();
+    write(out, format!("false nearest neighbours: {}", fractions.join(", ")))?;
+    let dimension = args.get("dimension", embedding_dimension(&fnn, 0.01).unwrap_or(fnn.len()))?;
+    write(out, format!("embedding dimension: {}", dimension))?;
+
+    let points = delay_embed(&signal, dimension, delay);
+    let correlation = correlation_dimension(&points, theiler).ok_or("not enough points for the correlation dimension, try more samples")?;
+    write(out, format!("correlation dimension D2 = {:.3}", correlation.slope))?;
+
+    let sample_dt = if trajectory.len() > 1 { trajectory.times[1] - trajectory.times[0] } else { 1.0 };
+    let curve = rosenstein(&points, theiler, args.get("steps", 60)?);
+    let region = match (args.options.get("fit-from"), args.options.get("fit-to")) {
+        (None, None) => None,
+        _ => Some((args.get("fit-from", 0)?, args.get("fit-to", curve.len())?))
+    };
+    let fit = divergence_fit(&curve, sample_dt, region).ok_or("not enough points in the divergence curve to fit")?;
+    write(out, format!("largest lyapunov exponent (rosenstein) = {:.4} per unit time, fitted over samples {}..{}", fit.slope, fit.start, fit.end))?;
+
+    let progress = Progress::new();
+    let zero_one = with_progress_bar(&progress, || zero_one_test(&signal, 100, seed, &progress)).ok_or("cancelled")?;
+    write(out, format!("0-1 test: {}", zero_one))
+}
+
+fn periodic_orbits(args: &Args, out: &mut impl Write) -> Result<(), String> {
+    let system = args.get("system", String::from("logistic"))?;
+    let samples = args.get("samples", 5000)?;
+    let max_period = args.get("max-period", 6)?;
+
+    let (parameters, orbits) = match system.as_str() {
+        "logistic" => {
+            let mut map = LogisticMap { r: 4.0 };
+            args.apply_parameters(&mut map)?;
+            let trajectory = Trajectory::iterate(&map, &[0.3], samples, 1000);
+            (map.parameters(), find_map_orbits(&map, &trajectory.states, max_period, args.get("epsilon", 0.01)?))
+        }
+        "henon" => {
+            let mut map = Henon::default();
+            args.apply_parameters(&mut map)?;
+            let trajectory = Trajectory::iterate(&map, &[0.1, 0.1], samples, 1000);
+            (map.parameters(), find_map_orbits(&map, &trajectory.states, max_period, args.get("epsilon", 0.01)?))
+        }
+        "lorenz" => {
+            let mut lorenz = Lorenz::default();
+            args.apply_parameters(&mut lorenz)?;
+            // sampled every 0.05, returns sooner than half a time unit are just the trajectory moving on
+            let trajectory = Trajectory::integrate(&lorenz, &[1.0, 1.0, 1.0], 0.01, 5, samples, 200);
+            let periods = 10..=((args.get("max-time", 4.0)? / 0.05) as usize);
+            let section = Section { component: 2, value: lorenz.ro - 1.0 };
+            (lorenz.parameters(), find_flow_orbits(&lorenz, &trajectory.states, periods, args.get("epsilon", 2.0)?, section, 0.01))
+        }
+        other => return Err(format!("unknown system '{}', expected logistic, henon or lorenz", other))
+    };
+
+    let parameters: Vec<String> = parameters.iter().map(|(name, value)| format!("{} = {}", name, value)).collect();
+    writeln!(out, "{} ({}), {} orbits", system, parameters.join(", "), orbits.len()).map_err(|e| e.to_string())?;
+    for orbit in &orbits {
+        let point: Vec<String> = orbit.points[0].iter().map(|x| format!("{:.6}", x)).collect();
+        writeln!(out, "{}, through ({})", orbit, point.join(", ")).map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+fn continuation(args: &Args, out: &mut impl Write) -> Result<(), String> {
+    let system = args.get("system", String::from("lorenz"))?;
+    let max_step = args.get("max-step", 0.5)?;
+    let progress = Progress::new();
+
+    let (name, branches): (String, Vec<(String, Branch)>) = match system.as_str() {
+        "lorenz" => {
+            let name = args.get("parameter", String::from("ro"))?;
+            let (from, to): (f64, f64) = (args.get("from", 0.5)?, args.get("to", 40.0)?);
+            let mut lorenz = Lorenz::default();
+            args.apply_parameters(&mut lorenz)?;
+            let settings = Continuation { max_step, range: (from.min(to), from.max(to)), ..Continuation::default() };
+            if !lorenz.set_parameter(&name, from) {
+                return Err(format!("lorenz has no parameter '{}'", name));
+            }
+            let origin = with_progress_bar(&progress, || continue_equilibria(&lorenz, &name, &[0.0, 0.0, 0.0], settings, &progress))
+            .ok_or("no equilibrium at the origin")?;
+            // C+ = (±√(β(ρ - 1)), ±√(β(ρ - 1)), ρ - 1), newton takes it from there for other parameters
+            lorenz.set_parameter(&name, to);
+            let c = (lorenz.beta * (lorenz.ro - 1.0)).abs().sqrt();
+            let settings = Continuation { step: -settings.step, ..settings };
+            let wing = with_progress_bar(&progress, || continue_equilibria(&lorenz, &name, &[c, c, lorenz.ro - 1.0], settings, &progress))
+            .ok_or("no equilibrium near C+")?;
+            (name, vec![(String::from("origin"), origin), (String::from("C+"), wing)])
+        }
+        "logistic" => {
+            let name = args.get("parameter", String::from("r"))?;
+            let period = args.get("period", 1)?;
+            let (from, to): (f64, f64) = (args.get("from", 2.0)?, args.get("to", 4.0)?);
+            let mut map = LogisticMap::default();
+            args.apply_parameters(&mut map)?;
+            if !map.set_parameter(&name, from) {
+                return Err(format!("the logistic map has no parameter '{}'", name));
+            }
+            let step = if to < from { -0.05 } else { 0.05 };
+            let settings = Continuation { step, max_step, range: (from.min(to), from.max(to)), ..Continuation::default() };
+            let x0 = Trajectory::iterate(&map, &[0.3], 1, 1000).states[0].clone();
+            let cycle = with_progress_bar(&progress, || continue_map_orbits(&map, &name, &x0, period, settings, &progress))
+            .ok_or(format!("no cycle of period {} near {}", period, x0[0]))?;
+            (name, vec![(format!("period {}", period), cycle)])
+        }
+        other => return Err(format!("unknown system '{}', expected lorenz or logistic", other))
+    };
+
+    for (branch_name, branch) in &branches {
+        let (first, last) = (&branch.points[0], &branch.points[branch.points.len() - 1]);
+        writeln!(out, "{}: {} points, {} from {:.4} to {:.4}", branch_name, branch.points.len(), name, first.parameter, last.parameter).map_err(|e| e.to_string())?;
+        for special in &branch.bifurcations {
+            let state: Vec<String> = special.state.iter().map(|x| format!("{:.6}", x)).collect();
+            writeln!(out, "    {} ({}) at {} = {:.8}, ({})", special.kind.label(), special.kind.name(), name, special.parameter, state.join(", ")).map_err(|e| e.to_string())?;
+        }
+    }
+    Ok(())
+}
+
+fn feigenbaum_table(args: &Args, out: &mut impl Write) -> Result<(), String> {
+    let system = args.get("system", String::from("logistic"))?;
+    let max_n = args.get("max-n", 12)?;
+    // both have their maximum at 1/2, the guesses are rough R_0 and R_1
+    let cascade = match system.as_str() {
+        "logistic" => feigenbaum(&LogisticMap { r: 3.0 }, "r", 0.5, (2.0, 3.2), max_n),
+        "sine" => feigenbaum(&SineMap::default(), "r", 0.5, (0.5, 0.78), max_n),
+        other => return Err(format!("unknown system '{}', expected logistic or sine", other))
+    }.ok_or("couldn't find the superstable parameters R_0 to R_3, max-n needs to be at least 3")?;
+
+    let value = |v: Option<f64>| v.map_or(String::from("-"), |v| format!("{:.9}", v));
+    writeln!(out, "{:>3} {:>7} {:>18} {:>12} {:>12} {:>12} {:>12}", "n", "period", "R_n", "δ_n", "δ richardson", "α_n", "α richardson").map_err(|e| e.to_string())?;
+    for step in &cascade.steps {
+        writeln!(
+            out, "{:>3} {:>7} {:>18.15} {:>12} {:>12} {:>12} {:>12}",
+            step.n, 1_usize << step.n, step.parameter, value(step.delta), value(step.delta_extrapolated), value(step.alpha), value(step.alpha_extrapolated)
+        ).map_err(|e| e.to_string())?;
+    }
+    writeln!(out, "{} (known: δ = 4.669202, α = 2.502908)", cascade).map_err(|e| e.to_string())
+}
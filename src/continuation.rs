@@ -0,0 +1,432 @@
+use crate::{
+    dynamical_system::{DynamicalSystem, Map, Parameters}, math::{determinant, distance, eigenvalues, jacobian, solve_linear, Complex},
+    periodic_orbits::{newton, next_crossing, off_section, on_section, return_map, Section}, runner::Progress, sweep::with_parameter
+};
+
+/// step control and limits for [`continue_equilibria`] and friends
+#[derive(Debug, Clone, Copy)]
+pub struct Continuation {
+    /// first arclength step, its sign sets which way the parameter starts moving
+    pub step: f64,
+    pub min_step: f64,
+    pub max_step: f64,
+    pub max_points: usize,
+    /// the branch stops when the parameter leaves this range
+    pub range: (f64, f64)
+}
+
+impl Default for Continuation {
+    fn default() -> Self {
+        Continuation { step: 0.05, min_step: 1e-6, max_step: 0.5, max_points: 1000, range: (f64::NEG_INFINITY, f64::INFINITY) }
+    }
+}
+
+/// one point along a branch
+#[derive(Debug, Clone)]
+pub struct BranchPoint {
+    pub parameter: f64,
+    /// the equilibrium, the cycle point of a map, or where a flow's orbit crosses the section
+    pub state: Vec<f64>,
+    /// of the jacobian for an equilibrium, the multipliers for a periodic orbit
+    pub eigenvalues: Vec<Complex>,
+    pub stable: bool,
+    /// time round a flow's periodic orbit
+    pub period: Option<f64>
+}
+
+/// what changes at a bifurcation point
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Bifurcation {
+    /// the branch turns back in the parameter (saddle-node, limit point)
+    Fold,
+    /// a real eigenvalue crosses 0 (a multiplier +1) without a fold, another branch crosses
+    /// this one there (pitchfork, transcritical)
+    BranchPoint,
+    /// a complex pair of eigenvalues crosses the imaginary axis, a limit cycle is born
+    Hopf,
+    /// a multiplier crosses -1
+    PeriodDoubling,
+    /// a complex pair of multipliers crosses the unit circle, an invariant torus is born
+    NeimarkSacker
+}
+
+impl Bifurcation {
+    /// the usual abbreviation, for plot labels
+    pub fn label(&self) -> &'static str {
+        match self {
+            Bifurcation::Fold => "LP",
+            Bifurcation::BranchPoint => "BP",
+            Bifurcation::Hopf => "H",
+            Bifurcation::PeriodDoubling => "PD",
+            Bifurcation::NeimarkSacker => "NS"
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Bifurcation::Fold => "fold",
+            Bifurcation::BranchPoint => "branch point",
+            Bifurcation::Hopf => "hopf",
+            Bifurcation::PeriodDoubling => "period doubling",
+            Bifurcation::NeimarkSacker => "neimark-sacker"
+        }
+    }
+}
+
+/// a bifurcation located along a branch
+#[derive(Debug, Clone)]
+pub struct SpecialPoint {
+    pub kind: Bifurcation,
+    pub parameter: f64,
+    pub state: Vec<f64>
+}
+
+/// a continued branch of equilibria or periodic orbits
+#[derive(Debug, Clone, Default)]
+pub struct Branch {
+    pub points: Vec<BranchPoint>,
+    pub bifurcations: Vec<SpecialPoint>
+}
+
+/// how many eigenvalues sit on the unstable side, split by how they can get there.
+/// A change in any count between two points means something crossed in between
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Signature {
+    // real eigenvalues > 0 (multipliers > 1)
+    real: usize,
+    // real multipliers < -1, always 0 for equilibria
+    flip: usize,
+    // complex eigenvalues with positive real part (multipliers outside the unit circle)
+    complex: usize
+}
+
+impl Signature {
+    fn of(eigenvalues: &[Complex], discrete: bool) -> Self {
+        let count = |f: &dyn Fn(&Complex) -> bool| eigenvalues.iter().filter(|z| f(z)).count();
+        if discrete {
+            Signature {
+                real: count(&|z| z.im == 0.0 && z.re > 1.0),
+                flip: count(&|z| z.im == 0.0 && z.re < -1.0),
+                complex: count(&|z| z.im != 0.0 && z.norm() > 1.0)
+            }
+        } else {
+            Signature { real: count(&|z| z.im == 0.0 && z.re > 0.0), flip: 0, complex: count(&|z| z.im != 0.0 && z.re > 0.0) }
+        }
+    }
+
+    fn unstable(&self) -> usize {
+        self.real + self.flip + self.complex
+    }
+}
+
+/// one way of turning `G(x, p) = 0` into a branch: the residual, and the shift between G's
+/// jacobian and the matrix whose eigenvalues decide stability
+struct Problem<G> {
+    g: G,
+    // 0 for equilibria (eigenvalues of f_x), 1 for fixed points of F^n (F^n - x, so DF^n = G_x + I)
+    shift: f64,
+    discrete: bool
+}
+
+impl<G: Fn(&[f64], f64) -> Vec<f64>> Problem<G> {
+    /// G over y = (x, p)
+    fn residual(&self, y: &[f64]) -> Vec<f64> {
+        let (x, p) = y.split_at(y.len() - 1);
+        (self.g)(x, p[0])
+    }
+
+    /// the m × (m + 1) jacobian of G at y
+    fn jacobian(&self, y: &[f64]) -> Vec<Vec<f64>> {
+        jacobian(|y| self.residual(y), y)
+    }
+
+    fn eigenvalues(&self, jacobian: &[Vec<f64>]) -> Vec<Complex> {
+        let m = jacobian.len();
+        let a: Vec<Vec<f64>> = (0..m).map(|i| (0..m).map(|j| jacobian[i][j] + if i == j { self.shift } else { 0.0 }).collect()).collect();
+        eigenvalues(&a)
+    }
+
+    fn is_stable(&self, eigenvalues: &[Complex]) -> bool {
+        if self.discrete { eigenvalues.iter().all(|z| z.norm() < 1.0) } else { eigenvalues.iter().all(|z| z.re < 0.0) }
+    }
+
+    /// unit tangent to the branch at y, oriented along `previous`: the null vector of G's jacobian
+    fn tangent(&self, jacobian: &[Vec<f64>], previous: &[f64]) -> Option<Vec<f64>> {
+        let mut a = jacobian.to_vec();
+        a.push(previous.to_vec());
+        let mut rhs = vec![0.0; previous.len()];
+        rhs[previous.len() - 1] = 1.0;
+        let t = solve_linear(&a, &rhs)?;
+        let norm = t.iter().map(|x| x * x).sum::<f64>().sqrt();
+        let sign = if t.iter().zip(previous).map(|(a, b)| a * b).sum::<f64>() < 0.0 { -1.0 } else { 1.0 };
+        Some(t.iter().map(|x| sign * x / norm).collect())
+    }
+
+    /// newton on G(y) = 0 plus the hyperplane `direction · (y - through) = 0`, from `guess`
+    fn correct(&self, guess: &[f64], direction: &[f64], through: &[f64]) -> Option<(Vec<f64>, usize)> {
+        let mut y = guess.to_vec();
+        for iteration in 1..=10 {
+            let mut h = self.residual(&y);
+            h.push(direction.iter().zip(&y).zip(through).map(|((d, y), t)| d * (y - t)).sum());
+            let mut a = self.jacobian(&y);
+            a.push(direction.to_vec());
+            let minus: Vec<f64> = h.iter().map(|r| -r).collect();
+            let delta = solve_linear(&a, &minus)?;
+            for (y, d) in y.iter_mut().zip(&delta) {
+                *y += d;
+            }
+            let size = delta.iter().map(|d| d * d).sum::<f64>().sqrt();
+            if !size.is_finite() {
+                return None;
+            }
+            if size < 1e-10 * y.iter().fold(1.0_f64, |m, y| m.max(y.abs())) {
+                return Some((y, iteration));
+            }
+        }
+        None
+    }
+
+    fn point(&self, y: &[f64], jacobian: &[Vec<f64>]) -> BranchPoint {
+        let eigenvalues = self.eigenvalues(jacobian);
+        BranchPoint {
+            parameter: y[y.len() - 1],
+            state: y[..(y.len() - 1)].to_vec(),
+            stable: self.is_stable(&eigenvalues),
+            eigenvalues,
+            period: None
+        }
+    }
+
+    /// what the bifurcation tests read at y, with t the tangent there
+    fn tests(&self, jacobian: &[Vec<f64>], t: &[f64]) -> Tests {
+        let mut augmented = jacobian.to_vec();
+        augmented.push(t.to_vec());
+        Tests {
+            direction: t[t.len() - 1],
+            branch: determinant(&augmented),
+            signature: Signature::of(&self.eigenvalues(jacobian), self.discrete)
+        }
+    }
+
+    /// pseudo-arclength continuation from the solution (x0, p0)
+    /// ```latex
+    /// predict:  y = y_k + Δs t_k
+    /// correct:  G(y) = 0,  t_k · (y - y_k) = 0  (through the prediction)
+    /// ```
+    /// stepping along the branch's arclength rather than the parameter goes round folds, where
+    /// the branch turns back and plain parameter stepping loses it. Between consecutive points
+    /// ```latex
+    /// fold:           t_p changes sign
+    /// branch point:   det [G_y; t^T] changes sign
+    /// hopf, PD, NS:   the eigenvalues' unstable counts change
+    /// ```
+    /// and each one found is pinned down by bisection along the segment
+    fn run(&self, x0: &[f64], p0: f64, settings: Continuation, progress: &Progress) -> Option<Branch> {
+        let mut y = x0.to_vec();
+        y.push(p0);
+        let jacobian = self.jacobian(&y);
+        let mut previous = vec![0.0; y.len()];
+        previous[y.len() - 1] = settings.step.signum();
+        let mut t = self.tangent(&jacobian, &previous)?;
+        let mut tests = self.tests(&jacobian, &t);
+
+        let mut branch = Branch { points: vec![self.point(&y, &jacobian)], bifurcations: vec![] };
+        let mut step = settings.step.abs();
+        let in_range = |p: f64| p >= settings.range.0 && p <= settings.range.1;
+
+        while branch.points.len() < settings.max_points && in_range(y[y.len() - 1]) {
+            if progress.is_cancelled() {
+                return None;
+            }
+            let guess: Vec<f64> = y.iter().zip(&t).map(|(y, t)| y + (step * t)).collect();
+            let Some((next, iterations)) = self.correct(&guess, &t, &guess) else {
+                step /= 2.0;
+                if step < settings.min_step {
+                    break;
+                }
+                continue;
+            };
+            let next_jacobian = self.jacobian(&next);
+            let Some(next_t) = self.tangent(&next_jacobian, &t) else { break };
+            let next_tests = self.tests(&next_jacobian, &next_t);
+
+            for kind in tests.crossings(&next_tests, self.discrete) {
+                // where the bisection gives up, it's reported at the step
+                let at = self.locate(kind, &y, &next, &t, &tests).unwrap_or_else(|| next.clone());
+                branch.bifurcations.push(SpecialPoint { kind, parameter: at[at.len() - 1], state: at[..(at.len() - 1)].to_vec() });
+            }
+
+            branch.points.push(self.point(&next, &next_jacobian));
+            progress.tick();
+            (y, t, tests) = (next, next_t, next_tests);
+            // quick convergence means the step could be longer
+            if iterations <= 3 {
+                step = (step * 1.5).min(settings.max_step);
+            }
+        }
+        Some(branch)
+    }
+
+    /// the `kind` of bifurcation between a and b
+    fn locate(&self, kind: Bifurcation, a: &[f64], b: &[f64], t_a: &[f64], tests_a: &Tests) -> Option<Vec<f64>> {
+        match kind {
+            // the test functions are smooth, so secant steps along the branch from the last point
+            // before the zero. Close to a branch point the corrector can land on the other branch,
+            // but det [G_y; t^T] vanishes at the branch point along both, so that still homes in
+            Bifurcation::BranchPoint | Bifurcation::Fold => {
+                let value = |tests: &Tests| if kind == Bifurcation::Fold { tests.direction } else { tests.branch };
+                let (mut c, mut t_c, mut value_c) = (a.to_vec(), t_a.to_vec(), value(tests_a));
+                let jacobian_b = self.jacobian(b);
+                let (mut far, mut value_far) = (b.to_vec(), value(&self.tests(&jacobian_b, &self.tangent(&jacobian_b, t_a)?)));
+                let (mut found, mut last_step) = (b.to_vec(), f64::INFINITY);
+                for _ in 0..30 {
+                    let gap = c.iter().zip(&far).map(|(c, f)| (c - f) * (c - f)).sum::<f64>().sqrt();
+                    let step = gap * value_c / (value_c - value_far);
+                    // steps growing again means it's down to the noise in the finite differences
+                    // (or wandered off along the other branch)
+                    if step.abs() >= last_step {
+                        break;
+                    }
+                    last_step = step.abs();
+                    let guess: Vec<f64> = c.iter().zip(&t_c).map(|(c, t)| c + (step * t)).collect();
+                    // newton's matrix is singular right on top of a branch point, the guess is as
+                    // close as it gets
+                    let Some((y, _)) = self.correct(&guess, &t_c, &guess) else { return Some(guess) };
+                    let jacobian = self.jacobian(&y);
+                    let Some(t_y) = self.tangent(&jacobian, &t_c) else { return Some(y) };
+                    let value_y = value(&self.tests(&jacobian, &t_y));
+                    found = y.clone();
+                    if value_y == 0.0 {
+                        break;
+                    }
+                    if value_y * value_c > 0.0 {
+                        (c, t_c, value_c) = (y, t_y, value_y);
+                    } else {
+                        (far, value_far) = (y, value_y);
+                    }
+                }
+                Some(found)
+            }
+            // eigenvalue counts only say which side, so bisection. Points in between come from
+            // correcting points of the chord a → b back onto the branch, perpendicular to the chord
+            _ => {
+                let direction: Vec<f64> = {
+                    let d: Vec<f64> = b.iter().zip(a).map(|(b, a)| b - a).collect();
+                    let norm = d.iter().map(|x| x * x).sum::<f64>().sqrt();
+                    d.iter().map(|x| x / norm).collect()
+                };
+                let at = |s: f64| -> Option<(Vec<f64>, Tests)> {
+                    let through: Vec<f64> = a.iter().zip(b).map(|(a, b)| a + (s * (b - a))).collect();
+                    let (y, _) = self.correct(&through, &direction, &through)?;
+                    let jacobian = self.jacobian(&y);
+                    let tests = self.tests(&jacobian, &self.tangent(&jacobian, t_a)?);
+                    Some((y, tests))
+                };
+                let (mut lo, mut hi) = (0.0, 1.0);
+                let mut found = b.to_vec();
+                for _ in 0..30 {
+                    let s = (lo + hi) / 2.0;
+                    let (y, tests) = at(s)?;
+                    if tests_a.crossings(&tests, self.discrete).contains(&kind) {
+                        hi = s;
+                        found = y;
+                    } else {
+                        lo = s;
+                    }
+                }
+                Some(found)
+            }
+        }
+    }
+}
+
+/// the bifurcation test functions at one point of a branch
+struct Tests {
+    // the tangent's parameter component
+    direction: f64,
+    // det [G_y; t^T], zero at a branch point but not at a fold
+    branch: f64,
+    signature: Signature
+}
+
+impl Tests {
+    /// bifurcations between here and `other`
+    fn crossings(&self, other: &Tests, discrete: bool) -> Vec<Bifurcation> {
+        let (before, after) = (self.signature, other.signature);
+        let mut kinds = vec![];
+        if self.branch * other.branch < 0.0 {
+            kinds.push(Bifurcation::BranchPoint);
+        } else if self.direction * other.direction < 0.0 {
+            kinds.push(Bifurcation::Fold);
+        }
+        if before.flip != after.flip {
+            kinds.push(Bifurcation::PeriodDoubling);
+        }
+        // a pair of real eigenvalues meeting and going complex changes the counts too, but not
+        // how many are unstable
+        if before.complex != after.complex && before.unstable() != after.unstable() {
+            kinds.push(if discrete { Bifurcation::NeimarkSacker } else { Bifurcation::Hopf });
+        }
+        kinds
+    }
+}
+
+/// continues the equilibrium of `sys` near `x0` in the parameter `name`, starting from the
+/// parameter's current value, re: [`Continuation`]. Each point's eigenvalues are the jacobian's
+/// and it's stable when they all have negative real part. `None` if there's no equilibrium
+/// near `x0` or it was cancelled
+pub fn continue_equilibria<S: DynamicalSystem + Parameters + Clone>(sys: &S, name: &str, x0: &[f64], settings: Continuation, progress: &Progress) -> Option<Branch> {
+    let p0 = sys.parameter(name)?;
+    let problem = Problem { g: |x: &[f64], p: f64| with_parameter(sys, name, p).rhs(0.0, x), shift: 0.0, discrete: false };
+    let x = newton(|x| (problem.g)(x, p0), x0, 1e-10)?;
+    problem.run(&x, p0, settings, progress)
+}
+
+/// continues the period-`n` orbit of `map` through (or near) `x0`, re: [`continue_equilibria`].
+/// The eigenvalues are the orbit's multipliers, so a multiplier through -1 is a period doubling.
+///
+/// `None` as well when newton lands on an orbit whose minimal period is a divisor of `n`: every
+/// fixed point is also a fixed point of F^n, but continuing it isn't continuing a period-`n` orbit
+pub fn continue_map_orbits<S: Map + Parameters + Clone>(map: &S, name: &str, x0: &[f64], n: usize, settings: Continuation, progress: &Progress) -> Option<Branch> {
+    let p0 = map.parameter(name)?;
+    let iterate = |x: &[f64], p: f64, n: usize| {
+        let map = with_parameter(map, name, p);
+        (0..n).fold(x.to_vec(), |x, _| map.step(&x))
+    };
+    let g = |x: &[f64], p: f64| iterate(x, p, n).iter().zip(x).map(|(f, x)| f - x).collect::<Vec<f64>>();
+    let problem = Problem { g, shift: 1.0, discrete: true };
+    let x = newton(|x| (problem.g)(x, p0), x0, 1e-10)?;
+
+    let scale = x.iter().fold(1.0_f64, |m, x| m.max(x.abs()));
+    if (1..n).any(|d| n.is_multiple_of(d) && distance(&iterate(&x, p0, d), &x) < 1e-8 * scale) {
+        return None;
+    }
+    problem.run(&x, p0, settings, progress)
+}
+
+/// continues the periodic orbit of the autonomous flow `sys` through (or near) `x0` that crosses
+/// `section` `n` times, as a fixed point of the poincaré return map, re: [`flow_orbit`](crate::periodic_orbits::flow_orbit).
+/// Points are where the orbit crosses the section, with its period
+#[allow(clippy::too_many_arguments)]
+pub fn continue_flow_orbits<S: DynamicalSystem + Parameters + Clone>(sys: &S, name: &str, x0: &[f64], n: usize, section: Section, dt: f64, settings: Continuation, progress: &Progress) -> Option<Branch> {
+    let p0 = sys.parameter(name)?;
+    let g = |u: &[f64], p: f64| match return_map(&with_parameter(sys, name, p), u, n, section, dt) {
+        Some((v, _)) => v.iter().zip(u).map(|(v, u)| v - u).collect(),
+        None => vec![f64::NAN; u.len()]
+    };
+    let problem = Problem { g, shift: 1.0, discrete: true };
+
+    let (start, _) = next_crossing(sys, x0, 0.0, section, dt, 100.0)?;
+    let u = newton(|u| (problem.g)(u, p0), &off_section(&start, section), 1e-9)?;
+    let mut branch = problem.run(&u, p0, settings, progress)?;
+
+    // back to full states, with the periods
+    for point in branch.points.iter_mut() {
+        point.period = return_map(&with_parameter(sys, name, point.parameter), &point.state, n, section, dt).map(|(_, t)| t);
+        point.state = on_section(&point.state, section);
+    }
+    for special in branch.bifurcations.iter_mut() {
+        special.state = on_section(&special.state, section);
+    }
+    Some(branch)
+}
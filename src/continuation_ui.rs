@@ -0,0 +1,231 @@
+use chaos_lab::{
+    continuation::{continue_equilibria, continue_flow_orbits, continue_map_orbits, Branch, Continuation},
+    logistic_map::LogisticMap, lorenz::Lorenz, periodic_orbits::{find_flow_orbits, Section}, runner::Progress,
+    trajectory::Trajectory
+};
+use eframe::egui;
+use egui_plot::{Line, LineStyle, Plot, PlotPoint, PlotPoints, Points, Text};
+
+use crate::worker::Worker;
+
+/// what gets continued
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ContinuationCase {
+    /// the origin and C± of lorenz in ρ
+    LorenzEquilibria,
+    /// the AB periodic orbit of lorenz (once round each wing) in ρ
+    LorenzOrbit,
+    /// the logistic map's fixed point, 2-cycle and 4-cycle in r
+    LogisticCycles
+}
+
+impl ContinuationCase {
+    fn name(&self) -> &'static str {
+        match self {
+            ContinuationCase::LorenzEquilibria => "lorenz equilibria",
+            ContinuationCase::LorenzOrbit => "lorenz AB orbit",
+            ContinuationCase::LogisticCycles => "logistic cycles"
+        }
+    }
+
+    /// (parameter, what's plotted against it)
+    fn axes(&self) -> (&'static str, &'static str) {
+        match self {
+            ContinuationCase::LorenzEquilibria => ("ρ", "x"),
+            ContinuationCase::LorenzOrbit => ("ρ", "period"),
+            ContinuationCase::LogisticCycles => ("r", "x")
+        }
+    }
+}
+
+// where the AB orbit is followed, below C± (z = ρ - 1) so it keeps crossing it cleanly down to
+// the homoclinic orbit at ρ ≈ 13.93
+const SECTION: Section = Section { component: 2, value: 20.0 };
+
+/// pseudo-arclength continuation of equilibria and periodic orbits, with their bifurcations
+pub struct ContinuationPanel {
+    pub case: ContinuationCase,
+    pub lorenz: Lorenz,
+    /// upper end of the parameter range
+    pub max_parameter: f64,
+    pub max_step: f64,
+
+    computed: ContinuationCase,
+    branches: Vec<(String, Branch)>,
+    job: Option<Worker<(String, Branch)>>,
+    status: String
+}
+
+impl Default for ContinuationPanel {
+    fn default() -> Self {
+        ContinuationPanel {
+            case: ContinuationCase::LorenzEquilibria,
+            lorenz: Lorenz::default(),
+            max_parameter: 40.0,
+            max_step: 0.5,
+            computed: ContinuationCase::LorenzEquilibria,
+            branches: vec![],
+            job: None,
+            status: String::new()
+        }
+    }
+}
+
+/// the branches of `case`, each sent as soon as it's done
+fn continue_case(case: ContinuationCase, lorenz: Lorenz, max_parameter: f64, max_step: f64, progress: &Progress, send: &dyn Fn((String, Branch))) {
+    let settings = Continuation { max_step, max_points: 2000, range: (0.0, max_parameter), ..Continuation::default() };
+    match case {
+        ContinuationCase::LorenzEquilibria => {
+            // the origin from below the pitchfork, then C+ from the top down through it and out along C-
+            let origin = Lorenz { ro: 0.5, ..lorenz };
+            let Some(branch) = continue_equilibria(&origin, "ro", &[0.0, 0.0, 0.0], settings, progress) else { return };
+            send((String::from("origin"), branch));
+            let top = Lorenz { ro: max_parameter, ..lorenz };
+            let c = (top.beta * (top.ro - 1.0)).sqrt();
+            let settings = Continuation { step: -settings.step, ..settings };
+            let Some(branch) = continue_equilibria(&top, "ro", &[c, c, top.ro - 1.0], settings, progress) else { return };
+            send((String::from("C±"), branch));
+        }
+        ContinuationCase::LorenzOrbit => {
+            let start = Lorenz { ro: 28.0, ..lorenz };
+            let flow = Trajectory::integrate(&start, &[1.0, 1.0, 1.0], 0.01, 5, 1500, 200);
+            let orbits = find_flow_orbits(&start, &flow.states, 25..=40, 2.0, SECTION, 0.01);
+            let Some(ab) = orbits.iter().find(|o| o.n == 2) else { return };
+            for (name, step) in [("AB, down", -0.2), ("AB, up", 0.2)] {
+                let settings = Continuation { step, ..settings };
+                let Some(branch) = continue_flow_orbits(&start, "ro", &ab.points[0], 2, SECTION, 0.01, settings, progress) else { return };
+                send((String::from(name), branch));
+            }
+        }
+        ContinuationCase::LogisticCycles => {
+            // each cycle from where it's stable, both ways. Below r = 1 the fixed point runs off to -∞
+            let settings = Continuation { range: (0.9, max_parameter), ..settings };
+            for (n, r) in [(1, 2.0), (2, 3.2), (4, 3.5)] {
+                let map = LogisticMap { r };
+                let x0 = Trajectory::iterate(&map, &[0.3], 1, 1000).states[0].clone();
+                for (direction, step) in [("down", -0.05), ("up", 0.05)] {
+                    let settings = Continuation { step, ..settings };
+                    let Some(branch) = continue_map_orbits(&map, "r", &x0, n, settings, progress) else { return };
+                    send((format!("period {}, {}", n, direction), branch));
+                }
+            }
+        }
+    }
+}
+
+impl ContinuationPanel {
+    fn compute(&mut self, ctx: &egui::Context) {
+        let (case, lorenz, max_parameter, max_step) = (self.case, self.lorenz, self.max_parameter, self.max_step);
+        self.computed = case;
+        self.branches.clear();
+        self.status.clear();
+        self.job = Some(Worker::spawn(ctx, 0, move |progress, send| continue_case(case, lorenz, max_parameter, max_step, progress, send)));
+    }
+
+    fn poll(&mut self) {
+        let Some(job) = &self.job else { return };
+        let finished = job.is_finished();
+        self.branches.extend(job.poll());
+        if finished {
+            if self.branches.is_empty() && !job.progress.is_cancelled() {
+                self.status = String::from("nothing to continue from, no solution near the starting guess");
+            }
+            self.job = None;
+        }
+    }
+
+    pub fn ui(&mut self, ui: &mut egui::Ui) {
+        self.poll();
+
+        ui.horizontal(|ui| {
+            egui::ComboBox::from_label("continue")
+            .selected_text(self.case.name())
+            .show_ui(ui, |ui| {
+                for case in [ContinuationCase::LorenzEquilibria, ContinuationCase::LorenzOrbit, ContinuationCase::LogisticCycles] {
+                    if ui.selectable_value(&mut self.case, case, case.name()).changed() {
+                        self.max_parameter = if case == ContinuationCase::LogisticCycles { 4.0 } else { 40.0 };
+                    }
+                }
+            });
+            if self.case != ContinuationCase::LogisticCycles {
+                ui.label("sigma");
+                ui.add(egui::Slider::new(&mut self.lorenz.sigma, 1.0..=20.0));
+                ui.label("beta");
+                ui.add(egui::Slider::new(&mut self.lorenz.beta, 0.1..=5.0));
+            }
+            ui.label(format!("{} up to", self.case.axes().0));
+            let top = if self.case == ContinuationCase::LogisticCycles { 4.0 } else { 200.0 };
+            ui.add(egui::Slider::new(&mut self.max_parameter, 1.5..=top));
+            ui.label("max step");
+            ui.add(egui::Slider::new(&mut self.max_step, 0.001..=2.0).logarithmic(true));
+            if ui.button("Continue").clicked() {
+                self.compute(ui.ctx());
+            }
+        });
+
+        if let Some(job) = &self.job {
+            job.ui(ui);
+        }
+        ui.label(&self.status);
+        if self.branches.is_empty() {
+            return;
+        }
+
+        egui::Grid::new("bifurcations").striped(true).show(ui, |ui| {
+            ui.label("branch");
+            ui.label("");
+            ui.label(self.computed.axes().0);
+            ui.label("state");
+            ui.end_row();
+            for (name, branch) in &self.branches {
+                for special in &branch.bifurcations {
+                    ui.label(name);
+                    ui.label(special.kind.label());
+                    ui.label(format!("{:.6}", special.parameter));
+                    ui.label(special.state.iter().map(|x| format!("{:.4}", x)).collect::<Vec<_>>().join(", "));
+                    ui.end_row();
+                }
+            }
+        });
+        ui.label("solid: stable, dashed: unstable. LP fold, BP branch point, H hopf, PD period doubling, NS neimark-sacker");
+
+        let orbit = self.computed == ContinuationCase::LorenzOrbit;
+        let (x_label, y_label) = self.computed.axes();
+        Plot::new("continuation plot")
+        .view_aspect(1.5)
+        .x_axis_label(x_label)
+        .y_axis_label(y_label)
+        .legend(egui_plot::Legend::default())
+        .show(ui, |plot_ui| {
+            for (name, branch) in &self.branches {
+                let measure = |state: &[f64], period: Option<f64>| if orbit { period.unwrap_or(f64::NAN) } else { state[0] };
+                // one line per stretch of the same stability, sharing the point where it changes
+                let mut start = 0;
+                while start + 1 < branch.points.len() {
+                    let stable = branch.points[start + 1].stable;
+                    let mut end = start + 1;
+                    while end + 1 < branch.points.len() && branch.points[end + 1].stable == stable {
+                        end += 1;
+                    }
+                    let line: PlotPoints<'_> = branch.points[start..=end].iter().map(|p| [p.parameter, measure(&p.state, p.period)]).collect();
+                    let style = if stable { LineStyle::Solid } else { LineStyle::dashed_loose() };
+                    let color = if stable { egui::Color32::from_rgb(60, 100, 200) } else { egui::Color32::from_rgb(220, 80, 60) };
+                    plot_ui.line(Line::new(name.clone(), line).style(style).color(color).width(2.0));
+                    start = end;
+                }
+                for special in &branch.bifurcations {
+                    // flow orbits only keep the period of the computed points, take the closest one.
+                    // The branch can turn back at a fold, so it isn't ordered by parameter
+                    let y = if orbit {
+                        let nearest = branch.points.iter().min_by(|a, b| (a.parameter - special.parameter).abs().total_cmp(&(b.parameter - special.parameter).abs()));
+                        measure(&[], nearest.and_then(|p| p.period))
+                    } else {
+                        special.state[0]
+                    };
+                    plot_ui.points(Points::new(special.kind.label(), vec![[special.parameter, y]]).radius(5.0).color(egui::Color32::BLACK));
+                    plot_ui.text(Text::new(special.kind.label(), PlotPoint::new(special.parameter, y), format!("  {}", special.kind.label())).anchor(egui::Align2::LEFT_BOTTOM));
+                }
+            }
+        });
+    }
+}
@@ -0,0 +1,49 @@
+use crate::{dynamical_system::{Map, Parameters}, logistic_map::LogisticMap};
+
+/// a ring of logistic maps, each one diffusively coupled to its two neighbours
+/// ```latex
+/// x_i′ = (1 - ε) f(x_i) + (ε / 2) (f(x_{i-1}) + f(x_{i+1}))
+/// ```
+/// with ε = 0 the sites are independent logistic maps, as ε grows they start to synchronise
+/// and patterns (frozen domains, travelling defects, turbulence) show up in space-time
+#[derive(Debug, Clone, Copy)]
+pub struct CoupledMapLattice {
+    pub sites: usize,
+    pub local: LogisticMap,
+    pub epsilon: f64
+}
+
+impl Map for CoupledMapLattice {
+    fn dimension(&self) -> usize {
+        self.sites
+    }
+
+    fn step(&self, state: &[f64]) -> Vec<f64> {
+        let n = self.sites;
+        let f: Vec<f64> = state.iter().map(|x| self.local.step(&[*x])[0]).collect();
+        (0..n).map(|i| {
+            ((1.0 - self.epsilon) * f[i]) + (0.5 * self.epsilon * (f[(i + n - 1) % n] + f[(i + 1) % n]))
+        }).collect()
+    }
+}
+
+impl Parameters for CoupledMapLattice {
+    fn parameters(&self) -> Vec<(String, f64)> {
+        vec![(String::from("r"), self.local.r), (String::from("epsilon"), self.epsilon)]
+    }
+
+    fn set_parameter(&mut self, name: &str, value: f64) -> bool {
+        match name {
+            "r" => self.local.r = value,
+            "epsilon" => self.epsilon = value,
+            _ => return false
+        }
+        true
+    }
+}
+
+impl Default for CoupledMapLattice {
+    fn default() -> Self {
+        Self { sites: 100, local: LogisticMap { r: 3.9 }, epsilon: 0.3 }
+    }
+}
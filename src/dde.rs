@@ -0,0 +1,242 @@
+use std::collections::VecDeque;
+
+use crate::dynamical_system::Parameters;
+
+/// a delay differential equation
+/// ```latex
+/// x′(t) = f(t, x(t), x(t - τ_1), ..., x(t - τ_k))
+/// ```
+/// the derivative depends on where the system *was*, not just where it is. Knowing x(t) isn't
+/// enough to carry on: we need the whole history over [t - max τ, t], which makes these
+/// systems infinite dimensional even when `dimension` is 1
+pub trait DelaySystem {
+    fn dimension(&self) -> usize;
+    fn delays(&self) -> Vec<f64>; // every τ the rhs looks back by
+    /// `delayed[k]` is the state at `t - delays()[k]`
+    fn rhs(&self, t: f64, state: &[f64], delayed: &[Vec<f64>]) -> Vec<f64>;
+}
+
+/// past states on a uniform time grid, with the derivative at every node so we can do
+/// cubic hermite interpolation between them.
+///
+/// before the start time the history is the constant initial state. Nodes older than the
+/// longest delay are dropped as we go
+pub struct History {
+    dt: f64,
+    max_delay: f64,
+    // number of nodes dropped off the front, node k is at time (dropped + k) * dt
+    dropped: usize,
+    initial: Vec<f64>,
+    states: VecDeque<Vec<f64>>,
+    derivs: VecDeque<Vec<f64>>
+}
+
+impl History {
+    /// constant history `x(t) = initial` for `t <= 0`, stepped forward in steps of `dt`
+    pub fn new(sys: &impl DelaySystem, initial: &[f64], dt: f64) -> Self {
+        let delays = sys.delays();
+        let lagged = vec![initial.to_vec(); delays.len()];
+        let deriv = sys.rhs(0.0, initial, &lagged);
+
+        History {
+            dt,
+            max_delay: delays.iter().cloned().fold(0.0, f64::max),
+            dropped: 0,
+            initial: initial.to_vec(),
+            states: VecDeque::from([initial.to_vec()]),
+            derivs: VecDeque::from([deriv])
+        }
+    }
+
+    pub fn dt(&self) -> f64 {
+        self.dt
+    }
+
+    /// time of the newest node
+    pub fn time(&self) -> f64 {
+        (self.dropped + self.states.len() - 1) as f64 * self.dt
+    }
+
+    /// newest state
+    pub fn current(&self) -> &[f64] {
+        self.states.back().expect("history is never empty")
+    }
+
+    /// state at any past time `t`
+    pub fn at(&self, t: f64) -> Vec<f64> {
+        if t <= 0.0 {
+            return self.initial.clone();
+        }
+
+        let pos = (t / self.dt) - self.dropped as f64;
+        let last = self.states.len() - 1;
+        if pos < 0.0 {
+            // asked further back than we kept, shouldn't happen when the delays are fixed
+            return self.states[0].clone();
+        }
+
+        // ahead of the newest node (delay shorter than dt): extrapolate along the last derivative
+        let k = pos.floor() as usize;
+        if k >= last {
+            let h = t - self.time();
+            return self.states[last].iter().zip(&self.derivs[last]).map(|(x, d)| x + (h * d)).collect();
+        }
+
+        // cubic hermite on [t_k, t_k+1], s in [0,1]
+        let s = pos - k as f64;
+        let h00 = (2.0 * s * s * s) - (3.0 * s * s) + 1.0;
+        let h10 = (s * s * s) - (2.0 * s * s) + s;
+        let h01 = (-2.0 * s * s * s) + (3.0 * s * s);
+        let h11 = (s * s * s) - (s * s);
+
+        (0..self.initial.len()).map(|i| {
+            (h00 * self.states[k][i])
+            + (h10 * self.dt * self.derivs[k][i])
+            + (h01 * self.states[k + 1][i])
+            + (h11 * self.dt * self.derivs[k + 1][i])
+        }).collect()
+    }
+
+    fn push(&mut self, state: Vec<f64>, deriv: Vec<f64>) {
+        self.states.push_back(state);
+        self.derivs.push_back(deriv);
+
+        // keep a couple of spare nodes past the longest delay for the interpolation
+        let keep = (self.max_delay / self.dt).ceil() as usize + 3;
+        while self.states.len() > keep {
+            self.states.pop_front();
+            self.derivs.pop_front();
+            self.dropped += 1;
+        }
+    }
+}
+
+/// rk4 for delay equations via the method of steps: on each step the delayed states are already
+/// known (they're in the history), so the dde is just an ode in the current state and we can
+/// take an ordinary rk4 step, reading the lagged values off the interpolated history
+pub fn dde_rk4_step(sys: &impl DelaySystem, history: &mut History) {
+    let t = history.time();
+    let dt = history.dt();
+    let delays = sys.delays();
+    let lagged = |history: &History, s: f64| -> Vec<Vec<f64>> {
+        delays.iter().map(|tau| history.at(s - tau)).collect()
+    };
+    let x = history.current().to_vec();
+    let advance = |k: &[f64], h: f64| -> Vec<f64> {
+        x.iter().zip(k).map(|(s, k)| s + (k * h)).collect()
+    };
+
+    let k1 = sys.rhs(t, &x, &lagged(history, t));
+    let k2 = sys.rhs(t + (0.5 * dt), &advance(&k1, 0.5 * dt), &lagged(history, t + (0.5 * dt)));
+    let k3 = sys.rhs(t + (0.5 * dt), &advance(&k2, 0.5 * dt), &lagged(history, t + (0.5 * dt)));
+    let k4 = sys.rhs(t + dt, &advance(&k3, dt), &lagged(history, t + dt));
+
+    let next: Vec<f64> = (0..x.len()).map(|i| {
+        x[i] + ((dt / 6.0) * (k1[i] + (2.0 * k2[i]) + (2.0 * k3[i]) + k4[i]))
+    }).collect();
+
+    // derivative at the new node, for interpolating across it later
+    let deriv = sys.rhs(t + dt, &next, &lagged(history, t + dt));
+    history.push(next, deriv);
+}
+
+/// blood cell production model, the textbook example of delay-induced chaos
+/// ```latex
+/// x′ = β x(t-τ) / (1 + x(t-τ)^n) - γ x
+/// ```
+/// chaotic for the defaults (τ = 17); try τ below ~7 for a limit cycle
+#[derive(Debug, Clone, Copy)]
+pub struct MackeyGlass {
+    pub beta: f64,
+    pub gamma: f64,
+    pub n: f64,
+    pub tau: f64
+}
+
+impl DelaySystem for MackeyGlass {
+    fn dimension(&self) -> usize {
+        1
+    }
+
+    fn delays(&self) -> Vec<f64> {
+        vec![self.tau]
+    }
+
+    fn rhs(&self, _t: f64, state: &[f64], delayed: &[Vec<f64>]) -> Vec<f64> {
+        let lag = delayed[0][0];
+        vec![(self.beta * lag / (1.0 + lag.powf(self.n))) - (self.gamma * state[0])]
+    }
+}
+
+impl Parameters for MackeyGlass {
+    fn parameters(&self) -> Vec<(String, f64)> {
+        vec![
+            (String::from("beta"), self.beta),
+            (String::from("gamma"), self.gamma),
+            (String::from("n"), self.n),
+            (String::from("tau"), self.tau)
+        ]
+    }
+
+    fn set_parameter(&mut self, name: &str, value: f64) -> bool {
+        match name {
+            "beta" => self.beta = value,
+            "gamma" => self.gamma = value,
+            "n" => self.n = value,
+            "tau" => self.tau = value,
+            _ => return false
+        }
+        true
+    }
+}
+
+impl Default for MackeyGlass {
+    fn default() -> Self {
+        Self { beta: 0.2, gamma: 0.1, n: 10.0, tau: 17.0 }
+    }
+}
+
+/// delayed feedback through a nonlinear (sine) element, from ikeda's optical ring cavity
+/// ```latex
+/// x′ = -x + μ sin(x(t-τ))
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct IkedaDelay {
+    pub mu: f64,
+    pub tau: f64
+}
+
+impl DelaySystem for IkedaDelay {
+    fn dimension(&self) -> usize {
+        1
+    }
+
+    fn delays(&self) -> Vec<f64> {
+        vec![self.tau]
+    }
+
+    fn rhs(&self, _t: f64, state: &[f64], delayed: &[Vec<f64>]) -> Vec<f64> {
+        vec![-state[0] + (self.mu * delayed[0][0].sin())]
+    }
+}
+
+impl Parameters for IkedaDelay {
+    fn parameters(&self) -> Vec<(String, f64)> {
+        vec![(String::from("mu"), self.mu), (String::from("tau"), self.tau)]
+    }
+
+    fn set_parameter(&mut self, name: &str, value: f64) -> bool {
+        match name {
+            "mu" => self.mu = value,
+            "tau" => self.tau = value,
+            _ => return false
+        }
+        true
+    }
+}
+
+impl Default for IkedaDelay {
+    fn default() -> Self {
+        Self { mu: 20.0, tau: 5.0 }
+    }
+}
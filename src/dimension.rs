@@ -0,0 +1,200 @@
+use crate::math::linear_fit;
+
+/// a log-log curve and the straight line fitted to its scaling region.
+/// The slope is the dimension estimate, `log_x[start..end]` are the points that were fitted
+#[derive(Debug, Clone)]
+pub struct ScalingFit {
+    pub log_x: Vec<f64>,
+    pub log_y: Vec<f64>,
+    pub start: usize,
+    pub end: usize,
+    pub slope: f64,
+    pub intercept: f64
+}
+
+impl ScalingFit {
+    /// picks the scaling region automatically and fits it.
+    ///
+    /// real curves only follow a power law over some middle range (too small and there's no data
+    /// or only noise, too big and the attractor's finite size takes over), so we look at the local
+    /// slopes and take the stretch of at least `min_points` where they are flattest, then grow it
+    /// in both directions for as long as the local slope stays within 5% of the plateau.
+    /// None with fewer than two points, there's no line through them
+    pub fn fit(log_x: Vec<f64>, log_y: Vec<f64>, min_points: usize) -> Option<Self> {
+        let n = log_x.len();
+        if n < 2 {
+            return None;
+        }
+        let min_points = min_points.clamp(2, n.max(2));
+
+        // slope between each pair of neighbouring points
+        let local: Vec<f64> = (1..n).map(|i| (log_y[i] - log_y[i - 1]) / (log_x[i] - log_x[i - 1])).collect();
+
+        // window of local slopes with the smallest spread. A window of w slopes covers w + 1 points
+        let w = (min_points - 1).min(local.len()).max(1);
+        let mut best = (0, f64::MAX);
+        for start in 0..=(local.len().saturating_sub(w)) {
+            let window = &local[start..(start + w).min(local.len())];
+            let mean = window.iter().sum::<f64>() / window.len() as f64;
+            let spread = window.iter().map(|s| (s - mean) * (s - mean)).sum::<f64>().sqrt();
+            // relative spread, so a flat plateau at large r (slope ~0) doesn't win by default
+            let score = spread / mean.abs().max(1e-12);
+            if score < best.1 {
+                best = (start, score);
+            }
+        }
+
+        let (mut lo, mut hi) = (best.0, (best.0 + w).min(local.len()));
+        let plateau = local[lo..hi].iter().sum::<f64>() / (hi - lo).max(1) as f64;
+        let close = |s: f64| (s - plateau).abs() <= 0.05 * plateau.abs();
+        while lo > 0 && close(local[lo - 1]) {
+            lo -= 1;
+        }
+        while hi < local.len() && close(local[hi]) {
+            hi += 1;
+        }
+
+        // slopes lo..hi span points lo..=hi
+        let (start, end) = (lo, (hi + 1).min(n));
+        let (slope, intercept) = linear_fit(&log_x[start..end], &log_y[start..end]);
+
+        Some(ScalingFit { log_x, log_y, start, end, slope, intercept })
+    }
+
+    /// fits points `start..end`, for when the scaling region is picked by hand. None if that
+    /// leaves fewer than two points
+    pub fn fit_range(log_x: Vec<f64>, log_y: Vec<f64>, start: usize, end: usize) -> Option<Self> {
+        let end = end.min(log_x.len());
+        let start = start.min(end.saturating_sub(2));
+        if end < start + 2 {
+            return None;
+        }
+        let (slope, intercept) = linear_fit(&log_x[start..end], &log_y[start..end]);
+        Some(ScalingFit { log_x, log_y, start, end, slope, intercept })
+    }
+}
+
+/// `count` radii evenly spaced in log between `r_min` and `r_max`
+pub fn log_spaced(r_min: f64, r_max: f64, count: usize) -> Vec<f64> {
+    let (a, b) = (r_min.ln(), r_max.ln());
+    (0..count).map(|k| (a + ((b - a) * k as f64 / (count - 1).max(1) as f64)).exp()).collect()
+}
+
+/// length of the diagonal of the points' bounding box, an upper bound on the attractor's size
+pub fn diameter(points: &[Vec<f64>]) -> f64 {
+    let dim = points.first().map_or(0, |p| p.len());
+    (0..dim).map(|i| {
+        let (lo, hi) = points.iter().fold((f64::MAX, f64::MIN), |(lo, hi), p| (lo.min(p[i]), hi.max(p[i])));
+        (hi - lo) * (hi - lo)
+    }).sum::<f64>().sqrt()
+}
+
+/// grassberger–procaccia correlation sum
+/// ```latex
+/// C(r) = (number of pairs closer than r) / (number of pairs)
+/// ```
+/// pairs less than `theiler` samples apart in time are skipped: along a flow consecutive
+/// samples are close just because they're consecutive, which would fake a dimension of 1.
+///
+/// `radii` must be increasing
+pub fn correlation_sum(points: &[Vec<f64>], radii: &[f64], theiler: usize) -> Vec<f64> {
+    let radii_sq: Vec<f64> = radii.iter().map(|r| r * r).collect();
+    // counts[k] = pairs whose distance falls in [r_{k-1}, r_k)
+    let mut counts = vec![0_u64; radii.len() + 1];
+    let mut pairs = 0_u64;
+
+    for i in 0..points.len() {
+        for j in (i + theiler + 1)..points.len() {
+            let d_sq: f64 = points[i].iter().zip(&points[j]).map(|(a, b)| (a - b) * (a - b)).sum();
+            // first radius the pair fits inside
+            let k = radii_sq.partition_point(|r| *r <= d_sq);
+            counts[k] += 1;
+            pairs += 1;
+        }
+    }
+
+    let mut cumulative = 0;
+    radii.iter().enumerate().map(|(k, _)| {
+        cumulative += counts[k];
+        cumulative as f64 / pairs.max(1) as f64
+    }).collect()
+}
+
+/// correlation dimension D2: the slope of log C(r) against log r in the scaling region.
+///
+/// radii run over three decades below the attractor's diameter; radii with fewer than ~50 pairs
+/// inside are dropped before fitting, there the curve is mostly counting noise. Too few points
+/// leave nothing to fit and give None
+pub fn correlation_dimension(points: &[Vec<f64>], theiler: usize) -> Option<ScalingFit> {
+    let r_max = diameter(points);
+    let radii = log_spaced(r_max * 1e-3, r_max, 40);
+    let c = correlation_sum(points, &radii, theiler);
+
+    let n = points.len().saturating_sub(theiler) as f64;
+    let min_c = 50.0 / (0.5 * n * n).max(1.0);
+
+    let (log_x, log_y): (Vec<f64>, Vec<f64>) = radii.iter()
+    .zip(&c)
+    .filter(|(_, c)| **c >= min_c)
+    .map(|(r, c)| (r.ln(), c.ln()))
+    .unzip();
+
+    ScalingFit::fit(log_x, log_y, 8)
+}
+
+/// how a point set fills a grid of boxes of side `epsilon`
+#[derive(Debug, Clone, Copy)]
+pub struct BoxCount {
+    pub epsilon: f64,
+    /// number of boxes with at least one point in them, N(ε)
+    pub boxes: usize,
+    /// shannon entropy of the fraction of points in each box, I(ε) = -Σ p ln p
+    pub information: f64
+}
+
+/// covers the points with grids of side `epsilon` and counts the occupied boxes.
+///
+/// only occupied boxes are stored (hashed by their integer grid coordinates), so this works in any
+/// dimension without allocating the whole grid
+pub fn box_counts(points: &[Vec<f64>], epsilons: &[f64]) -> Vec<BoxCount> {
+    let n = points.len() as f64;
+    epsilons.iter().map(|eps| {
+        let mut occupancy: std::collections::HashMap<Vec<i64>, usize> = std::collections::HashMap::new();
+        for p in points {
+            let cell: Vec<i64> = p.iter().map(|x| (x / eps).floor() as i64).collect();
+            *occupancy.entry(cell).or_insert(0) += 1;
+        }
+
+        let information = occupancy.values().map(|count| {
+            let p = *count as f64 / n;
+            -p * p.ln()
+        }).sum();
+
+        BoxCount { epsilon: *eps, boxes: occupancy.len(), information }
+    }).collect()
+}
+
+// box sizes halving down from the diameter, stopping once the boxes get so small that
+// (on average) each point has one to itself, below that N(ε) just counts points
+fn box_scales(points: &[Vec<f64>]) -> Vec<BoxCount> {
+    let d = diameter(points);
+    let epsilons: Vec<f64> = (1..=24).map(|k| d * 0.5_f64.powf(0.5 * k as f64)).collect();
+    box_counts(points, &epsilons)
+    .into_iter()
+    .take_while(|b| b.boxes * 10 < points.len())
+    .collect()
+}
+
+/// box-counting (capacity) dimension D0: slope of log N(ε) against log(1/ε). None when there
+/// are too few points for more than one box size, re: `box_scales`
+pub fn box_counting_dimension(points: &[Vec<f64>]) -> Option<ScalingFit> {
+    let (log_x, log_y) = box_scales(points).iter().map(|b| (-b.epsilon.ln(), (b.boxes as f64).ln())).unzip();
+    ScalingFit::fit(log_x, log_y, 5)
+}
+
+/// information dimension D1: slope of I(ε) against log(1/ε). Weights boxes by how often
+/// the trajectory visits them, so it's ≤ D0 and less sensitive to rarely visited corners
+pub fn information_dimension(points: &[Vec<f64>]) -> Option<ScalingFit> {
+    let (log_x, log_y) = box_scales(points).iter().map(|b| (-b.epsilon.ln(), b.information)).unzip();
+    ScalingFit::fit(log_x, log_y, 5)
+}
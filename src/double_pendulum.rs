@@ -1,4 +1,6 @@
-use crate::{dynamical_system::DynamicalSystem};
+use crate::dynamical_system::{DynamicalSystem, Parameters};
+
+#[derive(Debug, Clone, Copy)]
 pub struct DoublePendulum {
     pub m1: f64,
     pub m2: f64,
@@ -34,6 +36,28 @@ impl DynamicalSystem for DoublePendulum {
     }
 }
 
+impl Parameters for DoublePendulum {
+    fn parameters(&self) -> Vec<(String, f64)> {
+        vec![
+            (String::from("m1"), self.m1),
+            (String::from("m2"), self.m2),
+            (String::from("l1"), self.l1),
+            (String::from("l2"), self.l2)
+        ]
+    }
+
+    fn set_parameter(&mut self, name: &str, value: f64) -> bool {
+        match name {
+            "m1" => self.m1 = value,
+            "m2" => self.m2 = value,
+            "l1" => self.l1 = value,
+            "l2" => self.l2 = value,
+            _ => return false
+        }
+        true
+    }
+}
+
 impl Default for DoublePendulum {
     fn default() -> Self {
         Self { m1: 1.0, m2: 1.0, l1: 1.0, l2: 1.0 }
@@ -0,0 +1,70 @@
+use crate::dynamical_system::{DynamicalSystem, Parameters};
+
+/// forced, damped duffing oscillator
+/// ```latex
+/// x″ + δ x′ + α x + β x³ = γ cos(ω t)
+/// ```
+/// with α < 0 < β the potential has two wells at x = ±sqrt(-α/β), a ball rolling in a
+/// double dip being shaken sideways. Weak forcing leaves one periodic attractor in each well
+/// with tangled (fractal) basins between them; stronger forcing gives a chaotic attractor
+/// hopping between the wells. The state is [x, x′]
+#[derive(Debug, Clone, Copy)]
+pub struct Duffing {
+    pub alpha: f64,
+    pub beta: f64,
+    pub delta: f64,
+    pub gamma: f64,
+    pub omega: f64
+}
+
+impl Duffing {
+    /// one period of the forcing
+    pub fn period(&self) -> f64 {
+        2.0 * std::f64::consts::PI / self.omega
+    }
+}
+
+impl DynamicalSystem for Duffing {
+    fn dimension(&self) -> usize {
+        2
+    }
+
+    fn rhs(&self, t: f64, state: &[f64]) -> Vec<f64> {
+        let (x, v) = (state[0], state[1]);
+        vec![
+            v,
+            (self.gamma * (self.omega * t).cos()) - (self.delta * v) - (self.alpha * x) - (self.beta * x * x * x)
+        ]
+    }
+}
+
+impl Parameters for Duffing {
+    fn parameters(&self) -> Vec<(String, f64)> {
+        vec![
+            (String::from("alpha"), self.alpha),
+            (String::from("beta"), self.beta),
+            (String::from("delta"), self.delta),
+            (String::from("gamma"), self.gamma),
+            (String::from("omega"), self.omega)
+        ]
+    }
+
+    fn set_parameter(&mut self, name: &str, value: f64) -> bool {
+        match name {
+            "alpha" => self.alpha = value,
+            "beta" => self.beta = value,
+            "delta" => self.delta = value,
+            "gamma" => self.gamma = value,
+            "omega" => self.omega = value,
+            _ => return false
+        }
+        true
+    }
+}
+
+impl Default for Duffing {
+    /// two wells at x = ±1, forced gently enough that each keeps its own attractor
+    fn default() -> Self {
+        Self { alpha: -1.0, beta: 1.0, delta: 0.25, gamma: 0.1, omega: 1.0 }
+    }
+}
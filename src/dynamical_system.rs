@@ -5,8 +5,47 @@ pub trait DynamicalSystem {
     fn rhs(&self, t: f64, state: &[f64]) -> Vec<f64>; // returns the next state of the system given the rules (ode's) of the system
 }
 
-pub trait Noise {
-    fn new_noisy(&self, noise_level: f64) -> Self;
+/// discrete time systems, iterated rather than integrated
+/// ```latex
+/// x_{n+1} = F(x_n)
+/// ```
+pub trait Map {
+    fn dimension(&self) -> usize;
+    fn step(&self, state: &[f64]) -> Vec<f64>; // the next state, F(x_n)
 }
 
+/// named parameters of a system, so generic tools (noise, sweeps, ...) can tweak them without knowing the struct
+pub trait Parameters {
+    fn parameters(&self) -> Vec<(String, f64)>; // every (name, current value)
+    fn set_parameter(&mut self, name: &str, value: f64) -> bool; // false if there's no parameter with that name
+
+    fn parameter(&self, name: &str) -> Option<f64> {
+        self.parameters().into_iter().find(|(n, _)| n == name).map(|(_, v)| v)
+    }
+}
+
+/// noise term of a stochastic differential equation
+/// ```latex
+/// dx = f(t,x) dt + g(t,x) dW
+/// ```
+/// `rhs` is the drift `f`, `diffusion` is `g`. The noise is diagonal: every component
+/// gets its own independent wiener process scaled by its own `g_i`
+pub trait Diffusion: DynamicalSystem {
+    fn diffusion(&self, t: f64, state: &[f64]) -> Vec<f64>;
+
+    /// `dg_i/dx_i` for each component, needed by the milstein scheme.
+    /// defaults to central differences, override when the derivative is known
+    fn diffusion_derivative(&self, t: f64, state: &[f64]) -> Vec<f64> {
+        let h = 1e-6;
+        let mut probe = state.to_vec();
+        (0..state.len()).map(|i| {
+            probe[i] = state[i] + h;
+            let up = self.diffusion(t, &probe)[i];
+            probe[i] = state[i] - h;
+            let down = self.diffusion(t, &probe)[i];
+            probe[i] = state[i];
+            (up - down) / (2.0 * h)
+        }).collect()
+    }
+}
 
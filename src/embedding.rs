@@ -0,0 +1,112 @@
+use crate::math::distance;
+
+/// takens delay embedding of a scalar series: the points
+/// ```latex
+/// y_i = (s_i, s_{i+τ}, s_{i+2τ}, ..., s_{i+(m-1)τ})
+/// ```
+/// for every i that fits. For a generic observable of a system on an attractor of dimension d,
+/// any m > 2d gives a faithful (diffeomorphic) copy of the attractor, so dimensions, exponents
+/// and recurrences can all be measured from the one series
+pub fn delay_embed(signal: &[f64], dimension: usize, delay: usize) -> Vec<Vec<f64>> {
+    let span = (dimension.max(1) - 1) * delay;
+    if signal.len() <= span {
+        return vec![];
+    }
+    (0..(signal.len() - span)).map(|i| (0..dimension.max(1)).map(|k| signal[i + (k * delay)]).collect()).collect()
+}
+
+/// average mutual information between the series and itself `delay` samples later, for every
+/// delay up to `max_delay`, from a `bins` × `bins` histogram
+/// ```latex
+/// I(τ) = Σ_{ij} p_ij(τ) ln(p_ij(τ) / (p_i p_j))
+/// ```
+/// the nats of information s_i gives about s_{i+τ}. Its first minimum is the usual embedding
+/// delay: coordinates as independent as they get without the delay being so long that chaos has
+/// made them unrelated. Unlike the autocorrelation it also sees nonlinear dependence
+pub fn average_mutual_information(signal: &[f64], max_delay: usize, bins: usize) -> Vec<f64> {
+    let bins = bins.max(1);
+    let lo = signal.iter().copied().fold(f64::INFINITY, f64::min);
+    let hi = signal.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    let width = if hi > lo { (hi - lo) / bins as f64 } else { 1.0 };
+    let bin: Vec<usize> = signal.iter().map(|s| (((s - lo) / width) as usize).min(bins - 1)).collect();
+
+    (0..=max_delay.min(signal.len().saturating_sub(1))).map(|delay| {
+        let n = signal.len() - delay;
+        let mut joint = vec![0_usize; bins * bins];
+        let mut first = vec![0_usize; bins];
+        let mut second = vec![0_usize; bins];
+        for i in 0..n {
+            joint[(bin[i] * bins) + bin[i + delay]] += 1;
+            first[bin[i]] += 1;
+            second[bin[i + delay]] += 1;
+        }
+
+        let n = n as f64;
+        joint.iter().enumerate().filter(|(_, count)| **count > 0).map(|(k, count)| {
+            let p = *count as f64 / n;
+            let (p_i, p_j) = (first[k / bins] as f64 / n, second[k % bins] as f64 / n);
+            p * (p / (p_i * p_j)).ln()
+        }).sum()
+    }).collect()
+}
+
+/// index of the first local minimum of `values`, re: [`average_mutual_information`].
+///
+/// a map's mutual information usually has no real minimum, it decays steadily into the noise
+/// floor and whatever dip turns up there means nothing. Maps are embedded with delay 1
+pub fn first_minimum(values: &[f64]) -> Option<usize> {
+    (1..values.len().saturating_sub(1)).find(|k| values[*k] < values[k - 1] && values[*k] <= values[k + 1])
+}
+
+/// fraction of false nearest neighbours in embedding dimensions 1 ..= `max_dimension`
+/// (kennel, brown & abarbanel).
+///
+/// in too few dimensions the attractor is folded onto itself and points that are far apart look
+/// like neighbours. Adding the next delay coordinate pulls such false neighbours apart, a
+/// neighbour at distance R_m counts as false if
+/// ```latex
+/// |s_{i+mτ} - s_{j+mτ}| / R_m > r_tol        (r_tol = 15)
+/// R_{m+1} / σ > a_tol                        (a_tol = 2, σ the series' standard deviation)
+/// ```
+/// the embedding dimension is where the fraction drops to (about) zero, re: [`embedding_dimension`].
+/// Neighbours closer than `theiler` samples in time are skipped, they're close because they're
+/// the same stretch of trajectory
+pub fn false_nearest_neighbours(signal: &[f64], delay: usize, max_dimension: usize, theiler: usize) -> Vec<f64> {
+    let (r_tol, a_tol) = (15.0, 2.0);
+    let n = signal.len() as f64;
+    let mean = signal.iter().sum::<f64>() / n;
+    let sigma = (signal.iter().map(|s| (s - mean) * (s - mean)).sum::<f64>() / n).sqrt();
+
+    (1..=max_dimension).map(|m| {
+        // points that also have an (m + 1)-th coordinate
+        let extended = delay_embed(signal, m + 1, delay);
+        let points: Vec<&[f64]> = extended.iter().map(|p| &p[..m]).collect();
+        if points.len() < 2 {
+            return f64::NAN;
+        }
+
+        let mut checked = 0;
+        let mut false_neighbours = 0;
+        for (i, p) in points.iter().enumerate() {
+            let nearest = points.iter().enumerate()
+            .filter(|(j, _)| i.abs_diff(*j) > theiler)
+            .map(|(j, q)| (j, distance(p, q)))
+            .filter(|(_, d)| *d > 0.0)
+            .min_by(|a, b| a.1.total_cmp(&b.1));
+            let Some((j, r)) = nearest else { continue };
+
+            let extra = (extended[i][m] - extended[j][m]).abs();
+            checked += 1;
+            if extra / r > r_tol || (r * r + extra * extra).sqrt() / sigma > a_tol {
+                false_neighbours += 1;
+            }
+        }
+        if checked == 0 { f64::NAN } else { false_neighbours as f64 / checked as f64 }
+    }).collect()
+}
+
+/// smallest dimension whose fraction of false nearest neighbours is at most `threshold`
+/// (0.01 is usual), `fractions[0]` being dimension 1
+pub fn embedding_dimension(fractions: &[f64], threshold: f64) -> Option<usize> {
+    fractions.iter().position(|f| *f <= threshold).map(|k| k + 1)
+}
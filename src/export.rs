@@ -0,0 +1,68 @@
+use std::{error::Error, path::Path};
+
+use crate::heatmap::Heatmap;
+
+/// writes a space-time field as csv: a header row of the grid positions, then one row per
+/// time (first column) with the field's value at every position
+pub fn write_space_time_csv(path: impl AsRef<Path>, positions: &[f64], times: &[f64], rows: &[Vec<f64>]) -> Result<(), Box<dyn Error>> {
+    let mut writer = csv::Writer::from_path(path)?;
+
+    let mut header = vec![String::from("t")];
+    header.extend(positions.iter().map(|x| format!("x={}", x)));
+    writer.write_record(&header)?;
+
+    for (t, row) in times.iter().zip(rows) {
+        let mut record = vec![t.to_string()];
+        record.extend(row.iter().map(|u| u.to_string()));
+        writer.write_record(&record)?;
+    }
+
+    writer.flush()?;
+    Ok(())
+}
+
+/// writes a heatmap as an 8 bit rgb png, one pixel per cell
+pub fn write_png(path: impl AsRef<Path>, heatmap: &Heatmap) -> Result<(), Box<dyn Error>> {
+    let file = std::io::BufWriter::new(std::fs::File::create(path)?);
+    let mut encoder = png::Encoder::new(file, heatmap.width as u32, heatmap.height as u32);
+    encoder.set_color(png::ColorType::Rgb);
+    encoder.set_depth(png::BitDepth::Eight);
+
+    let data: Vec<u8> = heatmap.pixels.iter().flatten().copied().collect();
+    encoder.write_header()?.write_image_data(&data)?;
+    Ok(())
+}
+
+/// writes a one parameter sweep as csv, a `name` column followed by one column per entry of `columns`
+pub fn write_sweep_csv(path: impl AsRef<Path>, name: &str, values: &[f64], columns: &[(&str, &[f64])]) -> Result<(), Box<dyn Error>> {
+    let mut writer = csv::Writer::from_path(path)?;
+
+    let mut header = vec![name];
+    header.extend(columns.iter().map(|(column, _)| *column));
+    writer.write_record(&header)?;
+
+    for (i, value) in values.iter().enumerate() {
+        let mut record = vec![value.to_string()];
+        record.extend(columns.iter().map(|(_, data)| data[i].to_string()));
+        writer.write_record(&record)?;
+    }
+
+    writer.flush()?;
+    Ok(())
+}
+
+/// writes a two parameter sweep as csv in long format, one `x_name,y_name,value_name` row per
+/// grid point. `rows[j][i]` is the value at `(xs[i], ys[j])`
+pub fn write_plane_csv(path: impl AsRef<Path>, (x_name, xs): (&str, &[f64]), (y_name, ys): (&str, &[f64]), value_name: &str, rows: &[Vec<f64>]) -> Result<(), Box<dyn Error>> {
+    let mut writer = csv::Writer::from_path(path)?;
+    writer.write_record([x_name, y_name, value_name])?;
+
+    for (y, row) in ys.iter().zip(rows) {
+        for (x, value) in xs.iter().zip(row) {
+            writer.write_record([x.to_string(), y.to_string(), value.to_string()])?;
+        }
+    }
+
+    writer.flush()?;
+    Ok(())
+}
@@ -0,0 +1,158 @@
+use crate::{dynamical_system::{Map, Parameters}, sweep::with_parameter};
+
+/// one step of the period-doubling cascade, re: [`feigenbaum`]
+#[derive(Debug, Clone)]
+pub struct CascadeStep {
+    /// the superstable cycle has period 2^n
+    pub n: usize,
+    /// parameter value R_n where the critical point is on the 2^n-cycle
+    pub parameter: f64,
+    /// d_n, from the critical point to the nearest other point of the cycle (half a period on)
+    pub distance: f64,
+    /// (R_n-1 - R_n-2) / (R_n - R_n-1)
+    pub delta: Option<f64>,
+    /// -d_n-1 / d_n
+    pub alpha: Option<f64>,
+    /// δ_n and α_n with the leading δ^{-n} error term extrapolated away
+    pub delta_extrapolated: Option<f64>,
+    pub alpha_extrapolated: Option<f64>
+}
+
+/// the period-doubling cascade of a 1d unimodal map and the feigenbaum constants read off it
+#[derive(Debug, Clone)]
+pub struct Cascade {
+    pub steps: Vec<CascadeStep>,
+    /// best estimates from the extrapolated values
+    pub delta: f64,
+    pub alpha: f64,
+    /// where the cascade accumulates and chaos begins
+    pub parameter_infinity: f64
+}
+
+impl std::fmt::Display for Cascade {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "δ = {:.6}, α = {:.6}, accumulating at {:.10}", self.delta, self.alpha, self.parameter_infinity)
+    }
+}
+
+/// x = F^period(x_c) for the map at `value`, and dx/dr along the way
+/// ```latex
+/// x_{k+1} = F(x_k, r)
+/// x'_{k+1} = F_x(x_k, r) x'_k + F_r(x_k, r)
+/// ```
+/// with F_x and F_r by central differences
+fn iterate_with_derivative<M: Map + Parameters + Clone>(map: &M, name: &str, value: f64, critical: f64, period: usize) -> (f64, f64) {
+    let f = with_parameter(map, name, value);
+    let h = 1e-7 * value.abs().max(1.0);
+    let (up, down) = (with_parameter(map, name, value + h), with_parameter(map, name, value - h));
+    let (mut x, mut dx) = (critical, 0.0);
+    for _ in 0..period {
+        let hx = 1e-7 * x.abs().max(1.0);
+        let f_x = (f.step(&[x + hx])[0] - f.step(&[x - hx])[0]) / (2.0 * hx);
+        let f_r = (up.step(&[x])[0] - down.step(&[x])[0]) / (2.0 * h);
+        dx = (f_x * dx) + f_r;
+        x = f.step(&[x])[0];
+    }
+    (x, dx)
+}
+
+/// the parameter near `guess` where the critical point comes back to itself after `period`
+/// iterations, by newton's method. `None` if it doesn't settle
+pub fn superstable_parameter<M: Map + Parameters + Clone>(map: &M, name: &str, critical: f64, period: usize, guess: f64) -> Option<f64> {
+    let mut r = guess;
+    for _ in 0..50 {
+        let (x, dx) = iterate_with_derivative(map, name, r, critical, period);
+        let step = (x - critical) / dx;
+        if !step.is_finite() {
+            return None;
+        }
+        r -= step;
+        if step.abs() <= 1e-15 * r.abs().max(1.0) {
+            return Some(r);
+        }
+    }
+    // round-off keeps it from getting any closer, the last few steps only jitter
+    let (x, _) = iterate_with_derivative(map, name, r, critical, period);
+    ((x - critical).abs() < 1e-9).then_some(r)
+}
+
+/// richardson extrapolation of a sequence converging geometrically,
+/// ```latex
+/// a_n = a + c q^n   =>   a = (a_{n+1} - q a_n) / (1 - q)
+/// ```
+/// one value fewer than `values`
+pub fn richardson(values: &[f64], ratio: f64) -> Vec<f64> {
+    values.windows(2).map(|w| (w[1] - (ratio * w[0])) / (1.0 - ratio)).collect()
+}
+
+/// walks the period-doubling cascade of the 1d unimodal map `map` with its maximum (or minimum)
+/// at `critical`, through the superstable parameters R_0 < R_1 < ... where the critical point
+/// is on a cycle of period 2^n. `guesses` are rough values for R_0 and R_1, every later one is
+/// predicted from the ones before
+/// ```latex
+/// R_{n+1} ≈ R_n + (R_n - R_{n-1}) / δ_n
+/// δ = lim (R_n - R_{n-1}) / (R_{n+1} - R_n) = 4.66920...
+/// α = lim -d_n / d_{n+1} = 2.50290...
+/// ```
+/// both limits converge like δ^{-n}, so richardson extrapolation with ratio 1/δ gains a few
+/// digits. Stops after `max_n` or once newton can't resolve the next R_n, None if that's before
+/// R_3. In f64 the estimates are best around n = 10, after that the gaps R_n - R_n-1 get lost in round-off
+pub fn feigenbaum<M: Map + Parameters + Clone>(map: &M, name: &str, critical: f64, guesses: (f64, f64), max_n: usize) -> Option<Cascade> {
+    let mut parameters = vec![superstable_parameter(map, name, critical, 1, guesses.0)?, superstable_parameter(map, name, critical, 2, guesses.1)?];
+    // the first ratio isn't known yet, start from the universal one
+    let mut delta = 4.669;
+    while parameters.len() <= max_n {
+        let n = parameters.len();
+        let (last, before) = (parameters[n - 1], parameters[n - 2]);
+        if n >= 3 {
+            delta = (before - parameters[n - 3]) / (last - before);
+        }
+        let Some(r) = superstable_parameter(map, name, critical, 1 << n, last + ((last - before) / delta)) else { break };
+        // converged back onto an earlier one, or out of the cascade
+        if (r - last) * (last - before) <= 0.0 || (r - last).abs() < 1e-13 * r.abs() {
+            break;
+        }
+        parameters.push(r);
+    }
+    // δ and α need R_0 .. R_3 for a ratio each and an extrapolation of it
+    if parameters.len() < 4 {
+        return None;
+    }
+
+    // d_n: the cycle point 2^(n-1) iterations on is the one closest to the critical point
+    let distances: Vec<f64> = parameters.iter().enumerate().map(|(n, r)| {
+        let f = with_parameter(map, name, *r);
+        let half = if n == 0 { 1 } else { 1 << (n - 1) };
+        (0..half).fold(critical, |x, _| f.step(&[x])[0]) - critical
+    }).collect();
+
+    let deltas: Vec<f64> = parameters.windows(3).map(|w| (w[1] - w[0]) / (w[2] - w[1])).collect();
+    let alphas: Vec<f64> = distances.windows(2).skip(1).map(|w| -w[0] / w[1]).collect();
+    let ratio = 1.0 / deltas.last().copied().unwrap_or(delta);
+    let (deltas_extrapolated, alphas_extrapolated) = (richardson(&deltas, ratio), richardson(&alphas, ratio));
+
+    let steps: Vec<CascadeStep> = parameters.iter().zip(&distances).enumerate().map(|(n, (r, d))| CascadeStep {
+        n,
+        parameter: *r,
+        distance: *d,
+        delta: n.checked_sub(2).and_then(|i| deltas.get(i)).copied(),
+        alpha: n.checked_sub(2).and_then(|i| alphas.get(i)).copied(),
+        delta_extrapolated: n.checked_sub(3).and_then(|i| deltas_extrapolated.get(i)).copied(),
+        alpha_extrapolated: n.checked_sub(3).and_then(|i| alphas_extrapolated.get(i)).copied()
+    }).collect();
+
+    // further down the cascade the gaps between R_n reach round-off and the estimates wander
+    // off again, so the best one is where consecutive extrapolations agree most closely
+    let best = |extrapolated: &[f64], plain: &[f64]| {
+        (1..extrapolated.len())
+        .min_by(|i, j| (extrapolated[*i] - extrapolated[i - 1]).abs().total_cmp(&(extrapolated[*j] - extrapolated[j - 1]).abs()))
+        .map(|i| extrapolated[i])
+        .or(extrapolated.last().or(plain.last()).copied())
+        .unwrap_or(f64::NAN)
+    };
+    let (delta, alpha) = (best(&deltas_extrapolated, &deltas), best(&alphas_extrapolated, &alphas));
+    let n = parameters.len();
+    // the gaps shrink geometrically, summing the rest of the series
+    let parameter_infinity = parameters[n - 1] + ((parameters[n - 1] - parameters[n - 2]) / (delta - 1.0));
+    Some(Cascade { steps, delta, alpha, parameter_infinity })
+}
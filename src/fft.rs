@@ -0,0 +1,70 @@
+use crate::math::Complex;
+
+/// in-place fast fourier transform (iterative radix-2 cooley–tukey)
+/// ```latex
+/// X_k = Σ_n x_n e^{-2πikn/N}
+/// ```
+/// the length must be a power of two
+pub fn fft(data: &mut [Complex]) {
+    transform(data, -1.0);
+}
+
+/// inverse of [`fft`], including the 1/N so `ifft(fft(x)) == x`
+pub fn ifft(data: &mut [Complex]) {
+    transform(data, 1.0);
+    let scale = 1.0 / data.len() as f64;
+    for x in data.iter_mut() {
+        *x = *x * scale;
+    }
+}
+
+/// fft of a real signal
+pub fn real_fft(signal: &[f64]) -> Vec<Complex> {
+    let mut data: Vec<Complex> = signal.iter().map(|x| Complex::new(*x, 0.0)).collect();
+    fft(&mut data);
+    data
+}
+
+/// real part of the inverse fft, for spectra of real signals
+pub fn real_ifft(spectrum: &[Complex]) -> Vec<f64> {
+    let mut data = spectrum.to_vec();
+    ifft(&mut data);
+    data.iter().map(|x| x.re).collect()
+}
+
+fn transform(data: &mut [Complex], sign: f64) {
+    let n = data.len();
+    assert!(n.is_power_of_two(), "fft length must be a power of two, got {}", n);
+
+    // bit reversal permutation, so the butterflies can work in place
+    let mut j = 0;
+    for i in 1..n {
+        let mut bit = n >> 1;
+        while j & bit != 0 {
+            j ^= bit;
+            bit >>= 1;
+        }
+        j |= bit;
+        if i < j {
+            data.swap(i, j);
+        }
+    }
+
+    // combine transforms of length len/2 into transforms of length len
+    let mut len = 2;
+    while len <= n {
+        let angle = sign * 2.0 * std::f64::consts::PI / len as f64;
+        // twiddle factors computed directly rather than by repeated multiplication,
+        // which would pile up round-off for long transforms
+        let twiddles: Vec<Complex> = (0..(len / 2)).map(|k| Complex::new((angle * k as f64).cos(), (angle * k as f64).sin())).collect();
+        for start in (0..n).step_by(len) {
+            for (k, w) in twiddles.iter().enumerate() {
+                let even = data[start + k];
+                let odd = data[start + k + (len / 2)] * *w;
+                data[start + k] = even + odd;
+                data[start + k + (len / 2)] = even - odd;
+            }
+        }
+        len <<= 1;
+    }
+}
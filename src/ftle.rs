@@ -0,0 +1,63 @@
+use crate::{dynamical_system::DynamicalSystem, integrators::rk4_step, runner::{run_grid, Progress}};
+
+/// the flow map φ: where `x0` ends up after integrating for `horizon` from t = 0 with rk4
+/// steps of (about) `dt`. A negative horizon runs the flow backwards
+pub fn flow_map(sys: &impl DynamicalSystem, x0: &[f64], horizon: f64, dt: f64) -> Vec<f64> {
+    let steps = (horizon.abs() / dt).ceil().max(1.0) as usize;
+    let dt = horizon / steps as f64;
+    let mut x = x0.to_vec();
+    let mut t = 0.0;
+    for _ in 0..steps {
+        rk4_step(sys, &mut x, t, dt);
+        t += dt;
+    }
+    x
+}
+
+/// finite-time lyapunov exponent at `x0` over `horizon`, restricted to the plane of the two state
+/// components in `axes`
+/// ```latex
+/// F = ∂φ/∂x₀          (n × 2, central differences of step h)
+/// C = Fᵀ F            (right cauchy–green tensor)
+/// σ = ln(λ_max(C)) / (2 |T|)
+/// ```
+/// the rate at which the most stretched direction grows over that one finite window. Ridges of
+/// σ over a grid are the lagrangian coherent structures: forward in time they repel
+/// neighbouring trajectories, backward in time they attract them
+pub fn ftle(sys: &impl DynamicalSystem, x0: &[f64], (i, j): (usize, usize), horizon: f64, dt: f64, h: f64) -> f64 {
+    let column = |k: usize| {
+        let mut up = x0.to_vec();
+        let mut down = x0.to_vec();
+        up[k] += h;
+        down[k] -= h;
+        let (up, down) = (flow_map(sys, &up, horizon, dt), flow_map(sys, &down, horizon, dt));
+        up.iter().zip(&down).map(|(u, d)| (u - d) / (2.0 * h)).collect::<Vec<f64>>()
+    };
+    let (a, b) = (column(i), column(j));
+    let dot = |u: &[f64], v: &[f64]| u.iter().zip(v).map(|(u, v)| u * v).sum::<f64>();
+
+    // largest eigenvalue of the symmetric 2×2 [[a·a, a·b], [a·b, b·b]]
+    let (caa, cab, cbb) = (dot(&a, &a), dot(&a, &b), dot(&b, &b));
+    let half_trace = (caa + cbb) / 2.0;
+    let lambda_max = half_trace + (half_trace * half_trace - (caa * cbb - cab * cab)).max(0.0).sqrt();
+
+    lambda_max.ln() / (2.0 * horizon.abs())
+}
+
+/// FTLE over a grid of initial states: `base` with component `axes.0` set to each of `xs` and
+/// `axes.1` to each of `ys`, re: [`ftle`]. `rows[j][i]` is at `(xs[i], ys[j])`.
+///
+/// the finite differences use a tenth of the grid spacing (an auxiliary grid around every
+/// point), fine enough to resolve the ridges the grid can show at all
+#[allow(clippy::too_many_arguments)]
+pub fn ftle_field<S: DynamicalSystem + Sync>(sys: &S, base: &[f64], axes: (usize, usize), xs: &[f64], ys: &[f64], horizon: f64, dt: f64, progress: &Progress) -> Option<Vec<Vec<f64>>> {
+    let spacing = |v: &[f64]| if v.len() > 1 { (v[v.len() - 1] - v[0]).abs() / (v.len() - 1) as f64 } else { 1.0 };
+    let h = 0.1 * spacing(xs).min(spacing(ys));
+
+    run_grid(xs, ys, progress, |x, y| {
+        let mut x0 = base.to_vec();
+        x0[axes.0] = x;
+        x0[axes.1] = y;
+        ftle(sys, &x0, axes, horizon, dt, h)
+    })
+}
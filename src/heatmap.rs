@@ -0,0 +1,76 @@
+/// a grid of values turned into colours, for space-time plots, parameter planes, basins, ...
+///
+/// `pixels` is row major, row 0 at the top
+#[derive(Debug, Clone)]
+pub struct Heatmap {
+    pub width: usize,
+    pub height: usize,
+    pub pixels: Vec<[u8; 3]>
+}
+
+impl Heatmap {
+    /// colours `rows` (all the same length) with the viridis colormap, stretched over `range`
+    /// or over the data's own min/max when `range` is `None`. NaNs come out black
+    pub fn from_rows(rows: &[Vec<f64>], range: Option<(f64, f64)>) -> Self {
+        let (lo, hi) = range.unwrap_or_else(|| {
+            rows.iter()
+            .flatten()
+            .filter(|v| v.is_finite())
+            .fold((f64::MAX, f64::MIN), |(lo, hi), v| (lo.min(*v), hi.max(*v)))
+        });
+        // flat data would divide by zero
+        let span = if hi > lo { hi - lo } else { 1.0 };
+
+        Heatmap::from_rows_with(rows, |v| if v.is_finite() { viridis((v - lo) / span) } else { [0, 0, 0] })
+    }
+
+    /// colours `rows` (all the same length) with any colormap
+    pub fn from_rows_with(rows: &[Vec<f64>], colour: impl Fn(f64) -> [u8; 3]) -> Self {
+        let height = rows.len();
+        let width = rows.first().map_or(0, |r| r.len());
+        let pixels = rows.iter().flatten().map(|v| colour(*v)).collect();
+
+        Heatmap { width, height, pixels }
+    }
+}
+
+/// colour for a largest lyapunov exponent: chaos (positive) from dark red to yellow, order
+/// (zero or negative) from dark to light blue, both saturating at `scale`. NaN (the orbit
+/// escaped to infinity) is black
+pub fn lyapunov_colour(lambda: f64, scale: f64) -> [u8; 3] {
+    if lambda.is_nan() {
+        return [0, 0, 0];
+    }
+    let s = (lambda.abs() / scale).clamp(0.0, 1.0);
+    if lambda > 0.0 {
+        [(120.0 + (135.0 * s)) as u8, (230.0 * s) as u8, 0]
+    } else {
+        [(20.0 + (150.0 * s)) as u8, (40.0 + (170.0 * s)) as u8, (120.0 + (135.0 * s)) as u8]
+    }
+}
+
+/// matplotlib's viridis, linearly interpolated between a handful of its stops.
+/// `x` is clamped to [0,1]
+pub fn viridis(x: f64) -> [u8; 3] {
+    const STOPS: [[f64; 3]; 9] = [
+        [68.0, 1.0, 84.0],
+        [71.0, 44.0, 122.0],
+        [59.0, 81.0, 139.0],
+        [44.0, 113.0, 142.0],
+        [33.0, 144.0, 141.0],
+        [39.0, 173.0, 129.0],
+        [92.0, 200.0, 99.0],
+        [170.0, 220.0, 50.0],
+        [253.0, 231.0, 37.0]
+    ];
+
+    let pos = x.clamp(0.0, 1.0) * (STOPS.len() - 1) as f64;
+    let i = (pos.floor() as usize).min(STOPS.len() - 2);
+    let s = pos - i as f64;
+
+    let mut rgb = [0; 3];
+    for c in 0..3 {
+        rgb[c] = (STOPS[i][c] + (s * (STOPS[i + 1][c] - STOPS[i][c]))).round() as u8;
+    }
+    rgb
+}
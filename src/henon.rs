@@ -0,0 +1,47 @@
+use crate::dynamical_system::{Map, Parameters};
+
+/// hénon's quadratic map of the plane, a stretched and folded version of the logistic map
+/// ```latex
+/// x_{n+1} = 1 - a x_n² + y_n
+/// y_{n+1} = b x_n
+/// ```
+/// the classic parameters a = 1.4, b = 0.3 give a fractal (cantor set × line) strange attractor
+#[derive(Debug, Clone, Copy)]
+pub struct Henon {
+    pub a: f64,
+    pub b: f64
+}
+
+impl Map for Henon {
+    fn dimension(&self) -> usize {
+        2
+    }
+
+    fn step(&self, state: &[f64]) -> Vec<f64> {
+        vec![
+            1.0 - (self.a * state[0] * state[0]) + state[1],
+            self.b * state[0]
+        ]
+    }
+}
+
+impl Parameters for Henon {
+    fn parameters(&self) -> Vec<(String, f64)> {
+        vec![(String::from("a"), self.a), (String::from("b"), self.b)]
+    }
+
+    fn set_parameter(&mut self, name: &str, value: f64) -> bool {
+        match name {
+            "a" => self.a = value,
+            "b" => self.b = value,
+            _ => return false
+        }
+        true
+    }
+}
+
+impl Default for Henon {
+    fn default() -> Self {
+        Self { a: 1.4, b: 0.3 }
+    }
+}
@@ -0,0 +1,59 @@
+use std::{error::Error, io::Read, path::Path};
+
+use crate::trajectory::Trajectory;
+
+/// column names from the header row of a csv, for picking what to import
+pub fn read_csv_headers(path: impl AsRef<Path>) -> Result<Vec<String>, Box<dyn Error>> {
+    let mut reader = csv::ReaderBuilder::new().comment(Some(b'#')).trim(csv::Trim::All).from_path(path)?;
+    Ok(reader.headers()?.iter().map(String::from).collect())
+}
+
+/// reads measured data into a [`Trajectory`] so every analysis tool works on it like on a simulation.
+///
+/// `columns` become the state components, in order, each given by its header name or by its
+/// (0 based) position. Times come from the `time` column if there is one, otherwise sample k
+/// is at `k * dt`. Lines starting with `#` are skipped (like the seed line of our own csv output),
+/// as are rows with an empty field in a wanted column, lab data tends to have a few
+pub fn read_csv_trajectory(data: impl Read, columns: &[&str], time: Option<&str>, dt: f64) -> Result<Trajectory, Box<dyn Error>> {
+    let mut reader = csv::ReaderBuilder::new().comment(Some(b'#')).trim(csv::Trim::All).from_reader(data);
+    let headers = reader.headers()?.clone();
+    let find = |column: &str| {
+        headers.iter().position(|h| h == column)
+        .or_else(|| column.parse::<usize>().ok().filter(|i| *i < headers.len()))
+        .ok_or_else(|| format!("no column '{}', the columns are {}", column, headers.iter().collect::<Vec<_>>().join(", ")))
+    };
+    if columns.is_empty() {
+        return Err("no columns to import".into());
+    }
+    let indices = columns.iter().map(|c| find(c)).collect::<Result<Vec<_>, _>>()?;
+    let time = time.map(find).transpose()?;
+
+    let mut trajectory = Trajectory::default();
+    for (row, record) in reader.records().enumerate() {
+        let record = record?;
+        let field = |i: usize| record.get(i).unwrap_or("");
+        if indices.iter().chain(&time).any(|i| field(*i).is_empty()) {
+            continue;
+        }
+        // data rows counted from 1, the header not included
+        let parse = |i: usize| field(i).parse::<f64>().map_err(|_| format!("row {}: '{}' in column '{}' is not a number", row + 1, field(i), &headers[i]));
+
+        let state = indices.iter().map(|i| parse(*i)).collect::<Result<Vec<_>, _>>()?;
+        let t = match time {
+            Some(i) => parse(i)?,
+            None => trajectory.len() as f64 * dt
+        };
+        trajectory.times.push(t);
+        trajectory.states.push(state);
+    }
+
+    if trajectory.is_empty() {
+        return Err("no rows with numbers in every wanted column".into());
+    }
+    Ok(trajectory)
+}
+
+/// [`read_csv_trajectory`] from a file
+pub fn read_csv_trajectory_from_path(path: impl AsRef<Path>, columns: &[&str], time: Option<&str>, dt: f64) -> Result<Trajectory, Box<dyn Error>> {
+    read_csv_trajectory(std::fs::File::open(path)?, columns, time, dt)
+}
@@ -1,4 +1,6 @@
-use crate::dynamical_system::DynamicalSystem;
+use rand::Rng;
+
+use crate::{dynamical_system::{Diffusion, DynamicalSystem}, rng::gaussian};
 
 
 /// # Euler Step
@@ -134,4 +136,39 @@ pub fn rk4_step(sys: &impl DynamicalSystem, state: &mut [f64], t: f64, dt: f64)
     }
     
     
-}
\ No newline at end of file
+}
+
+/// # Euler–Maruyama step
+/// the stochastic version of `euler_step`. Each component takes the deterministic euler step
+/// plus a random kick `g_i * dW_i`, where `dW_i ~ N(0, dt)` is the increment of a wiener process.
+///
+/// note the kick scales with `sqrt(dt)`, not `dt`: the variance of brownian motion grows linearly in time
+///
+/// `rng` - source of the gaussian increments, seed it (re: `rng::rng_generator`) to replay a run
+pub fn euler_maruyama_step(sys: &impl Diffusion, state: &mut [f64], t: f64, dt: f64, rng: &mut impl Rng) {
+    let drift = sys.rhs(t, state);
+    let diffusion = sys.diffusion(t, state);
+
+    for i in 0..sys.dimension() {
+        let dw = dt.sqrt() * gaussian(rng);
+        state[i] += (drift[i] * dt) + (diffusion[i] * dw);
+    }
+}
+
+/// # Milstein step
+/// euler–maruyama plus the next term of the stochastic taylor (itô–taylor) expansion:
+/// ```latex
+/// x += f dt + g dW + 0.5 g g′ (dW² - dt)
+/// ```
+/// for additive noise `g′ = 0` and this is identical to euler–maruyama, for state dependent
+/// (multiplicative) noise it raises the strong order of convergence from 0.5 to 1
+pub fn milstein_step(sys: &impl Diffusion, state: &mut [f64], t: f64, dt: f64, rng: &mut impl Rng) {
+    let drift = sys.rhs(t, state);
+    let diffusion = sys.diffusion(t, state);
+    let diffusion_prime = sys.diffusion_derivative(t, state);
+
+    for i in 0..sys.dimension() {
+        let dw = dt.sqrt() * gaussian(rng);
+        state[i] += (drift[i] * dt) + (diffusion[i] * dw) + (0.5 * diffusion[i] * diffusion_prime[i] * ((dw * dw) - dt));
+    }
+}
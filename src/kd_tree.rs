@@ -0,0 +1,99 @@
+use crate::math::distance;
+
+/// k-d tree over a set of points for nearest neighbour and fixed radius searches.
+///
+/// brute force is O(N²) over a whole series, which is fine for a recurrence plot of 2000 points
+/// but not for the tens of thousands a data lyapunov estimate wants. Queries here are about
+/// O(log N) for the low dimensional attractors we deal with
+pub struct KdTree<'a> {
+    points: &'a [Vec<f64>],
+    nodes: Vec<Node>,
+    root: Option<usize>
+}
+
+struct Node {
+    // index of the point in `points` this node splits at
+    index: usize,
+    axis: usize,
+    left: Option<usize>,
+    right: Option<usize>
+}
+
+impl<'a> KdTree<'a> {
+    /// builds the tree, splitting at the median along the axes in turn
+    pub fn new(points: &'a [Vec<f64>]) -> Self {
+        let mut tree = KdTree { points, nodes: Vec::with_capacity(points.len()), root: None };
+        let mut indices: Vec<usize> = (0..points.len()).collect();
+        tree.root = tree.build(&mut indices, 0);
+        tree
+    }
+
+    fn build(&mut self, indices: &mut [usize], depth: usize) -> Option<usize> {
+        if indices.is_empty() {
+            return None;
+        }
+        let axis = depth % self.points[indices[0]].len().max(1);
+        let median = indices.len() / 2;
+        let points = self.points;
+        indices.select_nth_unstable_by(median, |a, b| points[*a][axis].total_cmp(&points[*b][axis]));
+
+        let (below, rest) = indices.split_at_mut(median);
+        let (index, above) = (rest[0], &mut rest[1..]);
+        let left = self.build(below, depth + 1);
+        let right = self.build(above, depth + 1);
+        self.nodes.push(Node { index, axis, left, right });
+        Some(self.nodes.len() - 1)
+    }
+
+    /// nearest point to `query` (index, distance) among those `skip` doesn't rule out, e.g. the
+    /// query itself and its neighbours in time
+    pub fn nearest(&self, query: &[f64], skip: impl Fn(usize) -> bool) -> Option<(usize, f64)> {
+        let mut best = None;
+        self.search_nearest(self.root, query, &skip, &mut best);
+        best
+    }
+
+    fn search_nearest(&self, node: Option<usize>, query: &[f64], skip: &impl Fn(usize) -> bool, best: &mut Option<(usize, f64)>) {
+        let Some(node) = node.map(|n| &self.nodes[n]) else { return };
+        let point = &self.points[node.index];
+
+        if !skip(node.index) {
+            let d = distance(query, point);
+            if best.is_none_or(|(_, b)| d < b) {
+                *best = Some((node.index, d));
+            }
+        }
+
+        // the side the query is on first, the other only if the splitting plane is closer than the best so far
+        let offset = query[node.axis] - point[node.axis];
+        let (near, far) = if offset < 0.0 { (node.left, node.right) } else { (node.right, node.left) };
+        self.search_nearest(near, query, skip, best);
+        if best.is_none_or(|(_, b)| offset.abs() < b) {
+            self.search_nearest(far, query, skip, best);
+        }
+    }
+
+    /// indices of every point within `radius` of `query` that `skip` doesn't rule out
+    pub fn within(&self, query: &[f64], radius: f64, skip: impl Fn(usize) -> bool) -> Vec<usize> {
+        let mut found = vec![];
+        self.search_within(self.root, query, radius, &skip, &mut found);
+        found
+    }
+
+    fn search_within(&self, node: Option<usize>, query: &[f64], radius: f64, skip: &impl Fn(usize) -> bool, found: &mut Vec<usize>) {
+        let Some(node) = node.map(|n| &self.nodes[n]) else { return };
+        let point = &self.points[node.index];
+
+        if !skip(node.index) && distance(query, point) <= radius {
+            found.push(node.index);
+        }
+
+        let offset = query[node.axis] - point[node.axis];
+        if offset - radius <= 0.0 {
+            self.search_within(node.left, query, radius, skip, found);
+        }
+        if offset + radius >= 0.0 {
+            self.search_within(node.right, query, radius, skip, found);
+        }
+    }
+}
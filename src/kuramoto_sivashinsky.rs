@@ -0,0 +1,173 @@
+use crate::{dynamical_system::{DynamicalSystem, Parameters}, fft::{real_fft, real_ifft}, math::Complex};
+
+/// the kuramoto–sivashinsky equation on a periodic domain [0, L)
+/// ```latex
+/// u_t = -u u_x - u_xx - u_xxxx
+/// ```
+/// the `-u_xx` term pumps energy in at long wavelengths, `-u_xxxx` damps the short ones and the
+/// nonlinearity shuffles energy between them. For L ≳ 20 the result is spatiotemporal chaos:
+/// cells that grow, split and merge forever.
+///
+/// the state is `u` sampled at `n` (a power of two) evenly spaced points
+#[derive(Debug, Clone, Copy)]
+pub struct KuramotoSivashinsky {
+    pub n: usize,
+    pub length: f64
+}
+
+impl KuramotoSivashinsky {
+    /// x coordinate of every grid point
+    pub fn grid(&self) -> Vec<f64> {
+        (0..self.n).map(|j| self.length * j as f64 / self.n as f64).collect()
+    }
+
+    /// the classic kassam–trefethen initial condition, u = cos(x/16)(1 + sin(x/16)), rescaled to the domain
+    pub fn initial_state(&self) -> Vec<f64> {
+        let scale = 32.0 * std::f64::consts::PI / self.length;
+        self.grid().iter().map(|x| {
+            let y = x * scale / 16.0;
+            y.cos() * (1.0 + y.sin())
+        }).collect()
+    }
+
+    /// angular wavenumbers in fft order: 0, 1, ..., n/2 - 1, n/2, -n/2 + 1, ..., -1 (times 2π/L)
+    fn wavenumbers(&self) -> Vec<f64> {
+        let n = self.n as i64;
+        (0..n).map(|j| {
+            let k = if j < n / 2 { j } else if j == n / 2 { 0 } else { j - n };
+            2.0 * std::f64::consts::PI * k as f64 / self.length
+        }).collect()
+    }
+}
+
+// pseudo-spectral rhs, derivatives in fourier space, product in real space.
+// Very stiff (the k⁴ term), so rk4 needs a tiny dt; use `Etdrk4` for real runs
+impl DynamicalSystem for KuramotoSivashinsky {
+    fn dimension(&self) -> usize {
+        self.n
+    }
+
+    fn rhs(&self, _t: f64, state: &[f64]) -> Vec<f64> {
+        let v = real_fft(state);
+        let k = self.wavenumbers();
+
+        let ux: Vec<Complex> = v.iter().zip(&k).map(|(v, k)| Complex::new(0.0, *k) * *v).collect();
+        let linear: Vec<Complex> = v.iter().zip(&k).map(|(v, k)| *v * ((k * k) - (k * k * k * k))).collect();
+
+        real_ifft(&ux).iter()
+        .zip(real_ifft(&linear))
+        .zip(state)
+        .map(|((ux, lin), u)| -(u * ux) + lin)
+        .collect()
+    }
+}
+
+impl Parameters for KuramotoSivashinsky {
+    fn parameters(&self) -> Vec<(String, f64)> {
+        vec![(String::from("length"), self.length)]
+    }
+
+    fn set_parameter(&mut self, name: &str, value: f64) -> bool {
+        if name != "length" {
+            return false;
+        }
+        self.length = value;
+        true
+    }
+}
+
+impl Default for KuramotoSivashinsky {
+    fn default() -> Self {
+        Self { n: 128, length: 32.0 * std::f64::consts::PI }
+    }
+}
+
+/// exponential time differencing rk4 (cox & matthews, with kassam & trefethen's contour integral trick).
+///
+/// in fourier space KS is `v′ = L v + N(v)` with `L` diagonal. The linear part is solved exactly
+/// (that's the `e^{hL}` factors) so the step size is only limited by the nonlinearity, not by the
+/// k⁴ stiffness. The coefficients depend on `dt`, so they're computed once up front
+pub struct Etdrk4 {
+    pub dt: f64,
+    e: Vec<f64>,
+    e2: Vec<f64>,
+    q: Vec<f64>,
+    f1: Vec<f64>,
+    f2: Vec<f64>,
+    f3: Vec<f64>,
+    // -0.5ik, turns fft(u²) into the fourier transform of -u u_x
+    g: Vec<Complex>
+}
+
+impl Etdrk4 {
+    pub fn new(ks: &KuramotoSivashinsky, dt: f64) -> Self {
+        let k = ks.wavenumbers();
+        let l: Vec<f64> = k.iter().map(|k| (k * k) - (k * k * k * k)).collect();
+
+        // the coefficients are ratios like (e^z - 1)/z that cancel catastrophically for small z,
+        // so evaluate them as the mean over a circle of points around z instead
+        let m = 32;
+        let roots: Vec<Complex> = (1..=m).map(|j| {
+            Complex::new(0.0, std::f64::consts::PI * (j as f64 - 0.5) / m as f64).exp()
+        }).collect();
+        let contour_mean = |z: f64, f: &dyn Fn(Complex) -> Complex| -> f64 {
+            roots.iter().map(|r| f(Complex::new(z, 0.0) + *r).re).sum::<f64>() / m as f64
+        };
+        let one = Complex::new(1.0, 0.0);
+        let c = |x: f64| Complex::new(x, 0.0);
+
+        let mut q = vec![];
+        let mut f1 = vec![];
+        let mut f2 = vec![];
+        let mut f3 = vec![];
+        for li in &l {
+            let z = dt * li;
+            q.push(dt * contour_mean(z, &|lr| ((lr * 0.5).exp() - one) / lr));
+            f1.push(dt * contour_mean(z, &|lr| {
+                (c(-4.0) - lr + (lr.exp() * (c(4.0) - (lr * 3.0) + (lr * lr)))) / (lr * lr * lr)
+            }));
+            f2.push(dt * contour_mean(z, &|lr| {
+                (c(2.0) + lr + (lr.exp() * (c(-2.0) + lr))) / (lr * lr * lr)
+            }));
+            f3.push(dt * contour_mean(z, &|lr| {
+                (c(-4.0) - (lr * 3.0) - (lr * lr) + (lr.exp() * (c(4.0) - lr))) / (lr * lr * lr)
+            }));
+        }
+
+        Etdrk4 {
+            dt,
+            e: l.iter().map(|l| (dt * l).exp()).collect(),
+            e2: l.iter().map(|l| (0.5 * dt * l).exp()).collect(),
+            q,
+            f1,
+            f2,
+            f3,
+            g: k.iter().map(|k| Complex::new(0.0, -0.5 * k)).collect()
+        }
+    }
+
+    /// advances `state` (u on the grid) by `dt`
+    pub fn step(&self, state: &mut [f64]) {
+        let v = real_fft(state);
+        let nonlinear = |v: &[Complex]| -> Vec<Complex> {
+            let u = real_ifft(v);
+            let u2: Vec<f64> = u.iter().map(|u| u * u).collect();
+            real_fft(&u2).iter().zip(&self.g).map(|(a, g)| *a * *g).collect()
+        };
+        let n = v.len();
+
+        let nv = nonlinear(&v);
+        let a: Vec<Complex> = (0..n).map(|i| (v[i] * self.e2[i]) + (nv[i] * self.q[i])).collect();
+        let na = nonlinear(&a);
+        let b: Vec<Complex> = (0..n).map(|i| (v[i] * self.e2[i]) + (na[i] * self.q[i])).collect();
+        let nb = nonlinear(&b);
+        let c: Vec<Complex> = (0..n).map(|i| (a[i] * self.e2[i]) + (((nb[i] * 2.0) - nv[i]) * self.q[i])).collect();
+        let nc = nonlinear(&c);
+
+        let next: Vec<Complex> = (0..n).map(|i| {
+            (v[i] * self.e[i]) + (nv[i] * self.f1[i]) + ((na[i] + nb[i]) * (2.0 * self.f2[i])) + (nc[i] * self.f3[i])
+        }).collect();
+
+        state.copy_from_slice(&real_ifft(&next));
+    }
+}
@@ -0,0 +1,36 @@
+/// A mechanical system written in terms of its generalised coordinates `q`.
+///
+/// Any system whose Lagrangian is `T(q, q') - V(q)` with a kinetic energy quadratic in `q'`
+/// leads to equations of motion of the form
+/// ```latex
+/// M(q) q′′ = f(q, q′)
+/// ```
+/// where `M` is the (symmetric, positive definite) mass matrix and `f` collects the
+/// gravity/potential terms and the velocity-dependent (centripetal/coriolis) terms.
+/// Instead of doing the "algebraic nastiness" by hand for every system (re: `double_pendulum.rs`),
+/// we only describe `M` and `f` and let [`lagrangian_rhs`] solve for the accelerations.
+pub trait Lagrangian {
+    fn coordinates(&self) -> usize; // number of generalised coordinates, the state is twice as long
+    fn mass_matrix(&self, q: &[f64]) -> Vec<Vec<f64>>;
+    fn generalised_forces(&self, q: &[f64], q_dot: &[f64]) -> Vec<f64>;
+}
+
+/// derivative of the state `[q_1..q_n, q_dot_1..q_dot_n]` for a [`Lagrangian`] system.
+///
+/// the first half of the derivative is simply the velocities, the second half the
+/// accelerations found by solving `M(q) q'' = f(q, q')`
+pub fn lagrangian_rhs(sys: &impl Lagrangian, state: &[f64]) -> Vec<f64> {
+    let n = sys.coordinates();
+    let (q, q_dot) = state.split_at(n);
+
+    let m = sys.mass_matrix(q);
+    let f = sys.generalised_forces(q, q_dot);
+
+    // same protection as the double pendulum: a singular mass matrix means the
+    // configuration is degenerate, so just don't accelerate rather than blowing up
+    let q_ddot = crate::math::solve_linear(&m, &f).unwrap_or_else(|| vec![0.0; n]);
+
+    let mut deriv = q_dot.to_vec();
+    deriv.extend(q_ddot);
+    deriv
+}
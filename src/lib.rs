@@ -0,0 +1,46 @@
+pub mod math;
+// lints the original integrators and double pendulum trip, left as they were written
+#[allow(clippy::assign_op_pattern, clippy::doc_lazy_continuation, clippy::empty_line_after_doc_comments)]
+pub mod integrators;
+pub mod logistic_map;
+pub mod sine_map;
+pub mod rng;
+pub mod lorenz;
+#[allow(clippy::too_many_arguments, clippy::neg_multiply)]
+pub mod double_pendulum;
+pub mod lagrangian;
+pub mod n_pendulum;
+pub mod dde;
+pub mod lorenz96;
+pub mod coupled_map_lattice;
+pub mod heatmap;
+pub mod fft;
+pub mod spectrum;
+pub mod zero_one;
+pub mod kuramoto_sivashinsky;
+pub mod export;
+pub mod import;
+pub mod henon;
+pub mod duffing;
+pub mod basins;
+pub mod ftle;
+pub mod trajectory;
+pub mod dimension;
+pub mod recurrence;
+pub mod embedding;
+pub mod kd_tree;
+pub mod periodic_orbits;
+pub mod continuation;
+pub mod feigenbaum;
+pub mod lyapunov;
+pub mod runner;
+pub mod sweep;
+pub mod sde;
+pub mod noise;
+pub mod dynamical_system;
+pub mod cli;
+
+// tests.rs keeps its own `mod tests` wrapper from when it lived in main.rs
+#[cfg(test)]
+#[allow(clippy::module_inception)]
+mod tests;
@@ -1,6 +1,4 @@
-use std::error::Error;
-
-use crate::dynamical_system::DynamicalSystem;
+use crate::dynamical_system::{DynamicalSystem, Map, Parameters};
 
 // fixed point iteration - value mapped to itself by the function: f(x) = x 
 // where the curve intersects the line y=x? Yes
@@ -12,6 +10,7 @@ use crate::dynamical_system::DynamicalSystem;
 // attractors vs. repellers
 // repeatedly applying x_{n+1} = rx_n(1-x_n)
 
+#[derive(Debug, Clone, Copy)]
 pub struct LogisticMap {
     pub r: f64
 }
@@ -28,6 +27,31 @@ impl DynamicalSystem for LogisticMap {
     }
 }
 
+// the map proper, x_{n+1} = r x_n (1 - x_n)
+impl Map for LogisticMap {
+    fn dimension(&self) -> usize {
+        1
+    }
+
+    fn step(&self, state: &[f64]) -> Vec<f64> {
+        vec![self.r * state[0] * (1.0 - state[0])]
+    }
+}
+
+impl Parameters for LogisticMap {
+    fn parameters(&self) -> Vec<(String, f64)> {
+        vec![(String::from("r"), self.r)]
+    }
+
+    fn set_parameter(&mut self, name: &str, value: f64) -> bool {
+        if name != "r" {
+            return false;
+        }
+        self.r = value;
+        true
+    }
+}
+
 impl Default for LogisticMap {
     fn default() -> Self {
         Self { r: 0.3 }
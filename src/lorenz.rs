@@ -1,8 +1,7 @@
-use rand::Rng;
-
-use crate::dynamical_system::{DynamicalSystem, Noise};
+use crate::dynamical_system::{DynamicalSystem, Parameters};
 
 // parameters for Lorenz system
+#[derive(Debug, Clone, Copy)]
 pub struct Lorenz {
     pub sigma: f64,
     pub ro: f64,
@@ -24,14 +23,23 @@ impl DynamicalSystem for Lorenz {
     }
 }
 
-impl Noise for Lorenz {
-    fn new_noisy(&self, noise_level: f64) -> Self {
-        let mut rng = rand::rng();
-        Lorenz { 
-              sigma: self.sigma + rng.random_range(0.0..noise_level),
-              ro: self.ro + rng.random_range(0.0..noise_level),
-              beta: self.beta + rng.random_range(0.0..noise_level)
-            }
+impl Parameters for Lorenz {
+    fn parameters(&self) -> Vec<(String, f64)> {
+        vec![
+            (String::from("sigma"), self.sigma),
+            (String::from("ro"), self.ro),
+            (String::from("beta"), self.beta)
+        ]
+    }
+
+    fn set_parameter(&mut self, name: &str, value: f64) -> bool {
+        match name {
+            "sigma" => self.sigma = value,
+            "ro" => self.ro = value,
+            "beta" => self.beta = value,
+            _ => return false
+        }
+        true
     }
 }
 
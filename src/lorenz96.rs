@@ -0,0 +1,70 @@
+use crate::dynamical_system::{DynamicalSystem, Parameters};
+
+/// Lorenz's 1996 toy atmosphere: `n` sites around a circle of latitude, each advected by its
+/// neighbours, damped and pushed by a constant forcing
+/// ```latex
+/// x_i′ = (x_{i+1} - x_{i-2}) x_{i-1} - x_i + F
+/// ```
+/// indices wrap around. Chaotic for F ≳ 5, with the number of positive lyapunov exponents
+/// growing with `n`, which makes it a good stress test for the high dimensional tools
+#[derive(Debug, Clone, Copy)]
+pub struct Lorenz96 {
+    // private, the stencil reaches two sites back so there have to be enough of them
+    n: usize,
+    pub forcing: f64
+}
+
+impl Lorenz96 {
+    /// `n` sites, at least 4 so the neighbours `i+1`, `i-1` and `i-2` are all different sites
+    pub fn new(n: usize, forcing: f64) -> Self {
+        assert!(n >= 4, "lorenz96 needs at least 4 sites, got {}", n);
+        Self { n, forcing }
+    }
+
+    pub fn sites(&self) -> usize {
+        self.n
+    }
+
+    /// the fixed point x_i = F, nudged at one site so something actually happens
+    pub fn initial_state(&self) -> Vec<f64> {
+        let mut state = vec![self.forcing; self.n];
+        state[0] += 0.01;
+        state
+    }
+}
+
+impl DynamicalSystem for Lorenz96 {
+    fn dimension(&self) -> usize {
+        self.n
+    }
+
+    fn rhs(&self, _t: f64, state: &[f64]) -> Vec<f64> {
+        let n = self.n;
+        (0..n).map(|i| {
+            let next = state[(i + 1) % n];
+            let prev = state[(i + n - 1) % n];
+            let prev2 = state[(i + n - 2) % n];
+            ((next - prev2) * prev) - state[i] + self.forcing
+        }).collect()
+    }
+}
+
+impl Parameters for Lorenz96 {
+    fn parameters(&self) -> Vec<(String, f64)> {
+        vec![(String::from("forcing"), self.forcing)]
+    }
+
+    fn set_parameter(&mut self, name: &str, value: f64) -> bool {
+        if name != "forcing" {
+            return false;
+        }
+        self.forcing = value;
+        true
+    }
+}
+
+impl Default for Lorenz96 {
+    fn default() -> Self {
+        Self::new(40, 8.0)
+    }
+}
@@ -1,3 +1,5 @@
+use crate::{dimension::ScalingFit, dynamical_system::{DynamicalSystem, Map}, integrators::rk4_step, kd_tree::KdTree, math::{distance, gram_schmidt, jacobian, mat_vec}, runner::Progress};
+
 /// finds the largest lyapunov exponent for a given function via double trajectory method
 /// 
 /// `x0` - inital state vector in this case, x0 is *NOT* the value along the horizontal axis, but rather the inital state "vector" (to generalize) which will evolve across time (n)
@@ -19,6 +21,7 @@
 /// 
 /// ### An interesting note for later
 /// 
+/// re: [`TwoTrajectory`] for the same thing on states with any number of dimensions
 pub fn lyapunov<F>(x0: f64, n: i64, f:F) -> f64 
 where F: Fn(f64) -> f64 {
     // initial starting vector state
@@ -63,3 +66,381 @@ where F: Fn(f64) -> f64 {
     
 
 }
+
+
+/// settings for [`TwoTrajectory`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Benettin {
+    /// distance between the two trajectories after every renormalisation
+    pub perturbation: f64,
+    /// steps between renormalisations. Longer lets the perturbation turn towards the most
+    /// unstable direction, but it mustn't grow out of the linear regime in between
+    pub renormalise_every: usize,
+    /// steps taken before the perturbed trajectory is started, to land on the attractor first
+    pub transient: usize
+}
+
+impl Default for Benettin {
+    fn default() -> Self {
+        Benettin { perturbation: 1e-8, renormalise_every: 10, transient: 1000 }
+    }
+}
+
+/// largest lyapunov exponent by following a reference and a perturbed trajectory, the same
+/// method as [`lyapunov`] for states of any dimension.
+///
+/// every `renormalise_every` steps the separation `d` is measured, `ln(d / perturbation)` added
+/// to a running sum and the perturbed state pulled back to `perturbation` away from the reference,
+/// along the direction they separated in. The running estimate is that sum over the time elapsed.
+///
+/// stepping is left to the caller so the same estimator works for flows, maps, and
+/// anything else that moves a `&mut [f64]` forward, re: [`TwoTrajectory::step`]
+#[derive(Debug, Clone)]
+pub struct TwoTrajectory {
+    pub settings: Benettin,
+    x: Vec<f64>,
+    // None until the transient is over
+    x_pert: Option<Vec<f64>>,
+    steps: usize,
+    sum: f64,
+    elapsed: f64
+}
+
+impl TwoTrajectory {
+    pub fn new(x0: &[f64], settings: Benettin) -> Self {
+        TwoTrajectory { settings, x: x0.to_vec(), x_pert: None, steps: 0, sum: 0.0, elapsed: 0.0 }
+    }
+
+    /// the reference trajectory's current state
+    pub fn state(&self) -> &[f64] {
+        &self.x
+    }
+
+    /// moves both trajectories forward by one step of `advance`, which covers `dt` time
+    /// (1 for maps). Returns the new estimate when this step renormalised
+    pub fn step(&mut self, mut advance: impl FnMut(&mut [f64]), dt: f64) -> Option<f64> {
+        advance(&mut self.x);
+        self.steps += 1;
+
+        let Some(x_pert) = &mut self.x_pert else {
+            if self.steps >= self.settings.transient {
+                // start off along the diagonal, the flow rotates it towards the unstable direction
+                let offset = self.settings.perturbation / (self.x.len() as f64).sqrt();
+                self.x_pert = Some(self.x.iter().map(|x| x + offset).collect());
+                self.steps = 0;
+            }
+            return None;
+        };
+
+        advance(x_pert);
+        self.elapsed += dt;
+
+        if !self.steps.is_multiple_of(self.settings.renormalise_every.max(1)) {
+            return None;
+        }
+
+        let delta = distance(&self.x, x_pert);
+        // landed on the reference (e.g. a superstable point of a map), nothing to measure
+        if delta == 0.0 {
+            return None;
+        }
+        self.sum += (delta / self.settings.perturbation).ln();
+        for (p, x) in x_pert.iter_mut().zip(&self.x) {
+            *p = x + (self.settings.perturbation * (*p - x) / delta);
+        }
+
+        Some(self.estimate())
+    }
+
+    /// time covered by the perturbed trajectory so far
+    pub fn elapsed(&self) -> f64 {
+        self.elapsed
+    }
+
+    /// running estimate of the largest exponent, 0 until something has been measured
+    pub fn estimate(&self) -> f64 {
+        if self.elapsed == 0.0 {
+            return 0.0;
+        }
+        self.sum / self.elapsed
+    }
+}
+
+/// largest lyapunov exponent of a flow, re: [`TwoTrajectory`]. Returns the running
+/// estimate after every renormalisation, the last one being the best
+pub fn largest_lyapunov(sys: &impl DynamicalSystem, x0: &[f64], dt: f64, steps: usize, settings: Benettin) -> Vec<f64> {
+    let mut estimator = TwoTrajectory::new(x0, settings);
+    (0..(settings.transient + steps)).filter_map(|_| estimator.step(|x| rk4_step(sys, x, 0.0, dt), dt)).collect()
+}
+
+/// largest lyapunov exponent of a map, re: [`TwoTrajectory`]. Returns the running
+/// estimate after every renormalisation, the last one being the best
+pub fn largest_lyapunov_map(map: &impl Map, x0: &[f64], iterations: usize, settings: Benettin) -> Vec<f64> {
+    let mut estimator = TwoTrajectory::new(x0, settings);
+    (0..(settings.transient + iterations)).filter_map(|_| {
+        estimator.step(|x| {
+            let next = map.step(x);
+            x.copy_from_slice(&next);
+        }, 1.0)
+    }).collect()
+}
+
+
+/// the flow together with `n` tangent vectors riding along with it.
+///
+/// the state is `[x, v_1, ..., v_n]`, each tangent vector evolving by the linearised flow
+/// `v′ = J(x) v`. Their stretching rates are the lyapunov exponents
+struct Variational<'a, S> {
+    sys: &'a S,
+    n: usize
+}
+
+impl<S: DynamicalSystem> DynamicalSystem for Variational<'_, S> {
+    fn dimension(&self) -> usize {
+        self.n * (self.n + 1)
+    }
+
+    fn rhs(&self, t: f64, state: &[f64]) -> Vec<f64> {
+        let x = &state[0..self.n];
+        let j = jacobian(|s| self.sys.rhs(t, s), x);
+
+        let mut deriv = self.sys.rhs(t, x);
+        for v in state[self.n..].chunks(self.n) {
+            deriv.extend(mat_vec(&j, v));
+        }
+        deriv
+    }
+}
+
+/// full lyapunov spectrum of a flow, largest first (benettin's algorithm).
+///
+/// a small sphere of initial conditions around `x0` becomes an ellipsoid as the flow stretches and
+/// squeezes it. We follow `n` tangent vectors (the ellipsoid's axes) along with the trajectory and
+/// re-orthonormalise them every `renormalise_every` steps: the log of how much each one grew,
+/// averaged over time, is the corresponding exponent. Without the gram–schmidt step every
+/// vector would just line up with the most unstable direction.
+///
+/// the first `transient` steps only bring `x0` onto the attractor and aren't counted. At least
+/// one step is always taken, there's nothing to average over otherwise
+///
+/// `progress` is checked at every re-orthonormalisation, `None` once it's cancelled
+pub fn lyapunov_spectrum(sys: &impl DynamicalSystem, x0: &[f64], dt: f64, steps: usize, transient: usize, progress: &Progress) -> Option<Vec<f64>> {
+    let steps = steps.max(1);
+    let n = x0.len();
+    let mut x = x0.to_vec();
+    let mut t = 0.0;
+    for _ in 0..transient {
+        rk4_step(sys, &mut x, t, dt);
+        t += dt;
+    }
+
+    // state followed by the identity matrix as the initial tangent vectors
+    let mut state = x;
+    for i in 0..n {
+        state.extend((0..n).map(|j| if i == j { 1.0 } else { 0.0 }));
+    }
+
+    let variational = Variational { sys, n };
+    let renormalise_every = 10;
+    let mut sums = vec![0.0; n];
+    let mut elapsed = 0.0;
+
+    for k in 1..=steps {
+        rk4_step(&variational, &mut state, t, dt);
+        t += dt;
+
+        if k % renormalise_every == 0 || k == steps {
+            if progress.is_cancelled() {
+                return None;
+            }
+            let mut vectors: Vec<Vec<f64>> = state[n..].chunks(n).map(|v| v.to_vec()).collect();
+            for (sum, norm) in sums.iter_mut().zip(gram_schmidt(&mut vectors)) {
+                *sum += norm.ln();
+            }
+            for (i, v) in vectors.iter().enumerate() {
+                state[(n * (i + 1))..(n * (i + 2))].copy_from_slice(v);
+            }
+            elapsed = k as f64 * dt;
+        }
+    }
+
+    let mut spectrum: Vec<f64> = sums.iter().map(|s| s / elapsed).collect();
+    spectrum.sort_by(|a, b| b.total_cmp(a));
+    Some(spectrum)
+}
+
+/// full lyapunov spectrum of a map, largest first. Same idea as [`lyapunov_spectrum`], but the
+/// tangent vectors are pushed through the map's jacobian once per iteration
+pub fn lyapunov_spectrum_map(map: &impl Map, x0: &[f64], iterations: usize, transient: usize, progress: &Progress) -> Option<Vec<f64>> {
+    let iterations = iterations.max(1);
+    let n = x0.len();
+    let mut x = x0.to_vec();
+    for _ in 0..transient {
+        x = map.step(&x);
+    }
+
+    let mut vectors: Vec<Vec<f64>> = (0..n).map(|i| (0..n).map(|j| if i == j { 1.0 } else { 0.0 }).collect()).collect();
+    let mut sums = vec![0.0; n];
+
+    for _ in 0..iterations {
+        if progress.is_cancelled() {
+            return None;
+        }
+        let j = jacobian(|s| map.step(s), &x);
+        x = map.step(&x);
+        vectors = vectors.iter().map(|v| mat_vec(&j, v)).collect();
+        for (sum, norm) in sums.iter_mut().zip(gram_schmidt(&mut vectors)) {
+            *sum += norm.ln();
+        }
+    }
+
+    let mut spectrum: Vec<f64> = sums.iter().map(|s| s / iterations as f64).collect();
+    spectrum.sort_by(|a, b| b.total_cmp(a));
+    Some(spectrum)
+}
+
+/// kaplan–yorke (lyapunov) dimension
+/// ```latex
+/// D_KY = j + (λ_1 + ... + λ_j) / |λ_{j+1}|
+/// ```
+/// where j is the largest number of exponents whose sum is still non-negative: a j dimensional
+/// volume on the attractor doesn't shrink, a j+1 dimensional one does, and D_KY interpolates between them.
+/// Conjectured to equal the information dimension
+pub fn kaplan_yorke_dimension(spectrum: &[f64]) -> f64 {
+    let mut sum = 0.0;
+    for (j, lambda) in spectrum.iter().enumerate() {
+        if sum + lambda < 0.0 {
+            return j as f64 + (sum / lambda.abs());
+        }
+        sum += lambda;
+    }
+    // never contracts, the whole space
+    spectrum.len() as f64
+}
+
+/// kolmogorov–sinai entropy estimate by pesin's identity: the sum of the positive exponents,
+/// the rate (nats per unit time/iteration) at which the system creates information
+pub fn ks_entropy(spectrum: &[f64]) -> f64 {
+    spectrum.iter().filter(|l| **l > 0.0).sum()
+}
+
+/// time for an error of size `initial_error` to grow to `tolerance` at the largest exponent's rate,
+/// ln(tolerance / initial_error) / λ_1. Infinite when nothing grows
+pub fn predictability_horizon(largest: f64, initial_error: f64, tolerance: f64) -> f64 {
+    if largest <= 0.0 {
+        return f64::INFINITY;
+    }
+    (tolerance / initial_error).ln() / largest
+}
+
+/// everything derived from the spectrum, re: [`diagnose`]
+#[derive(Debug, Clone)]
+pub struct Diagnostics {
+    pub spectrum: Vec<f64>,
+    pub kaplan_yorke: f64,
+    pub ks_entropy: f64,
+    /// for an initial error of 1e-6 growing to 1, re: [`predictability_horizon`]
+    pub predictability_horizon: f64
+}
+
+impl Diagnostics {
+    pub fn from_spectrum(spectrum: Vec<f64>) -> Self {
+        Diagnostics {
+            kaplan_yorke: kaplan_yorke_dimension(&spectrum),
+            ks_entropy: ks_entropy(&spectrum),
+            predictability_horizon: predictability_horizon(spectrum.first().copied().unwrap_or(0.0), 1e-6, 1.0),
+            spectrum
+        }
+    }
+}
+
+impl std::fmt::Display for Diagnostics {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let spectrum: Vec<String> = self.spectrum.iter().map(|l| format!("{:.4}", l)).collect();
+        writeln!(f, "lyapunov spectrum: [{}]", spectrum.join(", "))?;
+        writeln!(f, "kaplan-yorke dimension: {:.4}", self.kaplan_yorke)?;
+        writeln!(f, "ks entropy (pesin): {:.4}", self.ks_entropy)?;
+        write!(f, "predictability horizon (1e-6 -> 1): {:.4}", self.predictability_horizon)
+    }
+}
+
+/// spectrum and derived quantities of a flow, re: [`lyapunov_spectrum`]
+pub fn diagnose(sys: &impl DynamicalSystem, x0: &[f64], dt: f64, steps: usize, transient: usize, progress: &Progress) -> Option<Diagnostics> {
+    lyapunov_spectrum(sys, x0, dt, steps, transient, progress).map(Diagnostics::from_spectrum)
+}
+
+/// spectrum and derived quantities of a map, re: [`lyapunov_spectrum_map`]
+pub fn diagnose_map(map: &impl Map, x0: &[f64], iterations: usize, transient: usize, progress: &Progress) -> Option<Diagnostics> {
+    lyapunov_spectrum_map(map, x0, iterations, transient, progress).map(Diagnostics::from_spectrum)
+}
+
+/// rosenstein's divergence curve from a recorded series (states, or a delay embedding of a
+/// scalar one): every point is paired with its nearest neighbour outside the `theiler` window
+/// and the pair is followed forward
+/// ```latex
+/// y(k) = ⟨ln |x_{i+k} - x_{j(i)+k}|⟩_i,    k = 0 .. max_steps
+/// ```
+/// no equations needed, just data. y rises with slope λ1 (per sample) until the separation
+/// reaches the size of the attractor and it levels off, re: [`divergence_fit`]
+pub fn rosenstein(points: &[Vec<f64>], theiler: usize, max_steps: usize) -> Vec<f64> {
+    let n = points.len().saturating_sub(max_steps);
+    let tree = KdTree::new(&points[..n]);
+
+    let mut sums = vec![0.0; max_steps + 1];
+    let mut counts = vec![0_usize; max_steps + 1];
+    for i in 0..n {
+        let Some((j, _)) = tree.nearest(&points[i], |j| i.abs_diff(j) <= theiler) else { continue };
+        for k in 0..=max_steps {
+            let d = distance(&points[i + k], &points[j + k]);
+            // exact repeats (a map stuck on a cycle) have no log
+            if d > 0.0 {
+                sums[k] += d.ln();
+                counts[k] += 1;
+            }
+        }
+    }
+    sums.iter().zip(&counts).map(|(s, c)| if *c == 0 { f64::NAN } else { s / *c as f64 }).collect()
+}
+
+/// kantz's divergence curve: like [`rosenstein`] but averaging over every neighbour within
+/// `radius` rather than following just the nearest one
+/// ```latex
+/// S(k) = ⟨ln( (1/|U_i|) Σ_{j ∈ U_i} |x_{i+k} - x_{j+k}| )⟩_i
+/// ```
+/// averaging inside the log first makes it less sensitive to noise, at the price of having to
+/// pick a radius (a few percent of the attractor's size)
+pub fn kantz(points: &[Vec<f64>], theiler: usize, radius: f64, max_steps: usize) -> Vec<f64> {
+    let n = points.len().saturating_sub(max_steps);
+    let tree = KdTree::new(&points[..n]);
+
+    let mut sums = vec![0.0; max_steps + 1];
+    let mut counts = vec![0_usize; max_steps + 1];
+    for i in 0..n {
+        let neighbours = tree.within(&points[i], radius, |j| i.abs_diff(j) <= theiler);
+        if neighbours.is_empty() {
+            continue;
+        }
+        for k in 0..=max_steps {
+            let mean = neighbours.iter().map(|j| distance(&points[i + k], &points[j + k])).sum::<f64>() / neighbours.len() as f64;
+            if mean > 0.0 {
+                sums[k] += mean.ln();
+                counts[k] += 1;
+            }
+        }
+    }
+    sums.iter().zip(&counts).map(|(s, c)| if *c == 0 { f64::NAN } else { s / *c as f64 }).collect()
+}
+
+/// straight line through a divergence curve against time (`dt` per sample), its slope is the
+/// largest lyapunov exponent per unit time.
+///
+/// `range` (samples `start..end`) picks the linear region by hand, the curve is noisy at the
+/// start and flat once the separations saturate. Without it the straightest stretch is found
+/// like a dimension's scaling region, re: [`ScalingFit::fit`]. None if there aren't two samples to fit
+pub fn divergence_fit(curve: &[f64], dt: f64, range: Option<(usize, usize)>) -> Option<ScalingFit> {
+    let times: Vec<f64> = (0..curve.len()).map(|k| k as f64 * dt).collect();
+    match range {
+        Some((start, end)) => ScalingFit::fit_range(times, curve.to_vec(), start, end),
+        None => ScalingFit::fit(times, curve.to_vec(), 5)
+    }
+}
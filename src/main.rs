@@ -1,44 +1,79 @@
 #![warn(clippy::all, rust_2018_idioms)]
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")] // hide console window on Windows in release
 
-use egui_plot::{Line, Plot, PlotPoints, Points};
-use egui::*;
+use egui_plot::{Line, Plot, PlotPoints};
 use rand::Rng;
+use std::collections::VecDeque;
 
+use chaos_lab::{cli::Args, coupled_map_lattice::CoupledMapLattice, dde::{dde_rk4_step, History, IkedaDelay, MackeyGlass}, double_pendulum::DoublePendulum, dynamical_system::{Map, Parameters}, export::write_space_time_csv, heatmap::Heatmap, kuramoto_sivashinsky::{Etdrk4, KuramotoSivashinsky}, lorenz::Lorenz, lorenz96::Lorenz96, lyapunov::{diagnose, Benettin, Diagnostics, TwoTrajectory}, n_pendulum::{Link, NPendulum}, noise::{Distribution, NoiseSpec}, rng::SeededRng, sde::{AdditiveNoise, EnsembleStats, Scheme}};
 
+use eframe::egui;
 
-use crate::{double_pendulum::DoublePendulum, dynamical_system::Noise, logistic_map::LogisticMap, lorenz::Lorenz};
-
-mod math;
-mod integrators;
-mod logistic_map;
-mod rng;
-mod tests;
-mod lorenz;
-mod double_pendulum;
-mod lyapunov;
-mod dynamical_system;
+mod analysis_ui;
+mod basins_ui;
+mod continuation_ui;
+mod sweep_ui;
+mod worker;
 
-use eframe::egui;
+use analysis_ui::AnalysisPanel;
+use basins_ui::BasinsPanel;
+use continuation_ui::ContinuationPanel;
+use sweep_ui::SweepPanel;
+use worker::Worker;
 
 fn main() {
+    let args = match Args::parse(std::env::args().skip(1)) {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("{}\n\n{}", e, chaos_lab::cli::USAGE);
+            std::process::exit(1);
+        }
+    };
+
+    // any command means we're running headless
+    if args.command.is_some() {
+        if let Err(e) = chaos_lab::cli::run(&args, &mut std::io::stdout()) {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    let seed = match args.seed() {
+        Ok(seed) => seed,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    };
+    println!("seed = {}", seed);
+
     let native_options = eframe::NativeOptions::default();
-    eframe::run_native("My egui App", native_options, Box::new(|cc| Ok(Box::new(MyEguiApp::new(cc)))));
+    if let Err(e) = eframe::run_native("My egui App", native_options, Box::new(move |cc| Ok(Box::new(MyEguiApp::new(cc, seed))))) {
+        eprintln!("failed to start gui: {e}");
+    }
 }
 
 #[derive(Debug,Clone,Copy)]
 enum Simulation {
     Lorenz,
     Dp,
-    Lmap
+    NPendulum,
+    Delay,
+    Spatial,
+    Lmap,
+    Basins,
+    Continuation
 }
 
+#[allow(clippy::derivable_impls)]
 impl std::default::Default for Simulation {
     fn default() -> Self {
         Simulation::Lorenz
     }
 }
 
+#[allow(clippy::match_like_matches_macro)]
 impl PartialEq for Simulation {
     fn eq(&self, other: &Simulation) -> bool {
         // matching a tuple of enums
@@ -46,22 +81,61 @@ impl PartialEq for Simulation {
         match (self,other) {
             (Simulation::Lorenz, Simulation::Lorenz) => true,
             (Simulation::Dp, Simulation::Dp) => true,
+            (Simulation::NPendulum, Simulation::NPendulum) => true,
+            (Simulation::Delay, Simulation::Delay) => true,
+            (Simulation::Spatial, Simulation::Spatial) => true,
             (Simulation::Lmap, Simulation::Lmap) => true,
+            (Simulation::Basins, Simulation::Basins) => true,
+            (Simulation::Continuation, Simulation::Continuation) => true,
             _ => false
         }
     }
 }
 
+#[allow(clippy::to_string_trait_impl)]
 impl ToString for Simulation {
     fn to_string(&self) -> String {
         match self {
             Simulation::Lorenz => String::from("lorenz"),
             Simulation::Dp => String::from("double pendulum"),
-            Simulation::Lmap => String::from("logistic map")
+            Simulation::NPendulum => String::from("n-link pendulum"),
+            Simulation::Delay => String::from("delay equations"),
+            Simulation::Spatial => String::from("space-time"),
+            Simulation::Lmap => String::from("logistic map"),
+            Simulation::Basins => String::from("basins & ftle"),
+            Simulation::Continuation => String::from("continuation")
         }
     }
 }
 
+#[derive(Debug,Clone,Copy,PartialEq)]
+enum DelayModel {
+    MackeyGlass,
+    Ikeda
+}
+
+#[derive(Debug,Clone,Copy,PartialEq)]
+enum SpatialModel {
+    Lorenz96,
+    Lattice,
+    KuramotoSivashinsky
+}
+
+// how many time rows the space-time diagram keeps
+const HOVMOLLER_ROWS: usize = 400;
+// kuramoto–sivashinsky step, etdrk4 is happy with big steps
+const KS_DT: f64 = 0.25;
+
+// constant history the delay equations start from, and their step size
+const DDE_INITIAL: f64 = 1.2;
+const DDE_DT: f64 = 0.05;
+
+// steps the live lyapunov estimate takes each frame, re: `step_live_lyapunov`
+const LIVE_LYAPUNOV_STEPS: usize = 20;
+
+// what the live estimate was started for: simulation, parameters and state dimension
+type LiveKey = (Simulation, SpatialModel, Vec<(String, f64)>, usize);
+
 struct MyEguiApp {
     
     pub simulation: Simulation,
@@ -69,20 +143,64 @@ struct MyEguiApp {
     pub speed: u64,
 
     pub points: Vec<Vec<[f64; 2]>>,
+
+    // every random draw (noise, added trajectories, ensembles) comes from here, re: `SeededRng`
+    pub rng: SeededRng,
+    // how far "Add Trajectory" moves the new initial state from trajectory 0
+    pub perturbation: Distribution,
     // TODO: refactor, definitely a way to compress the points into a single vector
 
     // lorenz
     pub lorenz_system: Lorenz,
     pub lorenz_states: Vec<[f64; 3]>,
+    // strength of the additive noise, 0 is the plain deterministic flow
+    pub lorenz_noise: f64,
+    pub lorenz_ensemble: Option<EnsembleStats>,
+    pub lorenz_ensemble_job: Option<Worker<EnsembleStats>>,
 
     // double pendulum
     pub dp_system: DoublePendulum,
     pub dp_states: Vec<[f64; 4]>,
 
-    pub lmap_system: LogisticMap,
-    pub lmap_state: [f64; 1]
+    // n-link pendulum, state length depends on the number of links
+    pub np_system: NPendulum,
+    pub np_states: Vec<Vec<f64>>,
 
-    
+    // delay equations, the state is the whole history over the last tau
+    pub dde_model: DelayModel,
+    pub mackey_glass: MackeyGlass,
+    pub ikeda: IkedaDelay,
+    pub dde_history: History,
+    pub dde_series: Vec<[f64; 2]>,
+
+    // spatially extended systems, drawn as a hovmöller (space-time) diagram
+    pub spatial_model: SpatialModel,
+    pub lorenz96: Lorenz96,
+    pub lattice: CoupledMapLattice,
+    pub ks: KuramotoSivashinsky,
+    pub ks_stepper: Etdrk4,
+    pub spatial_state: Vec<f64>,
+    pub spatial_time: f64,
+    pub spatial_rows: VecDeque<Vec<f64>>,
+    pub spatial_times: VecDeque<f64>,
+    pub export_status: String,
+    pub spatial_texture: Option<egui::TextureHandle>,
+
+    // bifurcation diagram, lyapunov exponent vs parameter and parameter planes
+    pub sweep: SweepPanel,
+    // basins of attraction, flip times and ftle fields over planes of initial states
+    pub basins: BasinsPanel,
+    pub continuation: ContinuationPanel,
+
+    pub analysis: AnalysisPanel,
+
+    // lyapunov spectrum and friends, for the simulation (and parameters) they were computed for
+    pub diagnostics: Option<(Simulation, Diagnostics)>,
+    pub diagnostics_job: Option<(Simulation, Worker<Diagnostics>)>,
+    pub diagnostics_time: f64,
+    // largest exponent of whatever is playing, (time, estimate) after every renormalisation
+    pub live_lyapunov: Option<(LiveKey, TwoTrajectory)>,
+    pub live_estimates: Vec<[f64; 2]>
 }
 
 impl Default for MyEguiApp {
@@ -94,21 +212,57 @@ impl Default for MyEguiApp {
 
             points: vec![vec![]],
 
+            rng: SeededRng::new(0),
+            perturbation: Distribution::Uniform(0.1),
+
             lorenz_system: Lorenz::default(),
             // default x,y,z
             lorenz_states: vec![[1.0,1.0,1.0]],
+            lorenz_noise: 0.0,
+            lorenz_ensemble: None,
+            lorenz_ensemble_job: None,
 
             dp_system: Default::default(),
             dp_states: vec![[1.0,1.0,1.0,1.0]],
 
-            lmap_system: Default::default(),
-            lmap_state: [0.7]
+            np_system: Default::default(),
+            np_states: vec![n_pendulum_state(NPendulum::default().links())],
+
+            dde_model: DelayModel::MackeyGlass,
+            mackey_glass: MackeyGlass::default(),
+            ikeda: IkedaDelay::default(),
+            dde_history: History::new(&MackeyGlass::default(), &[DDE_INITIAL], DDE_DT),
+            dde_series: vec![],
+
+            spatial_model: SpatialModel::Lorenz96,
+            lorenz96: Lorenz96::default(),
+            lattice: CoupledMapLattice::default(),
+            ks: KuramotoSivashinsky::default(),
+            ks_stepper: Etdrk4::new(&KuramotoSivashinsky::default(), KS_DT),
+            spatial_state: Lorenz96::default().initial_state(),
+            spatial_time: 0.0,
+            spatial_rows: VecDeque::new(),
+            spatial_times: VecDeque::new(),
+            export_status: String::new(),
+            spatial_texture: None,
+
+            sweep: SweepPanel::default(),
+            basins: BasinsPanel::default(),
+            continuation: ContinuationPanel::default(),
+
+            analysis: AnalysisPanel::default(),
+
+            diagnostics: None,
+            diagnostics_job: None,
+            diagnostics_time: 200.0,
+            live_lyapunov: None,
+            live_estimates: vec![]
         }
     }
 }
 
 impl MyEguiApp {
-    fn new(cc: &eframe::CreationContext<'_>) -> Self {
+    fn new(_cc: &eframe::CreationContext<'_>, seed: u64) -> Self {
         // Customize egui here with cc.egui_ctx.set_fonts and cc.egui_ctx.set_visuals.
         // Restore app state using cc.storage (requires the "persistence" feature).
         // Use the cc.gl (a glow::Context) to create graphics shaders and buffers that you can use
@@ -116,7 +270,24 @@ impl MyEguiApp {
         Self::default();
         
         // state is stored int the struct; egui is stateless
-        MyEguiApp::default()
+        MyEguiApp { rng: SeededRng::new(seed), ..MyEguiApp::default() }
+    }
+
+    /// back to the default initial state(s) for every simulation, keeping the current parameters
+    fn reset_trajectories(&mut self) {
+        self.points = MyEguiApp::default().points;
+        self.lorenz_states = MyEguiApp::default().lorenz_states;
+        self.dp_states = MyEguiApp::default().dp_states;
+        self.np_states = vec![n_pendulum_state(self.np_system.links())];
+        self.dde_history = self.new_dde_history();
+        self.dde_series = vec![];
+        // replay the same random draws after a reset
+        self.rng.reset();
+        self.spatial_state = self.new_spatial_state();
+        self.spatial_time = 0.0;
+        self.spatial_rows.clear();
+        self.spatial_times.clear();
+        self.live_lyapunov = None;
     }
 
     fn ui_top_bar(&mut self, ui: &mut egui::Ui) {
@@ -176,12 +347,8 @@ impl MyEguiApp {
                     // rect is shape, response is current state of the widget
                     let (rect, response) = ui.allocate_at_least(size, egui::Sense::click());
                     
-                    // TODO : make this more generic/applicable to all simulations
                     if response.clicked() {
-                        self.points = MyEguiApp::default().points;
-                        self.lorenz_states = MyEguiApp::default().lorenz_states;
-                        
-                        self.dp_states = MyEguiApp::default().dp_states;
+                        self.reset_trajectories();
 
                         // pause on reset, annoying to have it continue to play?
                         if self.is_playing {
@@ -209,24 +376,40 @@ impl MyEguiApp {
                 });
 
                 // default state
-                let mut before = self.simulation;
+                let before = self.simulation;
                 // dropdown menu for simulation selection
                 egui::ComboBox::from_label("Select one!")
                 .selected_text(self.simulation.to_string())
                 .show_ui(ui, |ui| {
                     ui.selectable_value(&mut self.simulation, Simulation::Lorenz, Simulation::Lorenz.to_string());
                     ui.selectable_value(&mut self.simulation, Simulation::Dp, Simulation::Dp.to_string());
+                    ui.selectable_value(&mut self.simulation, Simulation::NPendulum, Simulation::NPendulum.to_string());
+                    ui.selectable_value(&mut self.simulation, Simulation::Delay, Simulation::Delay.to_string());
+                    ui.selectable_value(&mut self.simulation, Simulation::Spatial, Simulation::Spatial.to_string());
                     ui.selectable_value(&mut self.simulation, Simulation::Lmap, Simulation::Lmap.to_string());
+                    ui.selectable_value(&mut self.simulation, Simulation::Basins, Simulation::Basins.to_string());
+                    ui.selectable_value(&mut self.simulation, Simulation::Continuation, Simulation::Continuation.to_string());
                 });
                 // on change
                 if self.simulation != before {
                     // reset graph(s)
-                    self.points = MyEguiApp::default().points;
-                    self.lorenz_states = MyEguiApp::default().lorenz_states;
-                    self.dp_states = MyEguiApp::default().dp_states;
+                    self.reset_trajectories();
                     // stop simulation when switching
                     self.is_playing = false;
                 }
+
+                // seed for everything random, editing it restarts the random stream
+                let mut seed = self.rng.seed();
+                ui.label("seed");
+                if ui.add(egui::DragValue::new(&mut seed)).changed() {
+                    self.rng = SeededRng::new(seed);
+                }
+                if ui.button("new seed").clicked() {
+                    // u32 so the seed survives the f64 round trip through the drag value
+                    self.rng = SeededRng::new(rand::random::<u32>() as u64);
+                }
+
+                ui.checkbox(&mut self.analysis.open, "analysis");
                 
             });
     }
@@ -240,6 +423,8 @@ impl MyEguiApp {
             ui.add(egui::Slider::new(&mut self.lorenz_system.sigma, 0.0..=100.0));
             ui.label("beta");
             ui.add(egui::Slider::new(&mut self.lorenz_system.beta, 0.0..=100.0));
+            ui.label("noise");
+            ui.add(egui::Slider::new(&mut self.lorenz_noise, 0.0..=10.0));
         });
         
         // pushing points
@@ -248,15 +433,20 @@ impl MyEguiApp {
             // discard z value or self.lorenz_state[2]
             // points[0] is *always* user controlled trajectory
             
+            let noisy = AdditiveNoise { system: self.lorenz_system, sigma: self.lorenz_noise };
             for i in 0..self.lorenz_states.len() {
                 // maybe change t, dt to state variables? not really sure.
-                crate::integrators::rk4_step(&self.lorenz_system, &mut self.lorenz_states[i], 0.0, 0.01);
+                if self.lorenz_noise > 0.0 {
+                    chaos_lab::integrators::euler_maruyama_step(&noisy, &mut self.lorenz_states[i], 0.0, 0.01, &mut self.rng);
+                } else {
+                    chaos_lab::integrators::rk4_step(&self.lorenz_system, &mut self.lorenz_states[i], 0.0, 0.01);
+                }
                 self.points[i].push([self.lorenz_states[i][0], self.lorenz_states[i][1]]);
             }
             
             
         // only allow modifcation of the inital state, when paused and no line drawn on screen
-        } else if self.points[0].len() == 0 { 
+        } else if self.points[0].is_empty() { 
             // sliders for inital state
             ui.with_layout(egui::Layout::left_to_right(egui::Align::TOP), |ui| {
                 ui.label("x");
@@ -271,14 +461,10 @@ impl MyEguiApp {
             
         }
 
-        if ui.button("Add Trajectory").clicked() {
-            let mut rng = rand::rng();
+        if ui_add_trajectory(ui, &mut self.perturbation) {
             // randomized default state
-            self.lorenz_states.push([
-                self.lorenz_states[0][0] + rng.random_range(0.0..5.0),
-                self.lorenz_states[0][1] + rng.random_range(0.0..5.00),
-                self.lorenz_states[0][2] + rng.random_range(0.0..5.0)
-            ]);
+            let state = NoiseSpec::new().state_components(3, self.perturbation).perturb_state(&self.lorenz_states[0], &mut self.rng);
+            self.lorenz_states.push([state[0], state[1], state[2]]);
 
             // adding another points vector so we have something push the new points to
             self.points.push(vec![]);
@@ -287,7 +473,7 @@ impl MyEguiApp {
         let mut lines = vec![];
         for i in 0..self.points.len() {
             // plotting
-            let cur_points: PlotPoints = self.points[i].iter().map(|i| {
+            let cur_points: PlotPoints<'_> = self.points[i].iter().map(|i| {
                     [i[0],i[1]]
             }).collect();
 
@@ -305,8 +491,52 @@ impl MyEguiApp {
                 plot_ui.line(line);
             }
         });
-    
-        
+
+        ui.collapsing("noisy ensemble", |ui| {
+            ui.label("many noisy copies of the initial state vs. the noise free flow");
+            if ui.button("Run Ensemble").clicked() {
+                let noisy = AdditiveNoise { system: self.lorenz_system, sigma: self.lorenz_noise };
+                let (x0, seed) = (self.lorenz_states[0], self.rng.seed());
+                self.lorenz_ensemble_job = Some(Worker::spawn(ui.ctx(), 0, move |progress, send| {
+                    if let Some(stats) = chaos_lab::sde::ensemble_stats(&noisy, &x0, 100, 2000, 0.01, Scheme::EulerMaruyama, seed, progress) {
+                        send(stats);
+                    }
+                }));
+            }
+
+            if let Some(job) = &self.lorenz_ensemble_job {
+                let finished = job.is_finished();
+                if let Some(stats) = job.poll().pop() {
+                    self.lorenz_ensemble = Some(stats);
+                }
+                if finished || job.ui(ui) {
+                    self.lorenz_ensemble_job = None;
+                }
+            }
+
+            if let Some(stats) = &self.lorenz_ensemble {
+                // x component only, it's the one that flips between the wings
+                let series = |f: &dyn Fn(usize) -> f64| -> PlotPoints<'_> {
+                    stats.times.iter().enumerate().map(|(k, t)| [*t, f(k)]).collect()
+                };
+                let mean = Line::new("mean", series(&|k| stats.mean[k][0]));
+                let upper = Line::new("mean + std", series(&|k| stats.mean[k][0] + stats.std_dev[k][0]));
+                let lower = Line::new("mean - std", series(&|k| stats.mean[k][0] - stats.std_dev[k][0]));
+                let deterministic = Line::new("deterministic", series(&|k| stats.deterministic[k][0]));
+
+                Plot::new("Lorenz Ensemble")
+                .view_aspect(3.0)
+                .x_axis_label("t")
+                .y_axis_label("x")
+                .legend(egui_plot::Legend::default())
+                .show(ui, |plot_ui| {
+                    plot_ui.line(mean);
+                    plot_ui.line(upper);
+                    plot_ui.line(lower);
+                    plot_ui.line(deterministic);
+                });
+            }
+        });
     }
 
     fn ui_dp_simulation(&mut self, ui: &mut egui::Ui) {
@@ -329,13 +559,13 @@ impl MyEguiApp {
 
                     for i in 0..self.dp_states.len() {
                         // maybe change t, dt to state variables? not really sure.
-                        crate::integrators::rk4_step(&self.dp_system, &mut self.dp_states[i], 0.0, 0.01);
+                        chaos_lab::integrators::rk4_step(&self.dp_system, &mut self.dp_states[i], 0.0, 0.01);
                         // updating points at i
                         self.points[i].push([self.dp_states[i][0], self.dp_states[i][1]]);
                     }
                     
                 // manage inital state, only when on pause   
-                } else if self.points[0].len() == 0 { 
+                } else if self.points[0].is_empty() { 
                     // sliders for inital state
                     ui.with_layout(egui::Layout::left_to_right(egui::Align::TOP), |ui| {
                         
@@ -352,15 +582,10 @@ impl MyEguiApp {
                 }
 
 
-                if ui.button("Add Trajectory").clicked() {
-                    let mut rng = rand::rng();
+                if ui_add_trajectory(ui, &mut self.perturbation) {
                     // randomized default state
-                    self.dp_states.push([
-                        self.dp_states[0][0] + rng.random_range(0.0..5.0),
-                        self.dp_states[0][1] + rng.random_range(0.0..5.0),
-                        self.dp_states[0][2] + rng.random_range(0.0..5.0),
-                        self.dp_states[0][3] + rng.random_range(0.0..5.0)
-                    ]);
+                    let state = NoiseSpec::new().state_components(4, self.perturbation).perturb_state(&self.dp_states[0], &mut self.rng);
+                    self.dp_states.push([state[0], state[1], state[2], state[3]]);
 
                     // adding another points vector so we have something push the new points to
                     self.points.push(vec![]);
@@ -371,7 +596,7 @@ impl MyEguiApp {
                 let mut lines = vec![];
 
                 for i in 0..self.points.len() {
-                    let cur_points: PlotPoints = self.points[i].iter().map(|i| {
+                    let cur_points: PlotPoints<'_> = self.points[i].iter().map(|i| {
                         [i[0],i[1]]
                     }).collect();
                     let line = Line::new("Double Pendulum", cur_points);
@@ -380,6 +605,11 @@ impl MyEguiApp {
                 
 
                 
+                // physical view of every trajectory
+                let chain = NPendulum::from_links(&[(self.dp_system.m1, self.dp_system.l1), (self.dp_system.m2, self.dp_system.l2)]);
+                let bobs: Vec<Vec<[f64; 2]>> = self.dp_states.iter().map(|s| chain.positions(&s[0..2])).collect();
+                draw_pendulums(ui, &bobs, chain.reach());
+
                 Plot::new("Double Pendulum")
                 .view_aspect(2.0)
                 .x_axis_label("Theta 1")
@@ -393,58 +623,539 @@ impl MyEguiApp {
                 
     }
 
-    fn ui_lmap_simulation(&mut self, ui: &mut egui::Ui) {
-        //if ui.add(egui::Slider::new(&mut self.lmap_system.r,0.3..=0.4)).changed() {
-        //self.lmap_state = MyEguiApp::default().lmap_state;
-        //};
-        
-        // running simulation
-        if self.is_playing {
+    fn ui_np_simulation(&mut self, ui: &mut egui::Ui) {
+        let mut links = self.np_system.links();
+        ui.horizontal(|ui| {
+            ui.label("links");
+            ui.add(egui::Slider::new(&mut links, 1..=10));
+        });
 
-            for s in 2500..4000 {
-                let r = s as f64 / 100.0;
+        // changing the number of links changes the dimension of the system, so start over
+        if links != self.np_system.links() {
+            self.np_system.chain.resize(links, Link::default());
+            self.np_states = vec![n_pendulum_state(links)];
+            self.points = vec![vec![]];
+            self.is_playing = false;
+        }
 
-                for _ in 0..50 {
-                    // maybe change t, dt to state variables? not really sure.
-                    crate::integrators::rk4_step(&LogisticMap {r: r}, &mut self.lmap_state, 0.0, 0.01);
-                    // discard z value or self.lorenz_state[2]
-                    self.points[0].push([r, self.lmap_state[0]]);
-                }
+        // sliders for every mass and length
+        ui.horizontal_wrapped(|ui| {
+            for i in 0..links {
+                ui.label(format!("m{}", i + 1));
+                ui.add(egui::Slider::new(&mut self.np_system.chain[i].mass, 0.1..=10.0));
+                ui.label(format!("l{}", i + 1));
+                ui.add(egui::Slider::new(&mut self.np_system.chain[i].length, 0.1..=10.0));
+            }
+        });
 
-                self.lmap_state = MyEguiApp::default().lmap_state;
+        if self.is_playing {
+            for i in 0..self.np_states.len() {
+                chaos_lab::integrators::rk4_step(&self.np_system, &mut self.np_states[i], 0.0, 0.01);
+                // first vs last angle, the last link is where the chaos shows up first
+                self.points[i].push([self.np_states[i][0], self.np_states[i][links - 1]]);
             }
-            
-            
-            
-            self.is_playing = !self.is_playing;
+        } else if self.points[0].is_empty() {
+            ui.horizontal_wrapped(|ui| {
+                for i in 0..links {
+                    ui.label(format!("theta{}", i + 1));
+                    ui.add(egui::Slider::new(&mut self.np_states[0][i], -std::f64::consts::PI..=std::f64::consts::PI));
+                }
+                ui.heading("initial state");
+            });
         }
 
-        // plotting points
-        let cur_points: PlotPoints = self.points[0].iter().map(|i| {
+        if ui_add_trajectory(ui, &mut self.perturbation) {
+            // only nudge the angles, the chains start at rest
+            let state = NoiseSpec::new().state_components(links, self.perturbation).perturb_state(&self.np_states[0], &mut self.rng);
+            self.np_states.push(state);
+
+            self.points.push(vec![]);
+        }
+
+        let bobs: Vec<Vec<[f64; 2]>> = self.np_states.iter().map(|s| self.np_system.positions(&s[0..links])).collect();
+        draw_pendulums(ui, &bobs, self.np_system.reach());
+
+        let mut lines = vec![];
+        for i in 0..self.points.len() {
+            let cur_points: PlotPoints<'_> = self.points[i].iter().map(|i| {
                 [i[0],i[1]]
-        }).collect();
+            }).collect();
+            lines.push(Line::new(format!("Pendulum {}", i), cur_points));
+        }
 
-        let pts = Points::new("pts", cur_points).radius(0.9).color(egui::Color32::LIGHT_BLUE);
-        
-        Plot::new("my_plot")
+        Plot::new("N-link Pendulum")
         .view_aspect(2.0)
-        .x_axis_label("r")
-        .y_axis_label("x_n")
+        .x_axis_label("Theta 1")
+        .y_axis_label(format!("Theta {}", links))
         .show(ui, |plot_ui| {
-            plot_ui.points(pts);
+            for line in lines {
+                plot_ui.line(line);
+            }
         });
     }
+
+    fn new_dde_history(&self) -> History {
+        match self.dde_model {
+            DelayModel::MackeyGlass => History::new(&self.mackey_glass, &[DDE_INITIAL], DDE_DT),
+            DelayModel::Ikeda => History::new(&self.ikeda, &[DDE_INITIAL], DDE_DT)
+        }
+    }
+
+    fn ui_dde_simulation(&mut self, ui: &mut egui::Ui) {
+        let before = (self.dde_model, self.mackey_glass.tau, self.ikeda.tau);
+        ui.horizontal(|ui| {
+            egui::ComboBox::from_label("model")
+            .selected_text(format!("{:?}", self.dde_model))
+            .show_ui(ui, |ui| {
+                ui.selectable_value(&mut self.dde_model, DelayModel::MackeyGlass, "MackeyGlass");
+                ui.selectable_value(&mut self.dde_model, DelayModel::Ikeda, "Ikeda");
+            });
+
+            match self.dde_model {
+                DelayModel::MackeyGlass => {
+                    ui.label("beta");
+                    ui.add(egui::Slider::new(&mut self.mackey_glass.beta, 0.0..=1.0));
+                    ui.label("gamma");
+                    ui.add(egui::Slider::new(&mut self.mackey_glass.gamma, 0.0..=1.0));
+                    ui.label("n");
+                    ui.add(egui::Slider::new(&mut self.mackey_glass.n, 1.0..=20.0));
+                    ui.label("tau");
+                    ui.add(egui::Slider::new(&mut self.mackey_glass.tau, 0.1..=50.0));
+                }
+                DelayModel::Ikeda => {
+                    ui.label("mu");
+                    ui.add(egui::Slider::new(&mut self.ikeda.mu, 0.0..=50.0));
+                    ui.label("tau");
+                    ui.add(egui::Slider::new(&mut self.ikeda.tau, 0.1..=20.0));
+                }
+            }
+        });
+
+        // the history buffer is sized for the delay, so a new delay (or model) starts over
+        if before != (self.dde_model, self.mackey_glass.tau, self.ikeda.tau) {
+            self.dde_history = self.new_dde_history();
+            self.dde_series = vec![];
+            self.points = vec![vec![]];
+        }
+
+        if self.is_playing {
+            let tau = match self.dde_model {
+                DelayModel::MackeyGlass => self.mackey_glass.tau,
+                DelayModel::Ikeda => self.ikeda.tau
+            };
+            for _ in 0..20 {
+                match self.dde_model {
+                    DelayModel::MackeyGlass => dde_rk4_step(&self.mackey_glass, &mut self.dde_history),
+                    DelayModel::Ikeda => dde_rk4_step(&self.ikeda, &mut self.dde_history)
+                }
+                let t = self.dde_history.time();
+                let x = self.dde_history.current()[0];
+                // delay embedding, x(t) against x(t - tau), is the natural phase portrait
+                self.points[0].push([self.dde_history.at(t - tau)[0], x]);
+                self.dde_series.push([t, x]);
+            }
+        }
+
+        let phase: PlotPoints<'_> = self.points[0].iter().copied().collect();
+        Plot::new("Delay Phase Portrait")
+        .view_aspect(2.0)
+        .x_axis_label("x(t - tau)")
+        .y_axis_label("x(t)")
+        .show(ui, |plot_ui| {
+            plot_ui.line(Line::new("phase portrait", phase));
+        });
+
+        let series: PlotPoints<'_> = self.dde_series.iter().copied().collect();
+        Plot::new("Delay Time Series")
+        .view_aspect(4.0)
+        .x_axis_label("t")
+        .y_axis_label("x")
+        .show(ui, |plot_ui| {
+            plot_ui.line(Line::new("x(t)", series));
+        });
+    }
+
+    fn new_spatial_state(&mut self) -> Vec<f64> {
+        match self.spatial_model {
+            SpatialModel::Lorenz96 => self.lorenz96.initial_state(),
+            // random start for every site, from the seeded stream
+            SpatialModel::Lattice => (0..self.lattice.sites).map(|_| self.rng.random_range(0.0..1.0)).collect(),
+            SpatialModel::KuramotoSivashinsky => self.ks.initial_state()
+        }
+    }
+
+    fn ui_spatial_simulation(&mut self, ui: &mut egui::Ui) {
+        let before = (self.spatial_model, self.lorenz96.sites(), self.lattice.sites, self.ks.n, self.ks.length);
+        ui.horizontal(|ui| {
+            egui::ComboBox::from_label("model")
+            .selected_text(format!("{:?}", self.spatial_model))
+            .show_ui(ui, |ui| {
+                ui.selectable_value(&mut self.spatial_model, SpatialModel::Lorenz96, "Lorenz96");
+                ui.selectable_value(&mut self.spatial_model, SpatialModel::Lattice, "Lattice");
+                ui.selectable_value(&mut self.spatial_model, SpatialModel::KuramotoSivashinsky, "KuramotoSivashinsky");
+            });
+
+            match self.spatial_model {
+                SpatialModel::Lorenz96 => {
+                    ui.label("N");
+                    let mut n = self.lorenz96.sites();
+                    if ui.add(egui::Slider::new(&mut n, 4..=200)).changed() {
+                        self.lorenz96 = Lorenz96::new(n, self.lorenz96.forcing);
+                    }
+                    ui.label("F");
+                    ui.add(egui::Slider::new(&mut self.lorenz96.forcing, 0.0..=20.0));
+                }
+                SpatialModel::Lattice => {
+                    ui.label("sites");
+                    ui.add(egui::Slider::new(&mut self.lattice.sites, 4..=400));
+                    ui.label("r");
+                    ui.add(egui::Slider::new(&mut self.lattice.local.r, 2.5..=4.0));
+                    ui.label("epsilon");
+                    ui.add(egui::Slider::new(&mut self.lattice.epsilon, 0.0..=1.0));
+                }
+                SpatialModel::KuramotoSivashinsky => {
+                    // grid has to stay a power of two for the fft
+                    egui::ComboBox::from_label("N")
+                    .selected_text(self.ks.n.to_string())
+                    .show_ui(ui, |ui| {
+                        for n in [64, 128, 256, 512] {
+                            ui.selectable_value(&mut self.ks.n, n, n.to_string());
+                        }
+                    });
+                    ui.label("L");
+                    ui.add(egui::Slider::new(&mut self.ks.length, 10.0..=400.0));
+                }
+            }
+        });
+
+        // different number of sites (or domain), different system
+        if before != (self.spatial_model, self.lorenz96.sites(), self.lattice.sites, self.ks.n, self.ks.length) {
+            self.ks_stepper = Etdrk4::new(&self.ks, KS_DT);
+            self.spatial_state = self.new_spatial_state();
+            self.spatial_time = 0.0;
+            self.spatial_rows.clear();
+            self.spatial_times.clear();
+        }
+
+        if self.is_playing {
+            for _ in 0..2 {
+                match self.spatial_model {
+                    SpatialModel::Lorenz96 => {
+                        // a row every 0.05 time units
+                        for _ in 0..5 {
+                            chaos_lab::integrators::rk4_step(&self.lorenz96, &mut self.spatial_state, 0.0, 0.01);
+                        }
+                    }
+                    SpatialModel::Lattice => self.spatial_state = self.lattice.step(&self.spatial_state),
+                    SpatialModel::KuramotoSivashinsky => self.ks_stepper.step(&mut self.spatial_state)
+                }
+                // time per row: 5 rk4 steps, one iteration, one etdrk4 step
+                self.spatial_time += match self.spatial_model {
+                    SpatialModel::Lorenz96 => 0.05,
+                    SpatialModel::Lattice => 1.0,
+                    SpatialModel::KuramotoSivashinsky => KS_DT
+                };
+                self.spatial_rows.push_back(self.spatial_state.clone());
+                self.spatial_times.push_back(self.spatial_time);
+                if self.spatial_rows.len() > HOVMOLLER_ROWS {
+                    self.spatial_rows.pop_front();
+                    self.spatial_times.pop_front();
+                }
+            }
+        }
+
+        ui.horizontal(|ui| {
+            if ui.button("Export CSV").clicked() {
+                let positions = match self.spatial_model {
+                    SpatialModel::KuramotoSivashinsky => self.ks.grid(),
+                    _ => (0..self.spatial_state.len()).map(|i| i as f64).collect()
+                };
+                let rows: Vec<Vec<f64>> = self.spatial_rows.iter().cloned().collect();
+                let times: Vec<f64> = self.spatial_times.iter().copied().collect();
+                let path = format!("{:?}.csv", self.spatial_model).to_lowercase();
+                self.export_status = match write_space_time_csv(&path, &positions, &times, &rows) {
+                    Ok(()) => format!("wrote {} rows to {}", rows.len(), path),
+                    Err(e) => format!("export failed: {}", e)
+                };
+            }
+            ui.label(&self.export_status);
+        });
+
+        ui.label("space →, time ↓");
+        let rows: Vec<Vec<f64>> = self.spatial_rows.iter().cloned().collect();
+        show_heatmap(ui, &mut self.spatial_texture, "hovmoller", &Heatmap::from_rows(&rows, None), 400.0);
+    }
+
+    fn ui_lmap_simulation(&mut self, ui: &mut egui::Ui) {
+        // play computes the sweep once, it's a picture rather than an animation
+        if self.is_playing {
+            self.sweep.compute(ui.ctx());
+            self.is_playing = false;
+        }
+
+        self.sweep.ui(ui);
+    }
+
+    /// parameters of the system the live lyapunov estimate follows
+    fn live_parameters(&self) -> Vec<(String, f64)> {
+        match (self.simulation, self.spatial_model) {
+            (Simulation::Lorenz, _) => self.lorenz_system.parameters(),
+            (Simulation::Dp, _) => self.dp_system.parameters(),
+            (Simulation::NPendulum, _) => self.np_system.parameters(),
+            (Simulation::Spatial, SpatialModel::Lorenz96) => self.lorenz96.parameters(),
+            (Simulation::Spatial, SpatialModel::Lattice) => self.lattice.parameters(),
+            (Simulation::Spatial, SpatialModel::KuramotoSivashinsky) => self.ks.parameters(),
+            (Simulation::Delay | Simulation::Lmap | Simulation::Basins | Simulation::Continuation, _) => vec![]
+        }
+    }
+
+    /// moves the live lyapunov estimate along with the simulation, starting over from
+    /// trajectory 0 whenever the simulation or its parameters change
+    fn step_live_lyapunov(&mut self) {
+        if !self.is_playing {
+            return;
+        }
+
+        let x0 = match self.simulation {
+            Simulation::Lorenz => self.lorenz_states[0].to_vec(),
+            Simulation::Dp => self.dp_states[0].to_vec(),
+            Simulation::NPendulum => self.np_states[0].clone(),
+            Simulation::Spatial => self.spatial_state.clone(),
+            // the delay state is a whole history, the logistic and basin tabs are pictures
+            Simulation::Delay | Simulation::Lmap | Simulation::Basins | Simulation::Continuation => {
+                self.live_lyapunov = None;
+                return;
+            }
+        };
+        let key = (self.simulation, self.spatial_model, self.live_parameters(), x0.len());
+        if !matches!(&self.live_lyapunov, Some((k, _)) if *k == key) {
+            self.live_lyapunov = Some((key, TwoTrajectory::new(&x0, Benettin { transient: 100, ..Benettin::default() })));
+            self.live_estimates.clear();
+        }
+
+        let Some((_, estimator)) = &mut self.live_lyapunov else { return };
+        // several steps a frame, one would take minutes to converge
+        for _ in 0..LIVE_LYAPUNOV_STEPS {
+            let estimate = match (self.simulation, self.spatial_model) {
+                (Simulation::Lorenz, _) => estimator.step(|x| chaos_lab::integrators::rk4_step(&self.lorenz_system, x, 0.0, 0.01), 0.01),
+                (Simulation::Dp, _) => estimator.step(|x| chaos_lab::integrators::rk4_step(&self.dp_system, x, 0.0, 0.01), 0.01),
+                (Simulation::NPendulum, _) => estimator.step(|x| chaos_lab::integrators::rk4_step(&self.np_system, x, 0.0, 0.01), 0.01),
+                (Simulation::Spatial, SpatialModel::Lorenz96) => estimator.step(|x| chaos_lab::integrators::rk4_step(&self.lorenz96, x, 0.0, 0.01), 0.01),
+                (Simulation::Spatial, SpatialModel::Lattice) => estimator.step(|x| {
+                    let next = self.lattice.step(x);
+                    x.copy_from_slice(&next);
+                }, 1.0),
+                (Simulation::Spatial, SpatialModel::KuramotoSivashinsky) => estimator.step(|x| self.ks_stepper.step(x), KS_DT),
+                (Simulation::Delay | Simulation::Lmap | Simulation::Basins | Simulation::Continuation, _) => None
+            };
+            if let Some(estimate) = estimate {
+                self.live_estimates.push([estimator.elapsed(), estimate]);
+            }
+        }
+    }
+
+    /// lyapunov spectrum, kaplan–yorke dimension, ks entropy and predictability horizon of the
+    /// current simulation, started from trajectory 0
+    fn ui_diagnostics(&mut self, ui: &mut egui::Ui) {
+        let dt = 0.01;
+        ui.horizontal(|ui| {
+            ui.label("integration time");
+            ui.add(egui::Slider::new(&mut self.diagnostics_time, 10.0..=2000.0).logarithmic(true));
+        });
+        let steps = (self.diagnostics_time / dt) as usize;
+
+        match &self.live_lyapunov {
+            Some((_, estimator)) if !self.live_estimates.is_empty() => {
+                ui.label(format!("live largest exponent: λ1 ≈ {:.4}", estimator.estimate()));
+                let estimates: PlotPoints<'_> = self.live_estimates.iter().copied().collect();
+                Plot::new("live lyapunov")
+                .view_aspect(4.0)
+                .x_axis_label("t")
+                .show(ui, |plot_ui| {
+                    plot_ui.line(Line::new("λ1", estimates));
+                });
+            }
+            _ => {
+                ui.label("press play for a live estimate of the largest exponent");
+            }
+        }
+        ui.separator();
+
+        if ui.button("Compute").clicked() {
+            // copies of the system and state go to the worker, the simulation keeps running meanwhile
+            let ctx = ui.ctx();
+            let job = match self.simulation {
+                Simulation::Lorenz => {
+                    let (sys, x0) = (self.lorenz_system, self.lorenz_states[0]);
+                    Some(Worker::spawn(ctx, 0, move |progress, send| if let Some(diagnostics) = diagnose(&sys, &x0, dt, steps, 1000, progress) { send(diagnostics) }))
+                }
+                Simulation::Dp => {
+                    let (sys, x0) = (self.dp_system, self.dp_states[0]);
+                    Some(Worker::spawn(ctx, 0, move |progress, send| if let Some(diagnostics) = diagnose(&sys, &x0, dt, steps, 0, progress) { send(diagnostics) }))
+                }
+                Simulation::NPendulum => {
+                    let (sys, x0) = (self.np_system.clone(), self.np_states[0].clone());
+                    Some(Worker::spawn(ctx, 0, move |progress, send| if let Some(diagnostics) = diagnose(&sys, &x0, dt, steps, 0, progress) { send(diagnostics) }))
+                }
+                // 40 dimensions make the tangent space expensive, a tenth of the time still converges reasonably
+                Simulation::Spatial if self.spatial_model == SpatialModel::Lorenz96 => {
+                    let (sys, x0) = (self.lorenz96, self.spatial_state.clone());
+                    Some(Worker::spawn(ctx, 0, move |progress, send| if let Some(diagnostics) = diagnose(&sys, &x0, dt, steps / 10, 0, progress) { send(diagnostics) }))
+                }
+                _ => None
+            };
+            self.diagnostics_job = job.map(|job| (self.simulation, job));
+        }
+
+        if let Some((simulation, job)) = &self.diagnostics_job {
+            let finished = job.is_finished();
+            if let Some(diagnostics) = job.poll().pop() {
+                self.diagnostics = Some((*simulation, diagnostics));
+            }
+            // cancelling stops the spectrum at its next orthonormalisation
+            if finished || job.ui(ui) {
+                self.diagnostics_job = None;
+            }
+        }
+
+        match &self.diagnostics {
+            Some((simulation, diagnostics)) if *simulation == self.simulation => {
+                for line in diagnostics.to_string().lines() {
+                    ui.label(line);
+                }
+            }
+            _ => {
+                ui.label("Lorenz, the pendulums and Lorenz-96 can be diagnosed");
+            }
+        }
+    }
+}
+
+/// "Add Trajectory" button along with controls for how the new trajectory is perturbed.
+/// returns true when clicked
+fn ui_add_trajectory(ui: &mut egui::Ui, perturbation: &mut Distribution) -> bool {
+    ui.horizontal(|ui| {
+        let clicked = ui.button("Add Trajectory").clicked();
+
+        let width = perturbation.width();
+        egui::ComboBox::from_id_salt("perturbation")
+        .selected_text(perturbation.to_string())
+        .show_ui(ui, |ui| {
+            ui.selectable_value(perturbation, Distribution::Uniform(width), "uniform");
+            ui.selectable_value(perturbation, Distribution::Gaussian(width), "gaussian");
+            ui.selectable_value(perturbation, Distribution::Relative(width), "relative %");
+        });
+
+        let mut width = perturbation.width();
+        if ui.add(egui::DragValue::new(&mut width).speed(0.01).range(0.0..=100.0)).changed() {
+            *perturbation = match perturbation {
+                Distribution::Uniform(_) => Distribution::Uniform(width),
+                Distribution::Gaussian(_) => Distribution::Gaussian(width),
+                Distribution::Relative(_) => Distribution::Relative(width)
+            };
+        }
+
+        clicked
+    }).inner
+}
+
+/// draws a heatmap stretched to the available width, uploading it into `texture`
+/// (created on first use, updated in place after that)
+fn show_heatmap(ui: &mut egui::Ui, texture: &mut Option<egui::TextureHandle>, name: &str, heatmap: &Heatmap, height: f32) {
+    if heatmap.width == 0 || heatmap.height == 0 {
+        return;
+    }
+
+    let rgb: Vec<u8> = heatmap.pixels.iter().flatten().copied().collect();
+    let image = egui::ColorImage::from_rgb([heatmap.width, heatmap.height], &rgb);
+    // nearest so individual sites/cells stay crisp instead of being smeared together
+    let options = egui::TextureOptions::NEAREST;
+    match texture {
+        Some(tex) => tex.set(image, options),
+        None => *texture = Some(ui.ctx().load_texture(name, image, options))
+    }
+
+    if let Some(tex) = texture {
+        ui.add(egui::Image::new(&*tex).fit_to_exact_size(egui::vec2(ui.available_width(), height)));
+    }
+}
+
+/// `heatmap` inside a plot spanning `(label, lo, hi)` on each axis, so the axes read in the
+/// grid's own coordinates. The texture is only uploaded while `texture` is None, reset it
+/// whenever the heatmap changes
+fn show_image_plot(ui: &mut egui::Ui, texture: &mut Option<egui::TextureHandle>, name: &str, heatmap: &Heatmap, (x_label, x_lo, x_hi): (&str, f64, f64), (y_label, y_lo, y_hi): (&str, f64, f64)) {
+    let texture = texture.get_or_insert_with(|| {
+        let rgb: Vec<u8> = heatmap.pixels.iter().flatten().copied().collect();
+        let image = egui::ColorImage::from_rgb([heatmap.width, heatmap.height], &rgb);
+        ui.ctx().load_texture(name, image, egui::TextureOptions::NEAREST)
+    });
+
+    let image = egui_plot::PlotImage::new(
+        name,
+        texture.id(),
+        egui_plot::PlotPoint::new((x_lo + x_hi) / 2.0, (y_lo + y_hi) / 2.0),
+        egui::vec2((x_hi - x_lo) as f32, (y_hi - y_lo) as f32)
+    );
+    Plot::new(format!("{} plot", name))
+    .view_aspect(1.5)
+    .x_axis_label(x_label)
+    .y_axis_label(y_label)
+    .show(ui, |plot_ui| plot_ui.image(image));
+}
+
+/// default initial state for an `n` link chain: every arm at 1 rad, at rest
+fn n_pendulum_state(n: usize) -> Vec<f64> {
+    let mut state = vec![1.0; n];
+    state.extend(vec![0.0; n]);
+    state
+}
+
+/// draws the rods and bobs of one or more pendulum chains hanging from a common pivot
+///
+/// `chains` - bob positions of each chain (re: `NPendulum::positions`)
+///
+/// `reach` - total length of the chain, used to scale the drawing to fit
+fn draw_pendulums(ui: &mut egui::Ui, chains: &[Vec<[f64; 2]>], reach: f64) {
+    let size = egui::vec2(ui.available_width(), 250.0);
+    let (rect, _) = ui.allocate_exact_size(size, egui::Sense::hover());
+    let painter = ui.painter_at(rect);
+    painter.rect_filled(rect, 4.0, ui.visuals().extreme_bg_color);
+
+    let pivot = rect.center();
+    // leave a little margin so the bobs aren't cut off
+    let scale = (0.45 * rect.height()) as f64 / reach.max(1e-6);
+    // flip y, screen coordinates point down
+    let to_screen = |p: &[f64; 2]| egui::pos2(pivot.x + (p[0] * scale) as f32, pivot.y - (p[1] * scale) as f32);
+
+    for (i, chain) in chains.iter().enumerate() {
+        // same golden ratio hue spacing egui_plot uses, so chain i matches line i in the plot
+        let color: egui::Color32 = egui::epaint::Hsva::new((i as f32 * 0.618_034).fract(), 0.85, 0.5, 1.0).into();
+        let mut prev = pivot;
+        for bob in chain {
+            let p = to_screen(bob);
+            painter.line_segment([prev, p], egui::Stroke::new(2.0, color));
+            painter.circle_filled(p, 5.0, color);
+            prev = p;
+        }
+    }
+    painter.circle_filled(pivot, 3.0, ui.visuals().text_color());
 }
 
 impl eframe::App for MyEguiApp {
 
-    fn update(&mut self, ctx: &egui::Context, frame: &mut eframe::Frame) {
-        egui::CentralPanel::default().show(ctx, |ui| {
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        // the analysis follows whatever the sliders say
+        self.analysis.lorenz = self.lorenz_system;
+        self.analysis.dp = self.dp_system;
+        self.analysis.seed = self.rng.seed();
+        self.analysis.show(ctx);
+
+        egui::CentralPanel::default().show(ctx, |ui| egui::ScrollArea::vertical().show(ui, |ui| {
             
             self.ui_top_bar(ui);
             // should always have at least one line being plotted
             // points[0] is *always* user modfied trajectory
-            if self.points.len() == 0 {
+            if self.points.is_empty() {
                 self.points.push(Vec::new());
             }
 
@@ -452,14 +1163,27 @@ impl eframe::App for MyEguiApp {
                 self.ui_lorenz_simulation(ui);
             } else if self.simulation == Simulation::Dp { 
                 self.ui_dp_simulation(ui);
+            } else if self.simulation == Simulation::NPendulum {
+                self.ui_np_simulation(ui);
+            } else if self.simulation == Simulation::Delay {
+                self.ui_dde_simulation(ui);
+            } else if self.simulation == Simulation::Spatial {
+                self.ui_spatial_simulation(ui);
+            } else if self.simulation == Simulation::Basins {
+                self.basins.ui(ui);
+            } else if self.simulation == Simulation::Continuation {
+                self.continuation.ui(ui);
             } else {
                 self.ui_lmap_simulation(ui);
             }
-        });
+
+            self.step_live_lyapunov();
+            ui.collapsing("diagnostics", |ui| self.ui_diagnostics(ui));
+        }));
         // update every 32ms, regardless of user input
         // subtract 100 to intuitively increase speed.
         // realistically we are chaning the length between updates, wo increasing the "speed" will actually increase the
-        ctx.request_repaint_after(std::time::Duration::from_millis((100 as u64).abs_diff(self.speed)));
+        ctx.request_repaint_after(std::time::Duration::from_millis(100_u64.abs_diff(self.speed)));
     }
 }
 
@@ -7,7 +7,7 @@ impl Vec2 {
     pub fn norm(&self) -> f64 {((self.x * self.x) + (self.y * self.y)).sqrt()}
 }
 
-use std::ops::{Add, Sub, Mul};
+use std::ops::{Add, Sub, Mul, Div};
 use std::cmp::PartialEq;
 
 impl Add for Vec2 {
@@ -37,7 +37,292 @@ impl Mul<f64> for Vec2 {
 
 impl PartialEq for Vec2 {
     fn eq(&self, other: &Vec2) -> bool {
-        let e = std::f64::EPSILON;
+        let e = f64::EPSILON;
         (self.x - other.x).abs() < e && (self.y - other.y).abs() < e
     }
 }
+
+/// complex number, for fourier transforms and eigenvalues
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Complex { pub re: f64, pub im: f64 }
+
+impl Complex {
+    pub fn new(re: f64, im: f64) -> Self { Complex { re, im } }
+    pub fn norm(&self) -> f64 { self.re.hypot(self.im) }
+    pub fn conj(&self) -> Self { Complex::new(self.re, -self.im) }
+    /// e^(a + ib) = e^a (cos b + i sin b)
+    pub fn exp(&self) -> Self {
+        let r = self.re.exp();
+        Complex::new(r * self.im.cos(), r * self.im.sin())
+    }
+}
+
+impl Add for Complex {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Complex::new(self.re + rhs.re, self.im + rhs.im)
+    }
+}
+
+impl Sub for Complex {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Complex::new(self.re - rhs.re, self.im - rhs.im)
+    }
+}
+
+impl Mul for Complex {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        Complex::new((self.re * rhs.re) - (self.im * rhs.im), (self.re * rhs.im) + (self.im * rhs.re))
+    }
+}
+
+impl Mul<f64> for Complex {
+    type Output = Self;
+
+    fn mul(self, rhs: f64) -> Self::Output {
+        Complex::new(self.re * rhs, self.im * rhs)
+    }
+}
+
+impl Div for Complex {
+    type Output = Self;
+
+    fn div(self, rhs: Self) -> Self::Output {
+        let d = (rhs.re * rhs.re) + (rhs.im * rhs.im);
+        Complex::new(((self.re * rhs.re) + (self.im * rhs.im)) / d, ((self.im * rhs.re) - (self.re * rhs.im)) / d)
+    }
+}
+
+/// solves the linear system `a * x = b` by gaussian elimination with partial pivoting
+///
+/// `a` - square matrix, stored as a list of rows
+///
+/// `b` - right hand side
+///
+/// returns `None` when the matrix is (numerically) singular
+pub fn solve_linear(a: &[Vec<f64>], b: &[f64]) -> Option<Vec<f64>> {
+    let n = b.len();
+    // augmented matrix so the row swaps carry the rhs along with them
+    let mut m: Vec<Vec<f64>> = a.iter()
+    .zip(b)
+    .map(|(row, bi)| {
+        let mut r = row.clone();
+        r.push(*bi);
+        r
+    })
+    .collect();
+
+    for col in 0..n {
+        // pick the largest pivot in this column to keep round-off under control
+        let pivot = (col..n).max_by(|&i, &j| m[i][col].abs().total_cmp(&m[j][col].abs()))?;
+        if m[pivot][col].abs() < 1e-12 {
+            return None;
+        }
+        m.swap(col, pivot);
+
+        let pivot_row = m[col].clone();
+        for row in m.iter_mut().skip(col + 1) {
+            let factor = row[col] / pivot_row[col];
+            for (x, p) in row.iter_mut().zip(&pivot_row).skip(col) {
+                *x -= factor * p;
+            }
+        }
+    }
+
+    // back substitution
+    let mut x = vec![0.0; n];
+    for row in (0..n).rev() {
+        let sum: f64 = ((row + 1)..n).map(|k| m[row][k] * x[k]).sum();
+        x[row] = (m[row][n] - sum) / m[row][row];
+    }
+
+    Some(x)
+}
+
+/// determinant of the square matrix `a`, by the same elimination as [`solve_linear`]
+pub fn determinant(a: &[Vec<f64>]) -> f64 {
+    let n = a.len();
+    let mut m = a.to_vec();
+    let mut det = 1.0;
+    for col in 0..n {
+        let pivot = (col..n).max_by(|&i, &j| m[i][col].abs().total_cmp(&m[j][col].abs())).unwrap();
+        if m[pivot][col] == 0.0 {
+            return 0.0;
+        }
+        if pivot != col {
+            m.swap(col, pivot);
+            det = -det;
+        }
+        det *= m[col][col];
+
+        let pivot_row = m[col].clone();
+        for row in m.iter_mut().skip(col + 1) {
+            let factor = row[col] / pivot_row[col];
+            for (x, p) in row.iter_mut().zip(&pivot_row).skip(col) {
+                *x -= factor * p;
+            }
+        }
+    }
+    det
+}
+
+/// least squares straight line through the points, returns `(slope, intercept)`
+pub fn linear_fit(xs: &[f64], ys: &[f64]) -> (f64, f64) {
+    let n = xs.len() as f64;
+    let mean_x = xs.iter().sum::<f64>() / n;
+    let mean_y = ys.iter().sum::<f64>() / n;
+
+    let sxy: f64 = xs.iter().zip(ys).map(|(x, y)| (x - mean_x) * (y - mean_y)).sum();
+    let sxx: f64 = xs.iter().map(|x| (x - mean_x) * (x - mean_x)).sum();
+
+    let slope = sxy / sxx;
+    (slope, mean_y - (slope * mean_x))
+}
+
+/// pearson correlation coefficient of two equally long series, 0 when either is constant
+pub fn correlation(xs: &[f64], ys: &[f64]) -> f64 {
+    let n = xs.len() as f64;
+    let mean_x = xs.iter().sum::<f64>() / n;
+    let mean_y = ys.iter().sum::<f64>() / n;
+
+    let sxy: f64 = xs.iter().zip(ys).map(|(x, y)| (x - mean_x) * (y - mean_y)).sum();
+    let sxx: f64 = xs.iter().map(|x| (x - mean_x) * (x - mean_x)).sum();
+    let syy: f64 = ys.iter().map(|y| (y - mean_y) * (y - mean_y)).sum();
+
+    if sxx == 0.0 || syy == 0.0 { 0.0 } else { sxy / (sxx * syy).sqrt() }
+}
+
+/// euclidean distance between two points
+pub fn distance(a: &[f64], b: &[f64]) -> f64 {
+    a.iter().zip(b).map(|(x, y)| (x - y) * (x - y)).sum::<f64>().sqrt()
+}
+
+/// jacobian matrix `J_ij = ∂f_i/∂x_j` of `f` at `x` by central differences
+pub fn jacobian(f: impl Fn(&[f64]) -> Vec<f64>, x: &[f64]) -> Vec<Vec<f64>> {
+    let n = x.len();
+    let mut probe = x.to_vec();
+    let mut columns = Vec::with_capacity(n);
+
+    for j in 0..n {
+        // step scaled to the size of the coordinate so big and small states both work
+        let h = 1e-6 * x[j].abs().max(1.0);
+        probe[j] = x[j] + h;
+        let up = f(&probe);
+        probe[j] = x[j] - h;
+        let down = f(&probe);
+        probe[j] = x[j];
+        columns.push(up.iter().zip(&down).map(|(u, d)| (u - d) / (2.0 * h)).collect::<Vec<f64>>());
+    }
+
+    // columns -> rows
+    (0..columns.first().map_or(0, |c| c.len())).map(|i| columns.iter().map(|c| c[i]).collect()).collect()
+}
+
+/// matrix times vector
+pub fn mat_vec(a: &[Vec<f64>], v: &[f64]) -> Vec<f64> {
+    a.iter().map(|row| row.iter().zip(v).map(|(a, v)| a * v).sum()).collect()
+}
+
+/// modified gram–schmidt: makes `vectors` orthonormal in place and returns the length each one had
+/// after removing its components along the previous ones (the diagonal of R in a QR decomposition)
+pub fn gram_schmidt(vectors: &mut [Vec<f64>]) -> Vec<f64> {
+    let mut norms = Vec::with_capacity(vectors.len());
+    for i in 0..vectors.len() {
+        let (done, rest) = vectors.split_at_mut(i);
+        let v = &mut rest[0];
+        for u in done.iter() {
+            let proj: f64 = v.iter().zip(u).map(|(a, b)| a * b).sum();
+            for (x, y) in v.iter_mut().zip(u) {
+                *x -= proj * y;
+            }
+        }
+        let norm = v.iter().map(|x| x * x).sum::<f64>().sqrt();
+        for x in v.iter_mut() {
+            *x /= norm;
+        }
+        norms.push(norm);
+    }
+    norms
+}
+
+/// coefficients `c_0 .. c_n` (c_n = 1) of the characteristic polynomial det(λI - A) of a square
+/// matrix, by faddeev–leverrier
+/// ```latex
+/// M_0 = 0,  c_n = 1
+/// M_k = A M_{k-1} + c_{n-k+1} I
+/// c_{n-k} = -tr(A M_k) / k
+/// ```
+/// exact arithmetic apart from round-off, fine for the handful of dimensions our systems have
+pub fn characteristic_polynomial(a: &[Vec<f64>]) -> Vec<f64> {
+    let n = a.len();
+    let mut c = vec![0.0; n + 1];
+    c[n] = 1.0;
+    let mut m = vec![vec![0.0; n]; n];
+
+    for k in 1..=n {
+        // M_k = A M_{k-1} + c_{n-k+1} I
+        let mut next: Vec<Vec<f64>> = (0..n).map(|i| (0..n).map(|j| (0..n).map(|l| a[i][l] * m[l][j]).sum()).collect()).collect();
+        for (i, row) in next.iter_mut().enumerate() {
+            row[i] += c[n - k + 1];
+        }
+        m = next;
+        let trace: f64 = (0..n).map(|i| (0..n).map(|l| a[i][l] * m[l][i]).sum::<f64>()).sum();
+        c[n - k] = -trace / k as f64;
+    }
+    c
+}
+
+/// every (complex) root of the polynomial `Σ c_k z^k` with `c` highest coefficient last, by
+/// durand–kerner: all the roots are refined at once, each pushed away from the others
+/// ```latex
+/// z_i ← z_i - p(z_i) / (c_n Π_{j≠i} (z_i - z_j))
+/// ```
+pub fn polynomial_roots(c: &[f64]) -> Vec<Complex> {
+    let n = c.len().saturating_sub(1);
+    if n == 0 {
+        return vec![];
+    }
+    let lead = c[n];
+    let eval = |z: Complex| c.iter().rev().fold(Complex::default(), |acc, ck| (acc * z) + Complex::new(*ck, 0.0));
+
+    // start on a circle enclosing every root, at angles that aren't symmetric about the real axis
+    let radius = 1.0 + c[..n].iter().map(|ck| (ck / lead).abs()).fold(0.0, f64::max);
+    let seed = Complex::new(0.4, 0.9);
+    let mut roots: Vec<Complex> = (0..n).scan(Complex::new(radius, 0.0), |z, _| {
+        *z = *z * seed;
+        Some(*z)
+    }).collect();
+
+    for _ in 0..1000 {
+        let mut change: f64 = 0.0;
+        for i in 0..n {
+            let denominator = (0..n).filter(|j| *j != i).fold(Complex::new(lead, 0.0), |acc, j| acc * (roots[i] - roots[j]));
+            let step = eval(roots[i]) / denominator;
+            roots[i] = roots[i] - step;
+            change = change.max(step.norm() / roots[i].norm().max(1.0));
+        }
+        if change < 1e-15 {
+            break;
+        }
+    }
+
+    // real roots come out with a whisker of imaginary part
+    for z in roots.iter_mut() {
+        if z.im.abs() < 1e-9 * z.norm().max(1.0) {
+            z.im = 0.0;
+        }
+    }
+    roots
+}
+
+/// eigenvalues of a square matrix, largest magnitude first, re: [`characteristic_polynomial`]
+pub fn eigenvalues(a: &[Vec<f64>]) -> Vec<Complex> {
+    let mut values = polynomial_roots(&characteristic_polynomial(a));
+    values.sort_by(|a, b| b.norm().total_cmp(&a.norm()));
+    values
+}
@@ -0,0 +1,135 @@
+use crate::{dynamical_system::{DynamicalSystem, Parameters}, lagrangian::{lagrangian_rhs, Lagrangian}};
+
+/// chain of `n` point masses on massless rods, each hanging from the one before it.
+///
+/// angles are measured from the downward vertical, so the state is
+/// `[theta_1..theta_n, omega_1..omega_n]` (the same layout as `DoublePendulum` for n = 2)
+#[derive(Debug, Clone)]
+pub struct NPendulum {
+    /// from the pivot down. one mass per rod, so the two can't disagree on how many links there are
+    pub chain: Vec<Link>
+}
+
+/// a rod of `length` with a bob of `mass` on the end
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Link {
+    pub mass: f64,
+    pub length: f64
+}
+
+impl Default for Link {
+    fn default() -> Self {
+        Link { mass: 1.0, length: 1.0 }
+    }
+}
+
+impl NPendulum {
+    /// `n` links with unit masses and lengths
+    pub fn new(n: usize) -> Self {
+        Self { chain: vec![Link::default(); n] }
+    }
+
+    /// one link per (mass, length) pair
+    pub fn from_links(links: &[(f64, f64)]) -> Self {
+        Self { chain: links.iter().map(|&(mass, length)| Link { mass, length }).collect() }
+    }
+
+    pub fn links(&self) -> usize {
+        self.chain.len()
+    }
+
+    /// length of the whole chain stretched out
+    pub fn reach(&self) -> f64 {
+        self.chain.iter().map(|link| link.length).sum()
+    }
+
+    /// cartesian position of every bob (pivot at the origin, y pointing up) for the given angles
+    pub fn positions(&self, angles: &[f64]) -> Vec<[f64; 2]> {
+        let mut x = 0.0;
+        let mut y = 0.0;
+        angles.iter()
+        .zip(&self.chain)
+        .map(|(theta, link)| {
+            x += link.length * theta.sin();
+            y -= link.length * theta.cos();
+            [x, y]
+        })
+        .collect()
+    }
+
+    // mass hanging at or below link i, i.e. everything link i has to carry
+    fn mass_below(&self) -> Vec<f64> {
+        let mut below: Vec<f64> = self.chain.iter().map(|link| link.mass).collect();
+        for i in (0..below.len().saturating_sub(1)).rev() {
+            below[i] += below[i + 1];
+        }
+        below
+    }
+}
+
+impl Lagrangian for NPendulum {
+    fn coordinates(&self) -> usize {
+        self.links()
+    }
+
+    // M_jk = (sum of masses at or below max(j,k)) * l_j * l_k * cos(theta_j - theta_k)
+    fn mass_matrix(&self, q: &[f64]) -> Vec<Vec<f64>> {
+        let n = self.links();
+        let below = self.mass_below();
+        (0..n).map(|j| {
+            (0..n).map(|k| {
+                below[j.max(k)] * self.chain[j].length * self.chain[k].length * (q[j] - q[k]).cos()
+            }).collect()
+        }).collect()
+    }
+
+    // f_j = -sum_k (mass below max(j,k)) l_j l_k sin(theta_j - theta_k) omega_k^2 - g l_j sin(theta_j) (mass below j)
+    fn generalised_forces(&self, q: &[f64], q_dot: &[f64]) -> Vec<f64> {
+        let g = 9.81;
+        let n = self.links();
+        let below = self.mass_below();
+        (0..n).map(|j| {
+            let centripetal: f64 = (0..n).map(|k| {
+                below[j.max(k)] * self.chain[j].length * self.chain[k].length * (q[j] - q[k]).sin() * q_dot[k] * q_dot[k]
+            }).sum();
+
+            -centripetal - (g * self.chain[j].length * q[j].sin() * below[j])
+        }).collect()
+    }
+}
+
+impl DynamicalSystem for NPendulum {
+    fn dimension(&self) -> usize {
+        2 * self.links()
+    }
+
+    fn rhs(&self, _t: f64, state: &[f64]) -> Vec<f64> {
+        lagrangian_rhs(self, state)
+    }
+}
+
+// one mass and one length per link: m1..mn, l1..ln
+impl Parameters for NPendulum {
+    fn parameters(&self) -> Vec<(String, f64)> {
+        let masses = self.chain.iter().enumerate().map(|(i, link)| (format!("m{}", i + 1), link.mass));
+        let lengths = self.chain.iter().enumerate().map(|(i, link)| (format!("l{}", i + 1), link.length));
+        masses.chain(lengths).collect()
+    }
+
+    fn set_parameter(&mut self, name: &str, value: f64) -> bool {
+        let index = name.get(1..).and_then(|i| i.parse::<usize>().ok());
+        let Some(link) = index.and_then(|i| i.checked_sub(1)).and_then(|i| self.chain.get_mut(i)) else { return false };
+        match name.get(..1) {
+            Some("m") => link.mass = value,
+            Some("l") => link.length = value,
+            _ => return false
+        }
+        true
+    }
+}
+
+impl Default for NPendulum {
+    fn default() -> Self {
+        Self::new(3)
+    }
+}
@@ -0,0 +1,122 @@
+use rand::Rng;
+
+use crate::{dynamical_system::Parameters, rng::gaussian};
+
+/// how a single value gets perturbed. Every variant is symmetric about the original value
+/// and a width of zero (or less) leaves the value untouched
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Distribution {
+    /// value + U(-w, w)
+    Uniform(f64),
+    /// value + N(0, std²)
+    Gaussian(f64),
+    /// value * (1 + U(-p, p) / 100), handy when parameters live on very different scales.
+    /// p is capped at 100 so the value never flips sign
+    Relative(f64)
+}
+
+impl Distribution {
+    pub fn sample(&self, value: f64, rng: &mut impl Rng) -> f64 {
+        match *self {
+            Distribution::Uniform(w) if w > 0.0 => value + rng.random_range(-w..w),
+            Distribution::Gaussian(std) if std > 0.0 => value + (std * gaussian(rng)),
+            Distribution::Relative(p) if p > 0.0 => {
+                let p = p.min(100.0);
+                value * (1.0 + (rng.random_range(-p..p) / 100.0))
+            }
+            _ => value
+        }
+    }
+
+    /// the width/std/percentage, whichever this is
+    pub fn width(&self) -> f64 {
+        match *self {
+            Distribution::Uniform(w) | Distribution::Gaussian(w) | Distribution::Relative(w) => w
+        }
+    }
+}
+
+impl std::fmt::Display for Distribution {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Distribution::Uniform(w) => write!(f, "uniform ±{}", w),
+            Distribution::Gaussian(std) => write!(f, "gaussian σ={}", std),
+            Distribution::Relative(p) => write!(f, "relative ±{}%", p)
+        }
+    }
+}
+
+/// which parameters and initial state components get perturbed, and how.
+/// Anything not mentioned is left alone
+#[derive(Debug, Clone, Default)]
+pub struct NoiseSpec {
+    pub parameters: Vec<(String, Distribution)>,
+    pub state: Vec<Option<Distribution>>
+}
+
+impl NoiseSpec {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// perturb the named parameter
+    pub fn parameter(mut self, name: &str, dist: Distribution) -> Self {
+        self.parameters.push((name.to_string(), dist));
+        self
+    }
+
+    /// perturb every parameter `sys` has the same way
+    pub fn all_parameters(mut self, sys: &impl Parameters, dist: Distribution) -> Self {
+        for (name, _) in sys.parameters() {
+            self.parameters.push((name, dist));
+        }
+        self
+    }
+
+    /// perturb initial state component `i`
+    pub fn state_component(mut self, i: usize, dist: Distribution) -> Self {
+        if self.state.len() <= i {
+            self.state.resize(i + 1, None);
+        }
+        self.state[i] = Some(dist);
+        self
+    }
+
+    /// perturb the first `n` initial state components the same way
+    pub fn state_components(mut self, n: usize, dist: Distribution) -> Self {
+        for i in 0..n {
+            self = self.state_component(i, dist);
+        }
+        self
+    }
+
+    /// a perturbed copy of `state`
+    pub fn perturb_state(&self, state: &[f64], rng: &mut impl Rng) -> Vec<f64> {
+        state.iter()
+        .enumerate()
+        .map(|(i, x)| match self.state.get(i) {
+            Some(Some(dist)) => dist.sample(*x, rng),
+            _ => *x
+        })
+        .collect()
+    }
+}
+
+/// systems that can make a randomly perturbed copy of themselves
+pub trait Noise: Sized {
+    /// copy of the system with the parameters in `noise` perturbed.
+    /// Errors on a parameter name the system doesn't have
+    fn new_noisy(&self, noise: &NoiseSpec, rng: &mut impl Rng) -> Result<Self, String>;
+}
+
+// anything with named parameters can be made noisy
+impl<T: Parameters + Clone> Noise for T {
+    fn new_noisy(&self, noise: &NoiseSpec, rng: &mut impl Rng) -> Result<Self, String> {
+        let mut noisy = self.clone();
+        for (name, dist) in &noise.parameters {
+            let value = self.parameter(name).ok_or(format!("unknown parameter '{}'", name))?;
+            noisy.set_parameter(name, dist.sample(value, rng));
+        }
+        Ok(noisy)
+    }
+}
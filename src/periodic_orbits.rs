@@ -0,0 +1,306 @@
+use crate::{
+    dynamical_system::{DynamicalSystem, Map}, integrators::rk4_step, math::{distance, eigenvalues, jacobian, solve_linear, Complex}
+};
+
+/// a closed orbit and its stability
+#[derive(Debug, Clone)]
+pub struct PeriodicOrbit {
+    /// the points of the cycle for a map, the closed curve (sampled every dt) for a flow
+    pub points: Vec<Vec<f64>>,
+    /// iterations of the map, or of the poincaré return map for a flow
+    pub n: usize,
+    /// the period in iterations for a map, in time for a flow
+    pub period: f64,
+    /// eigenvalues of the linearised n-th iterate around the orbit, largest magnitude first.
+    /// For a flow these are the nontrivial floquet multipliers (the one along the flow is always 1)
+    pub multipliers: Vec<Complex>
+}
+
+impl PeriodicOrbit {
+    /// every perturbation shrinks
+    pub fn is_stable(&self) -> bool {
+        self.multipliers.iter().all(|m| m.norm() < 1.0)
+    }
+
+    /// the multipliers as `a, b±ci, ...`
+    pub fn format_multipliers(&self) -> String {
+        let multipliers: Vec<String> = self.multipliers.iter().map(|m| {
+            if m.im == 0.0 { format!("{:.4}", m.re) } else { format!("{:.4}{:+.4}i", m.re, m.im) }
+        }).collect();
+        multipliers.join(", ")
+    }
+}
+
+impl std::fmt::Display for PeriodicOrbit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "n = {}, period = {:.5}, multipliers [{}], {}",
+            self.n, self.period, self.format_multipliers(), if self.is_stable() { "stable" } else { "unstable" }
+        )
+    }
+}
+
+/// newton's method for `g(x) = 0` with a finite difference jacobian. Steps that make |g| worse
+/// are halved (a few times), far from the root the full step tends to shoot off the attractor.
+/// `None` if it doesn't converge or wanders into NaN
+pub(crate) fn newton(g: impl Fn(&[f64]) -> Vec<f64>, x0: &[f64], tolerance: f64) -> Option<Vec<f64>> {
+    let norm = |v: &[f64]| v.iter().map(|x| x * x).sum::<f64>().sqrt();
+    let mut x = x0.to_vec();
+    let mut residual = g(&x);
+
+    for _ in 0..50 {
+        if !norm(&residual).is_finite() {
+            return None;
+        }
+        if norm(&residual) < tolerance {
+            return Some(x);
+        }
+        let minus: Vec<f64> = residual.iter().map(|r| -r).collect();
+        let step = solve_linear(&jacobian(&g, &x), &minus)?;
+
+        let mut scale = 1.0;
+        loop {
+            let trial: Vec<f64> = x.iter().zip(&step).map(|(x, s)| x + (scale * s)).collect();
+            let trial_residual = g(&trial);
+            if norm(&trial_residual) < norm(&residual) || scale < 1e-3 {
+                x = trial;
+                residual = trial_residual;
+                break;
+            }
+            scale /= 2.0;
+        }
+    }
+    None
+}
+
+fn iterate(map: &impl Map, x: &[f64], n: usize) -> Vec<f64> {
+    (0..n).fold(x.to_vec(), |x, _| map.step(&x))
+}
+
+/// period-`n` orbit of a map through (or near) `x0`: newton on
+/// ```latex
+/// F^n(x) - x = 0
+/// ```
+/// which converges to unstable cycles just as happily as stable ones, the ones simple iteration
+/// can never show. If the point turns out to have a shorter period (a fixed point is also a
+/// period 4 point) the orbit is reported with that one
+pub fn map_orbit(map: &impl Map, x0: &[f64], n: usize) -> Option<PeriodicOrbit> {
+    let x = newton(|x| iterate(map, x, n).iter().zip(x).map(|(f, x)| f - x).collect(), x0, 1e-10)?;
+
+    let scale = x.iter().fold(1.0_f64, |m, x| m.max(x.abs()));
+    let n = (1..=n).find(|d| n.is_multiple_of(*d) && distance(&iterate(map, &x, *d), &x) < 1e-8 * scale)?;
+
+    let points: Vec<Vec<f64>> = (0..n).scan(x.clone(), |x, _| {
+        let point = x.clone();
+        *x = map.step(x);
+        Some(point)
+    }).collect();
+
+    Some(PeriodicOrbit {
+        multipliers: eigenvalues(&jacobian(|x| iterate(map, x, n), &x)),
+        points,
+        n,
+        period: n as f64
+    })
+}
+
+/// where along a recorded series the trajectory comes back close to itself: every `(i, p)` with
+/// ```latex
+/// |x_{i+p} - x_i| < ε,    p ∈ periods
+/// ```
+/// taking the smallest such p for each i. A chaotic trajectory shadows the unstable periodic
+/// orbits embedded in its attractor for a while every time it passes near one, so close
+/// returns are where (and with what period) to start looking for them
+pub fn close_returns(points: &[Vec<f64>], periods: std::ops::RangeInclusive<usize>, epsilon: f64) -> Vec<(usize, usize)> {
+    (0..points.len()).filter_map(|i| {
+        periods.clone()
+        .take_while(|p| i + p < points.len())
+        .find(|p| distance(&points[i + p], &points[i]) < epsilon)
+        .map(|p| (i, p))
+    }).collect()
+}
+
+/// true when the orbit with `n` through `point` is one of `known`: (n, the points it passes
+/// through) of the orbits found so far
+fn is_known(known: &[(usize, Vec<Vec<f64>>)], n: usize, point: &[f64], tolerance: f64) -> bool {
+    let scale = point.iter().fold(1.0_f64, |m, x| m.max(x.abs()));
+    known.iter().any(|(m, points)| *m == n && points.iter().any(|p| distance(p, point) < tolerance * scale))
+}
+
+/// periodic orbits of a map seeded from the close returns of a recorded trajectory (periods up
+/// to `max_period`), each found once, sorted by period, re: [`map_orbit`], [`close_returns`]
+pub fn find_map_orbits(map: &impl Map, points: &[Vec<f64>], max_period: usize, epsilon: f64) -> Vec<PeriodicOrbit> {
+    let mut orbits: Vec<PeriodicOrbit> = vec![];
+    let mut known = vec![];
+    for (i, p) in close_returns(points, 1..=max_period, epsilon) {
+        if let Some(orbit) = map_orbit(map, &points[i], p) {
+            if !is_known(&known, orbit.n, &orbit.points[0], 1e-6) {
+                known.push((orbit.n, orbit.points.clone()));
+                orbits.push(orbit);
+            }
+        }
+    }
+    orbits.sort_by_key(|o| o.n);
+    orbits
+}
+
+/// poincaré section: the hyperplane where state component `component` equals `value`, crossed
+/// in the increasing direction
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Section {
+    pub component: usize,
+    pub value: f64
+}
+
+/// the flow with the section's coordinate as the independent variable and time as an extra
+/// state component
+/// ```latex
+/// dx/dx_c = f(x) / f_c(x),    dt/dx_c = 1 / f_c(x)
+/// ```
+/// one rk4 step of this from just before a crossing lands exactly on the section (hénon's trick),
+/// so the return map is as smooth as the integration and newton can differentiate it
+struct OnSection<'a, S> {
+    sys: &'a S,
+    component: usize
+}
+
+impl<S: DynamicalSystem> DynamicalSystem for OnSection<'_, S> {
+    fn dimension(&self) -> usize {
+        self.sys.dimension() + 1
+    }
+
+    fn rhs(&self, _x_c: f64, state: &[f64]) -> Vec<f64> {
+        let n = self.sys.dimension();
+        let f = self.sys.rhs(state[n], &state[..n]);
+        let rate = f[self.component];
+        let mut derivative: Vec<f64> = f.iter().map(|fi| fi / rate).collect();
+        derivative.push(1.0 / rate);
+        derivative
+    }
+}
+
+/// integrates from `x` (at time `t`) to the next upward crossing of `section`, returning the
+/// state there and the time. `None` if there is none within `max_time`
+pub fn next_crossing(sys: &impl DynamicalSystem, x: &[f64], t: f64, section: Section, dt: f64, max_time: f64) -> Option<(Vec<f64>, f64)> {
+    let c = section.component;
+    let mut x = x.to_vec();
+    let mut t = t;
+    let end = t + max_time;
+
+    while t < end {
+        let before = x.clone();
+        rk4_step(sys, &mut x, t, dt);
+        t += dt;
+        if before[c] < section.value && x[c] >= section.value {
+            let (x_c, step) = (before[c], section.value - before[c]);
+            let mut y = before;
+            y.push(t - dt);
+            rk4_step(&OnSection { sys, component: c }, &mut y, x_c, step);
+            let t = y.pop()?;
+            return Some((y, t));
+        }
+    }
+    None
+}
+
+/// the state on `section` with the other coordinates `u`
+pub(crate) fn on_section(u: &[f64], section: Section) -> Vec<f64> {
+    let mut x = u.to_vec();
+    x.insert(section.component, section.value);
+    x
+}
+
+/// coordinates on the section, dropping the fixed one
+pub(crate) fn off_section(x: &[f64], section: Section) -> Vec<f64> {
+    let mut u = x.to_vec();
+    u.remove(section.component);
+    u
+}
+
+/// the `n`-th return to the section from section coordinates `u`, and the time it takes.
+/// Only for autonomous flows, time always restarts at 0
+pub(crate) fn return_map(sys: &impl DynamicalSystem, u: &[f64], n: usize, section: Section, dt: f64) -> Option<(Vec<f64>, f64)> {
+    let mut x = on_section(u, section);
+    let mut t = 0.0;
+    for _ in 0..n {
+        (x, t) = next_crossing(sys, &x, t, section, dt, 100.0)?;
+    }
+    Some((off_section(&x, section), t))
+}
+
+/// closed orbit of an autonomous flow crossing `section` `n` times per period, by newton–shooting
+/// on the poincaré return map from `x0` (any state, it is carried to the section first)
+/// ```latex
+/// P^n(u) - u = 0
+/// ```
+/// a periodic orbit is a fixed point of the return map, so the problem drops to a map one
+/// dimension smaller than the flow and [`map_orbit`]'s newton applies. The multipliers are the
+/// eigenvalues of D(P^n), the floquet multipliers other than the trivial 1
+pub fn flow_orbit(sys: &impl DynamicalSystem, x0: &[f64], n: usize, section: Section, dt: f64) -> Option<PeriodicOrbit> {
+    let (start, _) = next_crossing(sys, x0, 0.0, section, dt, 100.0)?;
+    let shoot = |u: &[f64]| match return_map(sys, u, n, section, dt) {
+        Some((v, _)) => v.iter().zip(u).map(|(v, u)| v - u).collect(),
+        None => vec![f64::NAN; u.len()]
+    };
+    let u = newton(shoot, &off_section(&start, section), 1e-9)?;
+    // an equilibrium sitting on the section returns to itself too (lorenz's C± lie on z = ρ - 1)
+    let x = on_section(&u, section);
+    let speed = sys.rhs(0.0, &x).iter().map(|f| f * f).sum::<f64>().sqrt();
+    if speed < 1e-6 * x.iter().fold(1.0_f64, |m, x| m.max(x.abs())) {
+        return None;
+    }
+
+    // shorter orbits traversed several times solve the same equation
+    let scale = u.iter().fold(1.0_f64, |m, x| m.max(x.abs()));
+    let n = (1..=n).find(|d| n.is_multiple_of(*d) && return_map(sys, &u, *d, section, dt).is_some_and(|(v, _)| distance(&v, &u) < 1e-6 * scale))?;
+    let (_, period) = return_map(sys, &u, n, section, dt)?;
+
+    // the closed curve, for drawing
+    let steps = (period / dt).ceil() as usize;
+    let mut x = on_section(&u, section);
+    let mut points = vec![x.clone()];
+    for k in 0..steps {
+        rk4_step(sys, &mut x, k as f64 * period / steps as f64, period / steps as f64);
+        points.push(x.clone());
+    }
+
+    Some(PeriodicOrbit {
+        multipliers: eigenvalues(&jacobian(|u| return_map(sys, u, n, section, dt).map_or(vec![f64::NAN; u.len()], |(v, _)| v), &u)),
+        points,
+        n,
+        period
+    })
+}
+
+/// closed orbits of a flow seeded from the close returns of a recorded trajectory (samples
+/// `dt_sample` apart, returns after `periods` samples). Each return is shot from its start,
+/// with n the number of section crossings along the stretch, re: [`flow_orbit`]
+pub fn find_flow_orbits(sys: &impl DynamicalSystem, points: &[Vec<f64>], periods: std::ops::RangeInclusive<usize>, epsilon: f64, section: Section, dt: f64) -> Vec<PeriodicOrbit> {
+    let c = section.component;
+    let mut orbits: Vec<PeriodicOrbit> = vec![];
+    // the drawn curves are too coarse to compare, orbits are told apart by their crossings
+    let mut known = vec![];
+    let mut tried = vec![];
+
+    for (i, p) in close_returns(points, periods, epsilon) {
+        let n = (i..(i + p)).filter(|k| points[*k][c] < section.value && points[k + 1][c] >= section.value).count();
+        // neighbouring samples along one close return all seed the same orbit
+        if n == 0 || tried.iter().any(|(j, m)| *m == n && i - j <= p) {
+            continue;
+        }
+        tried.push((i, n));
+        let Some(orbit) = flow_orbit(sys, &points[i], n, section, dt) else { continue };
+        if !is_known(&known, orbit.n, &orbit.points[0], 1e-5) {
+            let crossings: Vec<Vec<f64>> = (0..orbit.n).scan((orbit.points[0].clone(), 0.0), |(x, t), _| {
+                let point = x.clone();
+                (*x, *t) = next_crossing(sys, x, *t, section, dt, 100.0)?;
+                Some(point)
+            }).collect();
+            known.push((orbit.n, crossings));
+            orbits.push(orbit);
+        }
+    }
+    orbits.sort_by(|a, b| a.n.cmp(&b.n).then(a.period.total_cmp(&b.period)));
+    orbits
+}
@@ -0,0 +1,152 @@
+use crate::{heatmap::Heatmap, math::distance};
+
+/// how close two states have to be to count as a recurrence
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Threshold {
+    /// a fixed distance ε
+    Distance(f64),
+    /// whatever ε makes this fraction of all pairs recurrent, so plots of systems with very
+    /// different sizes can be compared directly
+    RecurrenceRate(f64)
+}
+
+/// recurrence plot of a trajectory: `R_ij = 1` when states i and j are within ε of each other
+/// ```latex
+/// R_ij = Θ(ε - |x_i - x_j|)
+/// ```
+/// periodic motion draws long diagonals at the period's spacing, chaos short broken diagonals,
+/// noise isolated dots, and a system stuck in a state (laminar phases) vertical lines
+#[derive(Debug, Clone)]
+pub struct RecurrencePlot {
+    pub n: usize,
+    pub epsilon: f64,
+    // row major n × n, symmetric
+    matrix: Vec<bool>
+}
+
+/// recurrence quantification analysis: numbers from the line structures of a recurrence plot
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rqa {
+    /// RR, fraction of pairs that are recurrent
+    pub recurrence_rate: f64,
+    /// DET, fraction of recurrent points on diagonal lines at least `l_min` long. Near 1 for
+    /// deterministic (periodic or chaotic) motion, small for noise
+    pub determinism: f64,
+    /// LAM, fraction of recurrent points on vertical lines at least `v_min` long
+    pub laminarity: f64,
+    /// L, mean length of the diagonal lines counted in DET. Roughly how long nearby stretches
+    /// of trajectory stay nearby, infinite-ish for periodic motion, ~1/λ for chaos
+    pub average_diagonal: f64,
+    /// ENTR, shannon entropy of the diagonal line length distribution
+    pub entropy: f64
+}
+
+impl std::fmt::Display for Rqa {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "RR = {:.4}, DET = {:.4}, LAM = {:.4}, L = {:.3}, ENTR = {:.3}",
+            self.recurrence_rate, self.determinism, self.laminarity, self.average_diagonal, self.entropy
+        )
+    }
+}
+
+impl RecurrencePlot {
+    /// recurrence plot of `points` (in time order) at the given threshold
+    pub fn new(points: &[Vec<f64>], threshold: Threshold) -> Self {
+        let n = points.len();
+        let distances: Vec<f64> = (0..n).flat_map(|i| (0..n).map(move |j| (i, j))).map(|(i, j)| distance(&points[i], &points[j])).collect();
+
+        let epsilon = match threshold {
+            Threshold::Distance(epsilon) => epsilon,
+            Threshold::RecurrenceRate(rate) => {
+                // the rate-quantile of the distances between distinct states
+                let mut off_diagonal: Vec<f64> = (0..n).flat_map(|i| ((i + 1)..n).map(move |j| (i, j))).map(|(i, j)| distances[(i * n) + j]).collect();
+                if off_diagonal.is_empty() {
+                    0.0
+                } else {
+                    let k = ((rate.clamp(0.0, 1.0) * off_diagonal.len() as f64) as usize).min(off_diagonal.len() - 1);
+                    *off_diagonal.select_nth_unstable_by(k, |a, b| a.total_cmp(b)).1
+                }
+            }
+        };
+
+        RecurrencePlot { n, epsilon, matrix: distances.iter().map(|d| *d <= epsilon).collect() }
+    }
+
+    pub fn is_recurrent(&self, i: usize, j: usize) -> bool {
+        self.matrix[(i * self.n) + j]
+    }
+
+    /// black for recurrent, white otherwise, with i along x and j up the y axis (row 0 is j = n - 1)
+    pub fn to_heatmap(&self) -> Heatmap {
+        let pixels = (0..self.n).rev()
+        .flat_map(|j| (0..self.n).map(move |i| (i, j)))
+        .map(|(i, j)| if self.is_recurrent(i, j) { [0, 0, 0] } else { [255, 255, 255] })
+        .collect();
+        Heatmap { width: self.n, height: self.n, pixels }
+    }
+
+    /// RQA measures, counting diagonal lines at least `l_min` long and vertical lines at least
+    /// `v_min` long (2 for both is usual). Pairs closer than `theiler` in time are left out,
+    /// 1 drops just the trivial main diagonal
+    pub fn rqa(&self, l_min: usize, v_min: usize, theiler: usize) -> Rqa {
+        let n = self.n;
+        let counts = |i: usize, j: usize| i.abs_diff(j) >= theiler && self.is_recurrent(i, j);
+
+        let recurrent = (0..n).flat_map(|i| (0..n).map(move |j| (i, j))).filter(|(i, j)| counts(*i, *j)).count();
+        let pairs = (0..n).flat_map(|i| (0..n).map(move |j| (i, j))).filter(|(i, j)| i.abs_diff(*j) >= theiler).count();
+
+        // diagonal line lengths over the upper triangle (the plot is symmetric, so the
+        // lower one just doubles every count and cancels out of every ratio)
+        let mut diagonals = vec![];
+        for k in theiler.max(1)..n {
+            push_runs((0..(n - k)).map(|i| counts(i, i + k)), &mut diagonals);
+        }
+        let mut verticals = vec![];
+        for j in 0..n {
+            push_runs((0..n).map(|i| counts(i, j)), &mut verticals);
+        }
+
+        let diagonal_points: usize = diagonals.iter().sum();
+        let long_diagonals: Vec<usize> = diagonals.iter().copied().filter(|l| *l >= l_min).collect();
+        let long_points: usize = long_diagonals.iter().sum();
+        let vertical_points: usize = verticals.iter().sum();
+        let long_vertical_points: usize = verticals.iter().filter(|l| **l >= v_min).sum();
+
+        // shannon entropy of the histogram of long diagonal lengths
+        let mut histogram = std::collections::BTreeMap::new();
+        for l in &long_diagonals {
+            *histogram.entry(*l).or_insert(0_usize) += 1;
+        }
+        let entropy = histogram.values().map(|count| {
+            let p = *count as f64 / long_diagonals.len() as f64;
+            -p * p.ln()
+        }).sum();
+
+        let ratio = |a: usize, b: usize| if b == 0 { 0.0 } else { a as f64 / b as f64 };
+        Rqa {
+            recurrence_rate: ratio(recurrent, pairs),
+            determinism: ratio(long_points, diagonal_points),
+            laminarity: ratio(long_vertical_points, vertical_points),
+            average_diagonal: ratio(long_points, long_diagonals.len()),
+            entropy
+        }
+    }
+}
+
+/// lengths of the runs of `true` in `line`
+fn push_runs(line: impl Iterator<Item = bool>, runs: &mut Vec<usize>) {
+    let mut run = 0;
+    for recurrent in line {
+        if recurrent {
+            run += 1;
+        } else if run > 0 {
+            runs.push(run);
+            run = 0;
+        }
+    }
+    if run > 0 {
+        runs.push(run);
+    }
+}
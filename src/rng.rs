@@ -2,4 +2,73 @@ use rand::prelude::*;
 
 pub fn rng_generator(seed: u64) -> StdRng {
     StdRng::seed_from_u64(seed)
-}
\ No newline at end of file
+}
+
+/// generator for the `index`-th independent sub-task of a seeded run (an ensemble member, a sweep point, ...)
+///
+/// each index gets its own stream so results don't depend on the order (or thread) the tasks run in
+pub fn stream_generator(seed: u64, index: u64) -> StdRng {
+    // mixed rather than xored, so seed 1 / member 0 doesn't replay seed 0 / member 1. The + 1
+    // keeps member 0 off the main stream, `rng_generator(seed)`
+    StdRng::seed_from_u64(splitmix64(seed ^ splitmix64(index.wrapping_add(1))))
+}
+
+// the splitmix64 finaliser, every input bit flips about half the output bits
+// re: https://prng.di.unimi.it/splitmix64.c
+fn splitmix64(x: u64) -> u64 {
+    let mut z = x.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+/// the one source of randomness for a run.
+///
+/// noise, perturbed trajectories and ensembles all draw from here, so any run can be
+/// replayed bit-for-bit from its seed. Implements `RngCore`, so it can be passed anywhere a `Rng` is expected
+pub struct SeededRng {
+    seed: u64,
+    rng: StdRng
+}
+
+impl SeededRng {
+    pub fn new(seed: u64) -> Self {
+        Self { seed, rng: rng_generator(seed) }
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// rewind to the start of the stream, the next draws repeat the ones made since the last reset
+    pub fn reset(&mut self) {
+        self.rng = rng_generator(self.seed);
+    }
+
+    /// independent generator for a sub-task, re: `stream_generator`. Doesn't advance the main stream
+    pub fn stream(&self, index: u64) -> StdRng {
+        stream_generator(self.seed, index)
+    }
+}
+
+impl RngCore for SeededRng {
+    fn next_u32(&mut self) -> u32 {
+        self.rng.next_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.rng.next_u64()
+    }
+
+    fn fill_bytes(&mut self, dst: &mut [u8]) {
+        self.rng.fill_bytes(dst)
+    }
+}
+
+/// standard normal sample (mean 0, variance 1) via the Box–Muller transform
+pub fn gaussian(rng: &mut impl Rng) -> f64 {
+    // u1 in (0,1] so the log is always finite
+    let u1: f64 = 1.0 - rng.random::<f64>();
+    let u2: f64 = rng.random();
+    (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos()
+}
@@ -0,0 +1,109 @@
+use std::{io::Write, sync::atomic::{AtomicBool, AtomicUsize, Ordering}, time::Duration};
+
+use rayon::prelude::*;
+
+/// shared between a running sweep and whoever is watching it: how far along it is, and a flag
+/// to stop it early. Several runs can report to the same `Progress`, their totals add up
+/// unless the total was fixed up front with [`Progress::with_total`]
+#[derive(Debug, Default)]
+pub struct Progress {
+    done: AtomicUsize,
+    total: AtomicUsize,
+    fixed_total: bool,
+    cancelled: AtomicBool
+}
+
+impl Progress {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// for work handed out over several runs (e.g. in chunks, to show results as they come in)
+    /// when the overall size is known beforehand. The runs don't add to `total`
+    pub fn with_total(total: usize) -> Self {
+        Progress { total: AtomicUsize::new(total), fixed_total: true, ..Self::default() }
+    }
+
+    /// number of items finished so far
+    pub fn done(&self) -> usize {
+        self.done.load(Ordering::Relaxed)
+    }
+
+    /// one more item finished, for runs that work through their items one at a time
+    pub fn tick(&self) {
+        self.done.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// number of items handed out so far
+    pub fn total(&self) -> usize {
+        self.total.load(Ordering::Relaxed)
+    }
+
+    /// done / total, 0 before anything was handed out
+    pub fn fraction(&self) -> f32 {
+        let total = self.total();
+        if total == 0 {
+            return 0.0;
+        }
+        self.done() as f32 / total as f32
+    }
+
+    /// asks the run to stop, items already started still finish
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
+
+/// `f` over every item, spread across all cores with rayon. Results keep the order of `items`.
+///
+/// every finished item ticks `progress`; once it's cancelled the remaining items are skipped and
+/// the whole run returns `None`
+pub fn run<T: Sync, R: Send>(items: &[T], progress: &Progress, f: impl Fn(&T) -> R + Sync) -> Option<Vec<R>> {
+    if !progress.fixed_total {
+        progress.total.fetch_add(items.len(), Ordering::Relaxed);
+    }
+
+    items.par_iter().map(|item| {
+        if progress.is_cancelled() {
+            return None;
+        }
+        let result = f(item);
+        progress.done.fetch_add(1, Ordering::Relaxed);
+        Some(result)
+    }).collect()
+}
+
+/// `f` at every point of the grid `xs × ys` (a parameter plane, a plane of initial states, ...),
+/// re: [`run`]. `rows[j][i]` is `f(xs[i], ys[j])`
+pub fn run_grid<R: Send>(xs: &[f64], ys: &[f64], progress: &Progress, f: impl Fn(f64, f64) -> R + Sync) -> Option<Vec<Vec<R>>> {
+    let points: Vec<(f64, f64)> = ys.iter().flat_map(|y| xs.iter().map(move |x| (*x, *y))).collect();
+    let mut values = run(&points, progress, |(x, y)| f(*x, *y))?.into_iter();
+
+    Some(ys.iter().map(|_| values.by_ref().take(xs.len()).collect()).collect())
+}
+
+/// runs `work` while a `done/total` counter ticks along on stderr, for the cli
+pub fn with_progress_bar<R: Send>(progress: &Progress, work: impl FnOnce() -> R + Send) -> R {
+    let finished = AtomicBool::new(false);
+    std::thread::scope(|scope| {
+        scope.spawn(|| {
+            while !finished.load(Ordering::Relaxed) {
+                if progress.total() > 0 {
+                    eprint!("\r{}/{}", progress.done(), progress.total());
+                    let _ = std::io::stderr().flush();
+                }
+                std::thread::sleep(Duration::from_millis(200));
+            }
+            // clear the counter so it doesn't mix with whatever is printed next
+            eprint!("\r{}\r", " ".repeat(24));
+        });
+
+        let result = work();
+        finished.store(true, Ordering::Relaxed);
+        result
+    })
+}
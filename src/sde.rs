@@ -0,0 +1,151 @@
+use crate::{dynamical_system::{Diffusion, DynamicalSystem}, integrators::{euler_maruyama_step, milstein_step, rk4_step}, rng::stream_generator, runner::{run, Progress}};
+
+/// adds the same constant noise `sigma * dW` to every component of a deterministic system
+pub struct AdditiveNoise<S> {
+    pub system: S,
+    pub sigma: f64
+}
+
+impl<S: DynamicalSystem> DynamicalSystem for AdditiveNoise<S> {
+    fn dimension(&self) -> usize {
+        self.system.dimension()
+    }
+
+    fn rhs(&self, t: f64, state: &[f64]) -> Vec<f64> {
+        self.system.rhs(t, state)
+    }
+}
+
+impl<S: DynamicalSystem> Diffusion for AdditiveNoise<S> {
+    fn diffusion(&self, _t: f64, state: &[f64]) -> Vec<f64> {
+        vec![self.sigma; state.len()]
+    }
+
+    fn diffusion_derivative(&self, _t: f64, state: &[f64]) -> Vec<f64> {
+        vec![0.0; state.len()]
+    }
+}
+
+/// noise proportional to the state, `sigma * x_i * dW_i`. Zero stays zero, big gets noisier
+pub struct MultiplicativeNoise<S> {
+    pub system: S,
+    pub sigma: f64
+}
+
+impl<S: DynamicalSystem> DynamicalSystem for MultiplicativeNoise<S> {
+    fn dimension(&self) -> usize {
+        self.system.dimension()
+    }
+
+    fn rhs(&self, t: f64, state: &[f64]) -> Vec<f64> {
+        self.system.rhs(t, state)
+    }
+}
+
+impl<S: DynamicalSystem> Diffusion for MultiplicativeNoise<S> {
+    fn diffusion(&self, _t: f64, state: &[f64]) -> Vec<f64> {
+        state.iter().map(|x| self.sigma * x).collect()
+    }
+
+    fn diffusion_derivative(&self, _t: f64, state: &[f64]) -> Vec<f64> {
+        vec![self.sigma; state.len()]
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Scheme {
+    EulerMaruyama,
+    Milstein
+}
+
+/// mean and spread of an ensemble of noisy trajectories, alongside the noise free trajectory
+/// started from the same point. `mean[k]`, `std_dev[k]` and `deterministic[k]` are all at `times[k]`
+pub struct EnsembleStats {
+    pub times: Vec<f64>,
+    pub mean: Vec<Vec<f64>>,
+    pub std_dev: Vec<Vec<f64>>,
+    pub deterministic: Vec<Vec<f64>>
+}
+
+// members summed together on one thread before the partial sums are combined, re: `ensemble_stats`
+const ENSEMBLE_CHUNK: usize = 64;
+
+/// runs `members` realisations of the sde from `x0` for `steps` steps of size `dt`, spread
+/// across all cores. `None` when cancelled through `progress`
+///
+/// member `i` draws its increments from `stream_generator(seed, i)`. Only running sums (and sums of
+/// squares) are kept: each chunk of `ENSEMBLE_CHUNK` members adds up its own, and the chunks are
+/// combined in order afterwards, so memory doesn't grow with the ensemble and the same seed always
+/// gives the same ensemble no matter how the threads were scheduled
+///
+/// the deterministic reference is integrated with rk4 on the drift alone
+#[allow(clippy::too_many_arguments)]
+pub fn ensemble_stats<S: Diffusion + Sync>(sys: &S, x0: &[f64], members: usize, steps: usize, dt: f64, scheme: Scheme, seed: u64, progress: &Progress) -> Option<EnsembleStats> {
+    let dim = x0.len();
+    let chunks: Vec<(u64, u64)> = (0..members as u64)
+    .step_by(ENSEMBLE_CHUNK)
+    .map(|start| (start, (start + ENSEMBLE_CHUNK as u64).min(members as u64)))
+    .collect();
+
+    let partial_sums = run(&chunks, progress, |&(first, last)| {
+        let mut sums = vec![vec![0.0; dim]; steps + 1];
+        let mut sums_sq = vec![vec![0.0; dim]; steps + 1];
+        for m in first..last {
+            let mut rng = stream_generator(seed, m);
+            let mut state = x0.to_vec();
+            let mut t = 0.0;
+            for k in 0..=steps {
+                if k > 0 {
+                    match scheme {
+                        Scheme::EulerMaruyama => euler_maruyama_step(sys, &mut state, t, dt, &mut rng),
+                        Scheme::Milstein => milstein_step(sys, &mut state, t, dt, &mut rng)
+                    }
+                    t += dt;
+                }
+                for i in 0..dim {
+                    sums[k][i] += state[i];
+                    sums_sq[k][i] += state[i] * state[i];
+                }
+            }
+        }
+        (sums, sums_sq)
+    })?;
+
+    let mut sums = vec![vec![0.0; dim]; steps + 1];
+    let mut sums_sq = vec![vec![0.0; dim]; steps + 1];
+    for (chunk_sums, chunk_sums_sq) in &partial_sums {
+        for k in 0..=steps {
+            for i in 0..dim {
+                sums[k][i] += chunk_sums[k][i];
+                sums_sq[k][i] += chunk_sums_sq[k][i];
+            }
+        }
+    }
+
+    let n = members as f64;
+    let mean: Vec<Vec<f64>> = sums.iter().map(|s| s.iter().map(|x| x / n).collect()).collect();
+    let std_dev = sums_sq.iter()
+    .zip(&mean)
+    .map(|(sq, mu)| {
+        // population variance, clamped since round-off can push it slightly negative
+        sq.iter().zip(mu).map(|(s, m)| ((s / n) - (m * m)).max(0.0).sqrt()).collect()
+    })
+    .collect();
+
+    let mut deterministic = Vec::with_capacity(steps + 1);
+    let mut state = x0.to_vec();
+    let mut t = 0.0;
+    deterministic.push(state.clone());
+    for _ in 0..steps {
+        rk4_step(sys, &mut state, t, dt);
+        t += dt;
+        deterministic.push(state.clone());
+    }
+
+    Some(EnsembleStats {
+        times: (0..=steps).map(|k| k as f64 * dt).collect(),
+        mean,
+        std_dev,
+        deterministic
+    })
+}
@@ -0,0 +1,42 @@
+use crate::dynamical_system::{Map, Parameters};
+
+/// the sine map of the unit interval, unimodal like the logistic map but not quadratic
+/// ```latex
+/// x_{n+1} = r sin(π x_n)
+/// ```
+/// it goes through the same period-doubling cascade (r_∞ ≈ 0.8655), at the same feigenbaum
+/// rates, re: [`feigenbaum`](crate::feigenbaum::feigenbaum)
+#[derive(Debug, Clone, Copy)]
+pub struct SineMap {
+    pub r: f64
+}
+
+impl Map for SineMap {
+    fn dimension(&self) -> usize {
+        1
+    }
+
+    fn step(&self, state: &[f64]) -> Vec<f64> {
+        vec![self.r * (std::f64::consts::PI * state[0]).sin()]
+    }
+}
+
+impl Parameters for SineMap {
+    fn parameters(&self) -> Vec<(String, f64)> {
+        vec![(String::from("r"), self.r)]
+    }
+
+    fn set_parameter(&mut self, name: &str, value: f64) -> bool {
+        if name != "r" {
+            return false;
+        }
+        self.r = value;
+        true
+    }
+}
+
+impl Default for SineMap {
+    fn default() -> Self {
+        Self { r: 0.8 }
+    }
+}
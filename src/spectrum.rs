@@ -0,0 +1,102 @@
+use std::f64::consts::PI;
+
+use crate::fft::real_fft;
+
+/// taper applied to each segment before the fft. Cutting a signal off abruptly leaks power from
+/// every peak into its neighbours, tapering to zero at the ends trades a wider peak for far less leakage
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Window {
+    Rectangular,
+    Hann,
+    Hamming,
+    Blackman
+}
+
+impl Window {
+    /// the window's `n` weights
+    pub fn coefficients(&self, n: usize) -> Vec<f64> {
+        let phase = |k: usize| 2.0 * PI * k as f64 / (n.max(2) - 1) as f64;
+        (0..n).map(|k| match self {
+            Window::Rectangular => 1.0,
+            Window::Hann => 0.5 - (0.5 * phase(k).cos()),
+            Window::Hamming => 0.54 - (0.46 * phase(k).cos()),
+            Window::Blackman => 0.42 - (0.5 * phase(k).cos()) + (0.08 * (2.0 * phase(k)).cos())
+        }).collect()
+    }
+}
+
+/// one-sided power spectral density
+#[derive(Debug, Clone)]
+pub struct Spectrum {
+    pub frequencies: Vec<f64>,
+    pub power: Vec<f64>
+}
+
+/// one-sided power spectral density of one windowed segment, mean removed first so the DC
+/// spike doesn't swamp everything. Scaled as a density: summing `power * df` gives the variance
+fn segment_psd(segment: &[f64], weights: &[f64], sample_rate: f64) -> Vec<f64> {
+    let n = segment.len();
+    let mean = segment.iter().sum::<f64>() / n as f64;
+    let tapered: Vec<f64> = segment.iter().zip(weights).map(|(x, w)| (x - mean) * w).collect();
+    let norm = sample_rate * weights.iter().map(|w| w * w).sum::<f64>();
+
+    real_fft(&tapered)[..=(n / 2)].iter().enumerate().map(|(k, c)| {
+        // every frequency but DC and nyquist also has a negative twin folded onto it
+        let fold = if k == 0 || k == n / 2 { 1.0 } else { 2.0 };
+        fold * (c.re * c.re + c.im * c.im) / norm
+    }).collect()
+}
+
+/// welch's method: the signal is cut into segments of `segment` samples (a power of two)
+/// overlapping by half, each one windowed and transformed, and their spectra averaged.
+///
+/// a single fft of the whole signal has a noisy spectrum no matter how long the signal is, the
+/// averaging trades frequency resolution (sample_rate / segment) for a steady estimate.
+/// periodic motion shows up as sharp peaks (and their harmonics), chaos as a broad continuum
+pub fn welch(signal: &[f64], sample_rate: f64, segment: usize, window: Window) -> Spectrum {
+    let segment = segment.min(signal.len().next_power_of_two() / 2).max(2);
+    let weights = window.coefficients(segment);
+    let hop = (segment / 2).max(1);
+
+    let mut power = vec![0.0; (segment / 2) + 1];
+    let mut count = 0;
+    let mut start = 0;
+    while start + segment <= signal.len() {
+        for (p, q) in power.iter_mut().zip(segment_psd(&signal[start..(start + segment)], &weights, sample_rate)) {
+            *p += q;
+        }
+        count += 1;
+        start += hop;
+    }
+    for p in power.iter_mut() {
+        *p /= count.max(1) as f64;
+    }
+
+    Spectrum {
+        frequencies: (0..power.len()).map(|k| k as f64 * sample_rate / segment as f64).collect(),
+        power
+    }
+}
+
+/// power spectral density over time
+#[derive(Debug, Clone)]
+pub struct Spectrogram {
+    /// sample index at the middle of each segment
+    pub centres: Vec<usize>,
+    pub frequencies: Vec<f64>,
+    /// one spectrum per segment
+    pub power: Vec<Vec<f64>>
+}
+
+/// spectra of successive segments of `segment` samples (a power of two), `hop` samples apart.
+/// For watching the spectrum change as a parameter is ramped
+pub fn spectrogram(signal: &[f64], sample_rate: f64, segment: usize, hop: usize, window: Window) -> Spectrogram {
+    let weights = window.coefficients(segment);
+    let starts: Vec<usize> = (0..).map(|k| k * hop.max(1)).take_while(|s| s + segment <= signal.len()).collect();
+
+    Spectrogram {
+        centres: starts.iter().map(|s| s + (segment / 2)).collect(),
+        frequencies: (0..=(segment / 2)).map(|k| k as f64 * sample_rate / segment as f64).collect(),
+        power: starts.iter().map(|s| segment_psd(&signal[*s..(s + segment)], &weights, sample_rate)).collect()
+    }
+}
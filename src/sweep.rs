@@ -0,0 +1,103 @@
+use crate::dynamical_system::{DynamicalSystem, Map, Parameters};
+use crate::integrators::rk4_step;
+use crate::lyapunov::{largest_lyapunov, largest_lyapunov_map, Benettin};
+use crate::runner::{run, run_grid, Progress};
+
+/// evenly spaced values from `from` to `to`, both included
+pub fn linspace(from: f64, to: f64, n: usize) -> Vec<f64> {
+    if n < 2 {
+        return vec![from; n];
+    }
+    (0..n).map(|i| from + ((to - from) * i as f64 / (n - 1) as f64)).collect()
+}
+
+/// copy of `system` with parameter `name` set to `value`.
+/// panics on an unknown name, sweeping a parameter that doesn't exist is a bug in the caller
+pub fn with_parameter<S: Parameters + Clone>(system: &S, name: &str, value: f64) -> S {
+    let mut system = system.clone();
+    assert!(system.set_parameter(name, value), "no parameter named '{}'", name);
+    system
+}
+
+/// largest lyapunov exponent of a map for sweeps: 2000 iterations after a transient of 500, re: [`largest_lyapunov_map`].
+/// NaN when the orbit escaped (or landed exactly on a superstable cycle, leaving nothing to measure)
+pub fn map_exponent(map: &impl Map, x0: &[f64]) -> f64 {
+    let settings = Benettin { renormalise_every: 1, transient: 500, ..Benettin::default() };
+    largest_lyapunov_map(map, x0, 2000, settings).last().copied().unwrap_or(f64::NAN)
+}
+
+/// largest lyapunov exponent of a flow for sweeps: 10000 steps of `dt` after a transient of 2000, re: [`largest_lyapunov`]
+pub fn flow_exponent(sys: &impl DynamicalSystem, x0: &[f64], dt: f64) -> f64 {
+    let settings = Benettin { transient: 2000, ..Benettin::default() };
+    largest_lyapunov(sys, x0, dt, 10000, settings).last().copied().unwrap_or(f64::NAN)
+}
+
+/// `exponent` (or any other measurement) of `system` with `name` set to each of `values`, in
+/// parallel. `None` when cancelled through `progress`
+pub fn sweep<S, R>(system: &S, name: &str, values: &[f64], progress: &Progress, exponent: impl Fn(&S) -> R + Sync) -> Option<Vec<R>>
+where S: Parameters + Clone + Sync, R: Send {
+    run(values, progress, |value| exponent(&with_parameter(system, name, *value)))
+}
+
+/// `exponent` over a grid of two parameters, in parallel. `rows[j][i]` is the value at
+/// `x_name = xs[i], y_name = ys[j]`. `None` when cancelled through `progress`
+pub fn sweep_plane<S, R>(system: &S, (x_name, xs): (&str, &[f64]), (y_name, ys): (&str, &[f64]), progress: &Progress, exponent: impl Fn(&S) -> R + Sync) -> Option<Vec<Vec<R>>>
+where S: Parameters + Clone + Sync, R: Send {
+    run_grid(xs, ys, progress, |x, y| exponent(&with_parameter(&with_parameter(system, x_name, x), y_name, y)))
+}
+
+/// bifurcation diagram of a map: for each value of `name`, the first component of `keep`
+/// iterates after `transient` iterations from `x0`
+pub fn bifurcation_map<S>(map: &S, name: &str, values: &[f64], x0: &[f64], transient: usize, keep: usize, progress: &Progress) -> Option<Vec<Vec<f64>>>
+where S: Map + Parameters + Clone + Sync {
+    sweep(map, name, values, progress, |map| {
+        let mut x = x0.to_vec();
+        for _ in 0..transient {
+            x = map.step(&x);
+        }
+        (0..keep).map(|_| {
+            x = map.step(&x);
+            x[0]
+        }).collect()
+    })
+}
+
+/// bifurcation diagram of a flow: for each value of `name`, the local maxima of `component`
+/// over `steps` rk4 steps after `transient` steps from `x0`. A periodic orbit shows up as a
+/// few repeated maxima, chaos as a smear (lorenz's own trick with z)
+#[allow(clippy::too_many_arguments)]
+pub fn bifurcation_flow<S>(sys: &S, name: &str, values: &[f64], x0: &[f64], dt: f64, transient: usize, steps: usize, component: usize, progress: &Progress) -> Option<Vec<Vec<f64>>>
+where S: DynamicalSystem + Parameters + Clone + Sync {
+    sweep(sys, name, values, progress, |sys| {
+        let mut x = x0.to_vec();
+        for _ in 0..transient {
+            rk4_step(sys, &mut x, 0.0, dt);
+        }
+
+        let mut maxima = vec![];
+        let (mut before, mut now) = (f64::NAN, x[component]);
+        for _ in 0..steps {
+            rk4_step(sys, &mut x, 0.0, dt);
+            if now > before && now > x[component] {
+                maxima.push(now);
+            }
+            (before, now) = (now, x[component]);
+        }
+        maxima
+    })
+}
+
+/// iterates `map` from `x0` while `name` ramps linearly from `from` to `to` over `iterations`,
+/// a slow sweep through its bifurcations. Returns the parameter value and the first component
+/// at every iteration
+pub fn ramp_map<S: Map + Parameters + Clone>(map: &S, name: &str, (from, to): (f64, f64), iterations: usize, x0: &[f64]) -> (Vec<f64>, Vec<f64>) {
+    let values = linspace(from, to, iterations);
+    let mut map = map.clone();
+    let mut x = x0.to_vec();
+    let signal = values.iter().map(|value| {
+        map.set_parameter(name, *value);
+        x = map.step(&x);
+        x[0]
+    }).collect();
+    (values, signal)
+}
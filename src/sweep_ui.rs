@@ -0,0 +1,312 @@
+use chaos_lab::{
+    export::{write_plane_csv, write_png, write_sweep_csv}, heatmap::{lyapunov_colour, Heatmap}, henon::Henon, logistic_map::LogisticMap,
+    lorenz::Lorenz, sweep::{bifurcation_flow, bifurcation_map, flow_exponent, linspace, map_exponent, sweep, sweep_plane}
+};
+use eframe::egui;
+use egui_plot::{HLine, Line, Plot, PlotPoints, Points};
+
+use crate::{show_image_plot, worker::Worker};
+
+/// system and parameter swept for the bifurcation diagram and lyapunov plot
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SweepSystem {
+    Logistic,
+    Henon,
+    Lorenz
+}
+
+impl SweepSystem {
+    /// (parameter name, default range)
+    fn parameter(&self) -> (&'static str, f64, f64) {
+        match self {
+            SweepSystem::Logistic => ("r", 2.5, 4.0),
+            SweepSystem::Henon => ("a", 1.0, 1.4),
+            SweepSystem::Lorenz => ("ro", 20.0, 200.0)
+        }
+    }
+}
+
+/// pair of parameters for the chaos map
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PlaneSystem {
+    Henon,
+    Lorenz
+}
+
+impl PlaneSystem {
+    /// (x name, x range, y name, y range)
+    #[allow(clippy::type_complexity)]
+    fn parameters(&self) -> ((&'static str, f64, f64), (&'static str, f64, f64)) {
+        match self {
+            PlaneSystem::Henon => (("a", 0.0, 1.5), ("b", 0.0, 0.4)),
+            PlaneSystem::Lorenz => (("ro", 10.0, 200.0), ("sigma", 2.0, 20.0))
+        }
+    }
+}
+
+// values per chunk of a one parameter sweep, and rows per chunk of a plane, re: `SweepPanel::poll`
+const SWEEP_CHUNK: usize = 50;
+const PLANE_CHUNK: usize = 4;
+
+// values, bifurcation diagram and exponents for one chunk of a sweep
+type SweepChunk = (Vec<f64>, Vec<Vec<f64>>, Vec<f64>);
+
+/// chaos map colours, re: `lyapunov_colour`
+fn plane_heatmap(system: PlaneSystem, rows: &[Vec<f64>]) -> Heatmap {
+    let scale = match system {
+        PlaneSystem::Henon => 0.5,
+        PlaneSystem::Lorenz => 1.5
+    };
+    // flows sit on zero when periodic, don't let integration noise colour them as chaos
+    let threshold = if system == PlaneSystem::Lorenz { 0.01 } else { 0.0 };
+
+    // image rows go top to bottom, the y axis bottom to top
+    let flipped: Vec<Vec<f64>> = rows.iter().rev().cloned().collect();
+    Heatmap::from_rows_with(&flipped, |lambda| lyapunov_colour(if lambda.abs() < threshold { 0.0 } else { lambda }, scale))
+}
+
+struct Plane {
+    system: PlaneSystem,
+    xs: Vec<f64>,
+    ys: Vec<f64>,
+    rows: Vec<Vec<f64>>,
+    heatmap: Heatmap
+}
+
+/// bifurcation diagram with the largest lyapunov exponent underneath, and a two parameter chaos map
+pub struct SweepPanel {
+    pub system: SweepSystem,
+    pub values: usize,
+    values_swept: Vec<f64>,
+    bifurcation: Vec<Vec<f64>>,
+    exponents: Vec<f64>,
+
+    pub plane_system: PlaneSystem,
+    pub plane_resolution: usize,
+    plane: Option<Plane>,
+    plane_texture: Option<egui::TextureHandle>,
+
+    job: Option<Worker<SweepChunk>>,
+    // (first row, rows) chunks
+    plane_job: Option<Worker<(usize, Vec<Vec<f64>>)>>,
+
+    export_status: String
+}
+
+impl Default for SweepPanel {
+    fn default() -> Self {
+        SweepPanel {
+            system: SweepSystem::Logistic,
+            values: 1000,
+            values_swept: vec![],
+            bifurcation: vec![],
+            exponents: vec![],
+            plane_system: PlaneSystem::Henon,
+            plane_resolution: 150,
+            plane: None,
+            plane_texture: None,
+            job: None,
+            plane_job: None,
+            export_status: String::new()
+        }
+    }
+}
+
+impl SweepPanel {
+    /// starts the bifurcation diagram and lyapunov exponent over the selected parameter range on a
+    /// background thread, in chunks so the plots fill in from the left as it goes
+    pub fn compute(&mut self, ctx: &egui::Context) {
+        let (name, from, to) = self.system.parameter();
+        let values = linspace(from, to, self.values);
+        let system = self.system;
+        self.values_swept.clear();
+        self.bifurcation.clear();
+        self.exponents.clear();
+
+        // every value is swept twice, once for the diagram and once for the exponent
+        self.job = Some(Worker::spawn(ctx, 2 * values.len(), move |progress, send| {
+            for chunk in values.chunks(SWEEP_CHUNK) {
+                let result = match system {
+                    SweepSystem::Logistic => {
+                        let map = LogisticMap { r: 4.0 };
+                        bifurcation_map(&map, name, chunk, &[0.5], 500, 100, progress)
+                        .zip(sweep(&map, name, chunk, progress, |map| map_exponent(map, &[0.5])))
+                    }
+                    SweepSystem::Henon => {
+                        let map = Henon::default();
+                        bifurcation_map(&map, name, chunk, &[0.1, 0.1], 500, 100, progress)
+                        .zip(sweep(&map, name, chunk, progress, |map| map_exponent(map, &[0.1, 0.1])))
+                    }
+                    SweepSystem::Lorenz => {
+                        let lorenz = Lorenz::default();
+                        // maxima of z, lorenz's own return map
+                        bifurcation_flow(&lorenz, name, chunk, &[1.0, 1.0, 1.0], 0.01, 5000, 5000, 2, progress)
+                        .zip(sweep(&lorenz, name, chunk, progress, |sys| flow_exponent(sys, &[1.0, 1.0, 1.0], 0.01)))
+                    }
+                };
+                // cancelled
+                let Some((bifurcation, exponents)) = result else { return };
+                send((chunk.to_vec(), bifurcation, exponents));
+            }
+        }));
+    }
+
+    /// starts the chaos map on a background thread, a few rows at a time
+    fn compute_plane(&mut self, ctx: &egui::Context) {
+        let system = self.plane_system;
+        let ((x_name, x_from, x_to), (y_name, y_from, y_to)) = system.parameters();
+        let xs = linspace(x_from, x_to, self.plane_resolution);
+        let ys = linspace(y_from, y_to, self.plane_resolution);
+
+        // not computed yet is NaN, drawn black until its row arrives
+        let rows = vec![vec![f64::NAN; xs.len()]; ys.len()];
+        self.plane = Some(Plane { system, heatmap: plane_heatmap(system, &rows), xs: xs.clone(), ys: ys.clone(), rows });
+        self.plane_texture = None;
+
+        self.plane_job = Some(Worker::spawn(ctx, xs.len() * ys.len(), move |progress, send| {
+            for (k, ys) in ys.chunks(PLANE_CHUNK).enumerate() {
+                let rows = match system {
+                    PlaneSystem::Henon => sweep_plane(&Henon::default(), (x_name, &xs), (y_name, ys), progress, |map| map_exponent(map, &[0.1, 0.1])),
+                    PlaneSystem::Lorenz => sweep_plane(&Lorenz::default(), (x_name, &xs), (y_name, ys), progress, |sys| flow_exponent(sys, &[1.0, 1.0, 1.0], 0.01))
+                };
+                let Some(rows) = rows else { return };
+                send((k * PLANE_CHUNK, rows));
+            }
+        }));
+    }
+
+    /// picks up whatever the background jobs finished since the last frame
+    fn poll(&mut self) {
+        if let Some(job) = &self.job {
+            // checked before polling, so nothing sent in between gets lost
+            let finished = job.is_finished();
+            for (values, bifurcation, exponents) in job.poll() {
+                self.values_swept.extend(values);
+                self.bifurcation.extend(bifurcation);
+                self.exponents.extend(exponents);
+            }
+            if finished {
+                self.job = None;
+            }
+        }
+
+        if let (Some(job), Some(plane)) = (&self.plane_job, &mut self.plane) {
+            let finished = job.is_finished();
+            let chunks = job.poll();
+            if !chunks.is_empty() {
+                for (start, rows) in chunks {
+                    for (i, row) in rows.into_iter().enumerate() {
+                        plane.rows[start + i] = row;
+                    }
+                }
+                plane.heatmap = plane_heatmap(plane.system, &plane.rows);
+                self.plane_texture = None;
+            }
+            if finished {
+                self.plane_job = None;
+            }
+        }
+    }
+
+    pub fn ui(&mut self, ui: &mut egui::Ui) {
+        self.poll();
+
+        ui.horizontal(|ui| {
+            egui::ComboBox::from_label("sweep")
+            .selected_text(format!("{:?} {}", self.system, self.system.parameter().0))
+            .show_ui(ui, |ui| {
+                ui.selectable_value(&mut self.system, SweepSystem::Logistic, "Logistic r");
+                ui.selectable_value(&mut self.system, SweepSystem::Henon, "Henon a");
+                ui.selectable_value(&mut self.system, SweepSystem::Lorenz, "Lorenz ro");
+            });
+            ui.label("values");
+            ui.add(egui::Slider::new(&mut self.values, 50..=2000).logarithmic(true));
+            if ui.button("Compute").clicked() {
+                self.compute(ui.ctx());
+            }
+        });
+        if let Some(job) = &self.job {
+            job.ui(ui);
+        }
+
+        let name = self.system.parameter().0;
+        let points: PlotPoints<'_> = self.values_swept.iter().zip(&self.bifurcation)
+        .flat_map(|(value, xs)| xs.iter().map(move |x| [*value, *x]))
+        .collect();
+        Plot::new("bifurcation")
+        .view_aspect(2.0)
+        .x_axis_label(name)
+        .y_axis_label(if self.system == SweepSystem::Lorenz { "max z" } else { "x_n" })
+        .link_axis("sweep", [true, false])
+        .show(ui, |plot_ui| {
+            plot_ui.points(Points::new("pts", points).radius(0.9).color(egui::Color32::LIGHT_BLUE));
+        });
+
+        let exponents: PlotPoints<'_> = self.values_swept.iter().zip(&self.exponents).map(|(value, lambda)| [*value, *lambda]).collect();
+        Plot::new("lyapunov sweep")
+        .view_aspect(4.0)
+        .x_axis_label(name)
+        .y_axis_label("λ1")
+        .link_axis("sweep", [true, false])
+        .show(ui, |plot_ui| {
+            plot_ui.line(Line::new("λ1", exponents));
+            plot_ui.hline(HLine::new("0", 0.0).color(egui::Color32::GRAY));
+        });
+
+        if !self.exponents.is_empty() && ui.button("Export CSV").clicked() {
+            let path = format!("lyapunov_{}.csv", name);
+            self.export_status = match write_sweep_csv(&path, name, &self.values_swept, &[("lambda", &self.exponents)]) {
+                Ok(()) => format!("wrote {}", path),
+                Err(e) => format!("export failed: {}", e)
+            };
+        }
+
+        ui.collapsing("parameter plane", |ui| self.ui_plane(ui));
+        ui.label(&self.export_status);
+    }
+
+    fn ui_plane(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            egui::ComboBox::from_label("plane")
+            .selected_text(format!("{:?}", self.plane_system))
+            .show_ui(ui, |ui| {
+                ui.selectable_value(&mut self.plane_system, PlaneSystem::Henon, "Henon a/b");
+                ui.selectable_value(&mut self.plane_system, PlaneSystem::Lorenz, "Lorenz ro/sigma");
+            });
+            ui.label("resolution");
+            ui.add(egui::Slider::new(&mut self.plane_resolution, 10..=400));
+            if ui.button("Compute").clicked() {
+                self.compute_plane(ui.ctx());
+            }
+        });
+        if let Some(job) = &self.plane_job {
+            job.ui(ui);
+        }
+        ui.label("red/yellow: chaotic (λ1 > 0), blue: periodic or fixed point, black: escaped (or not computed yet)");
+
+        let Some(plane) = &self.plane else { return };
+        let ((x_name, ..), (y_name, ..)) = plane.system.parameters();
+
+        let x_range = (x_name, plane.xs[0], plane.xs[plane.xs.len() - 1]);
+        let y_range = (y_name, plane.ys[0], plane.ys[plane.ys.len() - 1]);
+        show_image_plot(ui, &mut self.plane_texture, "parameter plane", &plane.heatmap, x_range, y_range);
+
+        ui.horizontal(|ui| {
+            let stem = format!("{:?}_plane", plane.system).to_lowercase();
+            if ui.button("Export PNG").clicked() {
+                let path = format!("{}.png", stem);
+                self.export_status = match write_png(&path, &plane.heatmap) {
+                    Ok(()) => format!("wrote {}", path),
+                    Err(e) => format!("export failed: {}", e)
+                };
+            }
+            if ui.button("Export CSV").clicked() {
+                let path = format!("{}.csv", stem);
+                self.export_status = match write_plane_csv(&path, (x_name, &plane.xs), (y_name, &plane.ys), "lambda", &plane.rows) {
+                    Ok(()) => format!("wrote {}", path),
+                    Err(e) => format!("export failed: {}", e)
+                };
+            }
+        });
+    }
+}
@@ -1,8 +1,6 @@
 #[cfg(test)]
-
-
 mod tests {
-    use crate::{double_pendulum::DoublePendulum, dynamical_system::DynamicalSystem, integrators::{euler_step, rk4_step}, logistic_map::LogisticMap, lyapunov, math::Vec2};
+    use crate::{double_pendulum::DoublePendulum, dynamical_system::DynamicalSystem, integrators::{euler_step, rk4_step}, logistic_map::LogisticMap, math::Vec2, n_pendulum::NPendulum};
 
     
 
@@ -42,7 +40,7 @@ mod tests {
     fn test_vec2_norm() {
         let v1 = Vec2::new(1.0,2.0);
 
-        assert_eq!((5.0 as f64).sqrt(),v1.norm());
+        assert_eq!(5.0_f64.sqrt(),v1.norm());
     }
 
     #[test]
@@ -160,4 +158,895 @@ mod tests {
         assert!(result < 0.0);
     }
 
+    #[test]
+    fn test_solve_linear() {
+        let a = vec![vec![2.0, 1.0], vec![1.0, 3.0]];
+        let x = crate::math::solve_linear(&a, &[3.0, 5.0]).unwrap();
+
+        assert!((x[0] - 0.8).abs() < 1e-12);
+        assert!((x[1] - 1.4).abs() < 1e-12);
+
+        // singular
+        let a = vec![vec![1.0, 2.0], vec![2.0, 4.0]];
+        assert!(crate::math::solve_linear(&a, &[1.0, 2.0]).is_none());
+    }
+
+    #[test]
+    fn test_n_pendulum_matches_double_pendulum() {
+        let dp = DoublePendulum { m1: 1.5, m2: 0.7, l1: 1.2, l2: 0.8 };
+        let np = NPendulum::from_links(&[(1.5, 1.2), (0.7, 0.8)]);
+
+        let state = [0.9, -1.7, 0.3, 2.1];
+        let expected = dp.rhs(0.0, &state);
+        let result = np.rhs(0.0, &state);
+
+        for i in 0..4 {
+            assert!((expected[i] - result[i]).abs() < 1e-9, "component {}: {} vs {}", i, expected[i], result[i]);
+        }
+    }
+
+    #[test]
+    fn test_n_pendulum_conserves_energy() {
+        let g = 9.81;
+        let np = NPendulum::from_links(&[(1.0, 1.0), (2.0, 0.5), (0.5, 1.0), (1.0, 0.8), (1.5, 1.2)]);
+        let n = np.links();
+
+        // kinetic + potential energy of the point masses
+        let energy = |s: &[f64]| -> f64 {
+            let mut vx = 0.0;
+            let mut vy = 0.0;
+            let pos = np.positions(&s[0..n]);
+            let mut e = 0.0;
+            for i in 0..n {
+                vx += np.chain[i].length * s[i].cos() * s[n + i];
+                vy += np.chain[i].length * s[i].sin() * s[n + i];
+                e += 0.5 * np.chain[i].mass * (vx * vx + vy * vy) + np.chain[i].mass * g * pos[i][1];
+            }
+            e
+        };
+
+        let mut state = vec![1.0, 0.5, -0.3, 2.0, 0.1, 0.0, 0.0, 0.0, 0.0, 0.0];
+        let e0 = energy(&state);
+
+        for _ in 0..1000 {
+            rk4_step(&np, &mut state, 0.0, 0.001);
+        }
+
+        let drift = (energy(&state) - e0).abs() / e0.abs();
+        assert!(drift < 1e-4, "energy drifted by {}", drift);
+    }
+
+    #[test]
+    fn test_sde_steppers() {
+        use crate::{integrators::{euler_maruyama_step, milstein_step}, lorenz::Lorenz, rng::rng_generator, sde::{AdditiveNoise, MultiplicativeNoise}};
+
+        // no noise: euler–maruyama is just euler
+        let sys = AdditiveNoise { system: Lorenz::default(), sigma: 0.0 };
+        let mut state = [1.0, 1.0, 1.0];
+        let mut expected = [1.0, 1.0, 1.0];
+        let mut rng = rng_generator(1);
+        for _ in 0..100 {
+            euler_maruyama_step(&sys, &mut state, 0.0, 0.01, &mut rng);
+            euler_step(&sys.system, &mut expected, 0.0, 0.01);
+        }
+        assert_eq!(expected, state);
+
+        // additive noise: milstein correction vanishes, same seed -> same path
+        let sys = AdditiveNoise { system: Lorenz::default(), sigma: 2.0 };
+        let mut em = [1.0, 1.0, 1.0];
+        let mut mil = [1.0, 1.0, 1.0];
+        let mut rng_em = rng_generator(7);
+        let mut rng_mil = rng_generator(7);
+        for _ in 0..100 {
+            euler_maruyama_step(&sys, &mut em, 0.0, 0.01, &mut rng_em);
+            milstein_step(&sys, &mut mil, 0.0, 0.01, &mut rng_mil);
+        }
+        assert_eq!(em, mil);
+
+        // multiplicative noise does pick up the correction
+        let sys = MultiplicativeNoise { system: Lorenz::default(), sigma: 0.5 };
+        let mut em = [1.0, 1.0, 1.0];
+        let mut mil = [1.0, 1.0, 1.0];
+        let mut rng_em = rng_generator(7);
+        let mut rng_mil = rng_generator(7);
+        euler_maruyama_step(&sys, &mut em, 0.0, 0.01, &mut rng_em);
+        milstein_step(&sys, &mut mil, 0.0, 0.01, &mut rng_mil);
+        assert_ne!(em, mil);
+    }
+
+    #[test]
+    fn test_sde_ensemble_geometric_brownian_motion() {
+        use crate::{runner::Progress, sde::{ensemble_stats, MultiplicativeNoise, Scheme}};
+
+        // dx = mu x dt + sigma x dW has E[x(t)] = x0 e^(mu t), whatever sigma is
+        struct Growth;
+        impl DynamicalSystem for Growth {
+            fn dimension(&self) -> usize {
+                1
+            }
+
+            fn rhs(&self, _t: f64, state: &[f64]) -> Vec<f64> {
+                vec![0.5 * state[0]]
+            }
+        }
+
+        let sys = MultiplicativeNoise { system: Growth, sigma: 0.2 };
+        let stats = ensemble_stats(&sys, &[1.0], 4000, 100, 0.01, Scheme::Milstein, 42, &Progress::new()).unwrap();
+
+        let expected = 0.5_f64.exp();
+        assert_eq!(101, stats.times.len());
+        assert!((stats.mean[100][0] - expected).abs() < 0.02, "mean {} vs {}", stats.mean[100][0], expected);
+        assert!((stats.deterministic[100][0] - expected).abs() < 1e-6);
+        // std of a lognormal: x0 e^(mu t) sqrt(e^(sigma^2 t) - 1)
+        let expected_std = expected * (0.04_f64.exp() - 1.0).sqrt();
+        assert!((stats.std_dev[100][0] - expected_std).abs() < 0.02, "std {} vs {}", stats.std_dev[100][0], expected_std);
+
+        // same seed, same ensemble
+        let again = ensemble_stats(&sys, &[1.0], 4000, 100, 0.01, Scheme::Milstein, 42, &Progress::new()).unwrap();
+        assert_eq!(stats.mean, again.mean);
+    }
+
+    #[test]
+    fn test_seeded_rng_replays() {
+        use rand::Rng;
+        use crate::{lorenz::Lorenz, noise::{Distribution, Noise, NoiseSpec}, rng::SeededRng};
+
+        let spec = NoiseSpec::new().parameter("ro", Distribution::Uniform(1.0));
+        let mut rng = SeededRng::new(12);
+        let first: Vec<f64> = (0..5).map(|_| rng.random()).collect();
+        let noisy = Lorenz::default().new_noisy(&spec, &mut rng).unwrap();
+
+        rng.reset();
+        let again: Vec<f64> = (0..5).map(|_| rng.random()).collect();
+        let noisy_again = Lorenz::default().new_noisy(&spec, &mut rng).unwrap();
+
+        assert_eq!(first, again);
+        assert_eq!(noisy.ro, noisy_again.ro);
+
+        // sub-streams don't touch the main stream and differ from each other
+        let a: f64 = rng.stream(0).random();
+        let b: f64 = rng.stream(1).random();
+        assert_ne!(a, b);
+        assert_eq!(a, SeededRng::new(12).stream(0).random::<f64>());
+        // and the first one isn't the main stream over again
+        let mut stream = SeededRng::new(12).stream(0);
+        let sub: Vec<f64> = (0..5).map(|_| stream.random()).collect();
+        assert_ne!(first, sub);
+    }
+
+    #[test]
+    fn test_cli_ensemble_is_reproducible() {
+        use crate::cli::{run, Args};
+
+        let args = |seed: &str| Args::parse(["ensemble", "--seed", seed, "--members", "5", "--steps", "50"].map(String::from)).unwrap();
+
+        let mut out = Vec::new();
+        run(&args("3"), &mut out).unwrap();
+        let mut again = Vec::new();
+        run(&args("3"), &mut again).unwrap();
+        let mut other = Vec::new();
+        run(&args("4"), &mut other).unwrap();
+
+        let text = String::from_utf8(out.clone()).unwrap();
+        assert!(text.starts_with("# seed = 3\nt,mean_x"));
+        // header, column names and every 10th of the 51 samples
+        assert_eq!(2 + 6, text.lines().count());
+        assert_eq!(out, again);
+        assert_ne!(out, other);
+
+        assert!(Args::parse(["ensemble", "--seed"].map(String::from)).is_err());
+        assert!(run(&Args::parse(["ensemble", "--scheme", "rk4"].map(String::from)).unwrap(), &mut Vec::new()).is_err());
+    }
+
+    #[test]
+    fn test_noise_spec() {
+        use crate::{dynamical_system::Parameters, lorenz::Lorenz, n_pendulum::NPendulum, noise::{Distribution, Noise, NoiseSpec}, rng::rng_generator};
+
+        let mut rng = rng_generator(5);
+
+        // zero width never panics and never moves anything
+        for dist in [Distribution::Uniform(0.0), Distribution::Gaussian(0.0), Distribution::Relative(-1.0)] {
+            assert_eq!(3.0, dist.sample(3.0, &mut rng));
+        }
+
+        // symmetric: the average perturbation is ~0, not biased upward
+        for dist in [Distribution::Uniform(1.0), Distribution::Gaussian(1.0), Distribution::Relative(10.0)] {
+            let mean: f64 = (0..20000).map(|_| dist.sample(10.0, &mut rng) - 10.0).sum::<f64>() / 20000.0;
+            assert!(mean.abs() < 0.03, "{} is biased: {}", dist, mean);
+        }
+
+        // more than 100% would flip the sign, so it's capped there
+        assert!((0..1000).all(|_| Distribution::Relative(500.0).sample(2.0, &mut rng) >= 0.0));
+
+        // only the named parameters move
+        let lorenz = Lorenz::default();
+        let noisy = lorenz.new_noisy(&NoiseSpec::new().parameter("beta", Distribution::Gaussian(0.5)), &mut rng).unwrap();
+        assert_eq!(lorenz.sigma, noisy.sigma);
+        assert_eq!(lorenz.ro, noisy.ro);
+        assert_ne!(lorenz.beta, noisy.beta);
+        assert!(lorenz.new_noisy(&NoiseSpec::new().parameter("rho", Distribution::Uniform(1.0)), &mut rng).is_err());
+
+        // every system has a noise impl through its parameters
+        let dp = DoublePendulum::default().new_noisy(&NoiseSpec::new().all_parameters(&DoublePendulum::default(), Distribution::Relative(5.0)), &mut rng).unwrap();
+        assert!((dp.m1 - 1.0).abs() <= 0.05 && dp.m1 != 1.0);
+        let lmap = LogisticMap { r: 3.5 }.new_noisy(&NoiseSpec::new().parameter("r", Distribution::Uniform(0.1)), &mut rng).unwrap();
+        assert!((lmap.r - 3.5).abs() <= 0.1);
+        let mut np = NPendulum::new(3);
+        assert!(np.set_parameter("l3", 2.0));
+        assert!(!np.set_parameter("l4", 2.0));
+        assert!(!np.set_parameter("l0", 2.0));
+        assert_eq!(Some(2.0), np.parameter("l3"));
+
+        // per state component
+        let state = NoiseSpec::new().state_component(1, Distribution::Uniform(1.0)).perturb_state(&[1.0, 1.0, 1.0], &mut rng);
+        assert_eq!(1.0, state[0]);
+        assert_ne!(1.0, state[1]);
+        assert_eq!(1.0, state[2]);
+    }
+
+    #[test]
+    fn test_dde_method_of_steps() {
+        use crate::dde::{dde_rk4_step, DelaySystem, History, MackeyGlass};
+
+        // x' = -x(t-1) with x = 1 for t <= 0 has the exact solution
+        // x = 1 - t on [0,1] and x = 1 - t + (t-1)^2 / 2 on [1,2]
+        struct Lag;
+        impl DelaySystem for Lag {
+            fn dimension(&self) -> usize {
+                1
+            }
+
+            fn delays(&self) -> Vec<f64> {
+                vec![1.0]
+            }
+
+            fn rhs(&self, _t: f64, _state: &[f64], delayed: &[Vec<f64>]) -> Vec<f64> {
+                vec![-delayed[0][0]]
+            }
+        }
+
+        let mut history = History::new(&Lag, &[1.0], 0.01);
+        for _ in 0..200 {
+            dde_rk4_step(&Lag, &mut history);
+        }
+        assert!((history.time() - 2.0).abs() < 1e-9);
+        assert!((history.current()[0] + 0.5).abs() < 1e-6, "x(2) = {}", history.current()[0]);
+        // interpolated history, halfway between grid points
+        let t = 1.505;
+        assert!((history.at(t)[0] - (1.0 - t + 0.5 * (t - 1.0) * (t - 1.0))).abs() < 1e-6);
+
+        // mackey–glass settles onto its (bounded, positive) chaotic attractor
+        let mg = MackeyGlass::default();
+        let mut history = History::new(&mg, &[1.2], 0.1);
+        let mut lo = f64::MAX;
+        let mut hi = f64::MIN;
+        for k in 0..20000 {
+            dde_rk4_step(&mg, &mut history);
+            if k > 5000 {
+                lo = lo.min(history.current()[0]);
+                hi = hi.max(history.current()[0]);
+            }
+        }
+        assert!(lo > 0.1 && hi < 1.5 && hi - lo > 0.8, "range [{}, {}]", lo, hi);
+    }
+
+    #[test]
+    fn test_spatially_extended_systems() {
+        use crate::{coupled_map_lattice::CoupledMapLattice, dynamical_system::Map, heatmap::Heatmap, lorenz96::Lorenz96};
+
+        // x_i = F is a fixed point of lorenz 96
+        let l96 = Lorenz96::new(36, 8.0);
+        assert!(l96.rhs(0.0, &[8.0; 36]).iter().all(|d| d.abs() < 1e-12));
+        // too few sites for the two-back stencil
+        assert!(std::panic::catch_unwind(|| Lorenz96::new(3, 8.0)).is_err());
+
+        // the nudged fixed point is unstable at F = 8, the disturbance spreads around the ring
+        let mut state = l96.initial_state();
+        for _ in 0..2000 {
+            rk4_step(&l96, &mut state, 0.0, 0.01);
+        }
+        assert!(state.iter().all(|x| x.is_finite()));
+        assert!(state.iter().filter(|x| (*x - 8.0).abs() > 0.5).count() > 18);
+
+        // uncoupled lattice = independent logistic maps
+        let cml = CoupledMapLattice { sites: 3, local: LogisticMap { r: 3.9 }, epsilon: 0.0 };
+        let state = [0.1, 0.5, 0.8];
+        let next = cml.step(&state);
+        for i in 0..3 {
+            assert_eq!(LogisticMap { r: 3.9 }.step(&[state[i]])[0], next[i]);
+        }
+
+        // a synchronised lattice stays synchronised for any coupling
+        let cml = CoupledMapLattice { sites: 10, local: LogisticMap { r: 3.9 }, epsilon: 0.4 };
+        let next = cml.step(&[0.3; 10]);
+        assert!(next.iter().all(|x| (x - next[0]).abs() < 1e-15));
+
+        let heatmap = Heatmap::from_rows(&[vec![0.0, 1.0], vec![0.5, f64::NAN]], None);
+        assert_eq!((2, 2), (heatmap.width, heatmap.height));
+        assert_eq!([68, 1, 84], heatmap.pixels[0]);
+        assert_eq!([253, 231, 37], heatmap.pixels[1]);
+        assert_eq!([0, 0, 0], heatmap.pixels[3]);
+    }
+
+    #[test]
+    fn test_fft_matches_dft() {
+        use crate::{fft::{fft, ifft}, math::Complex};
+
+        let n = 16;
+        let signal: Vec<Complex> = (0..n).map(|j| Complex::new((j as f64 * 0.7).sin() + 0.3, (j as f64).cos())).collect();
+
+        let mut data = signal.clone();
+        fft(&mut data);
+
+        for (k, result) in data.iter().enumerate() {
+            // naive O(n^2) dft
+            let mut expected = Complex::new(0.0, 0.0);
+            for (j, x) in signal.iter().enumerate() {
+                let angle = -2.0 * std::f64::consts::PI * (k * j) as f64 / n as f64;
+                expected = expected + (*x * Complex::new(angle.cos(), angle.sin()));
+            }
+            assert!((*result - expected).norm() < 1e-10);
+        }
+
+        ifft(&mut data);
+        for (a, b) in data.iter().zip(&signal) {
+            assert!((*a - *b).norm() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_kuramoto_sivashinsky_etdrk4() {
+        use crate::kuramoto_sivashinsky::{Etdrk4, KuramotoSivashinsky};
+
+        let ks = KuramotoSivashinsky::default();
+        let u0 = ks.initial_state();
+        let mean0: f64 = u0.iter().sum::<f64>() / u0.len() as f64;
+
+        // agrees with (tiny step) rk4 on the same pseudo-spectral rhs over a short run
+        let mut etd = u0.clone();
+        let stepper = Etdrk4::new(&ks, 0.05);
+        for _ in 0..20 {
+            stepper.step(&mut etd);
+        }
+        let mut rk = u0.clone();
+        for _ in 0..1000 {
+            rk4_step(&ks, &mut rk, 0.0, 0.001);
+        }
+        let max_diff = etd.iter().zip(&rk).map(|(a, b)| (a - b).abs()).fold(0.0, f64::max);
+        assert!(max_diff < 1e-4, "etdrk4 vs rk4: {}", max_diff);
+
+        // long run: mean is conserved and the chaos stays bounded
+        let mut u = u0.clone();
+        let stepper = Etdrk4::new(&ks, 0.25);
+        for _ in 0..600 {
+            stepper.step(&mut u);
+        }
+        let mean: f64 = u.iter().sum::<f64>() / u.len() as f64;
+        assert!((mean - mean0).abs() < 1e-9);
+        let max = u.iter().fold(0.0_f64, |m, x| m.max(x.abs()));
+        assert!(max > 1.0 && max < 5.0, "max |u| = {}", max);
+    }
+
+    #[test]
+    fn test_write_space_time_csv() {
+        let path = std::env::temp_dir().join("chaos_lab_space_time_test.csv");
+        crate::export::write_space_time_csv(&path, &[0.0, 0.5], &[0.0, 0.25], &[vec![1.0, 2.0], vec![3.0, 4.0]]).unwrap();
+
+        let text = std::fs::read_to_string(&path).unwrap();
+        assert_eq!("t,x=0,x=0.5\n0,1,2\n0.25,3,4\n", text);
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_correlation_dimension() {
+        use crate::{dimension::{correlation_dimension, ScalingFit}, henon::Henon, lorenz::Lorenz, trajectory::Trajectory};
+
+        // no line through fewer than two points
+        assert!(ScalingFit::fit(vec![], vec![], 8).is_none());
+        assert!(ScalingFit::fit_range(vec![1.0], vec![1.0], 0, 1).is_none());
+
+        let lorenz = Trajectory::integrate(&Lorenz::default(), &[1.0, 1.0, 1.0], 0.01, 5, 3000, 200);
+        // the theiler window leaves no pairs
+        assert!(correlation_dimension(&lorenz.states[..10], 10).is_none());
+        let fit = correlation_dimension(&lorenz.states, 10).unwrap();
+        assert!((fit.slope - 2.05).abs() < 0.1, "lorenz D2 = {} over {:?}", fit.slope, (fit.start, fit.end));
+
+        let henon = Trajectory::iterate(&Henon::default(), &[0.1, 0.1], 8000, 100);
+        let fit = correlation_dimension(&henon.states, 0).unwrap();
+        assert!((fit.slope - 1.21).abs() < 0.05, "henon D2 = {} over {:?}", fit.slope, (fit.start, fit.end));
+    }
+
+    #[test]
+    fn test_box_counting_dimension() {
+        use crate::{dimension::{box_counting_dimension, box_counts, information_dimension}, henon::Henon, trajectory::Trajectory};
+
+        // 4 points on a unit square's corners: one box when they're all inside, four when split
+        let square = vec![vec![0.0, 0.0], vec![0.0, 0.9], vec![0.9, 0.0], vec![0.9, 0.9]];
+        let counts = box_counts(&square, &[2.0, 0.5]);
+        assert_eq!(1, counts[0].boxes);
+        assert_eq!(4, counts[1].boxes);
+        assert!((counts[1].information - 4.0_f64.ln()).abs() < 1e-12);
+
+        // fully chaotic logistic map fills the whole interval
+        let logistic = Trajectory::iterate(&LogisticMap { r: 4.0 }, &[0.3], 50000, 100);
+        // a handful of points are a handful of boxes at every scale
+        assert!(box_counting_dimension(&logistic.states[..8]).is_none());
+        let d0 = box_counting_dimension(&logistic.states).unwrap();
+        assert!((d0.slope - 1.0).abs() < 0.05, "logistic D0 = {}", d0.slope);
+
+        let henon = Trajectory::iterate(&Henon::default(), &[0.1, 0.1], 100000, 100);
+        let d0 = box_counting_dimension(&henon.states).unwrap();
+        let d1 = information_dimension(&henon.states).unwrap();
+        assert!((d0.slope - 1.26).abs() < 0.08, "henon D0 = {}", d0.slope);
+        assert!((d1.slope - 1.23).abs() < 0.08, "henon D1 = {}", d1.slope);
+        assert!(d1.slope <= d0.slope + 0.02);
+    }
+
+    #[test]
+    fn test_lyapunov_spectrum() {
+        use crate::{cli::{run, Args}, henon::Henon, lorenz::Lorenz, lyapunov::{diagnose, diagnose_map, kaplan_yorke_dimension}, runner::Progress};
+
+        // textbook values, re: sprott's "chaos and time-series analysis"
+        let lorenz = diagnose(&Lorenz::default(), &[1.0, 1.0, 1.0], 0.01, 50000, 1000, &Progress::new()).unwrap();
+        assert!((lorenz.spectrum[0] - 0.906).abs() < 0.05, "{:?}", lorenz.spectrum);
+        assert!(lorenz.spectrum[1].abs() < 0.02, "{:?}", lorenz.spectrum);
+        // the exponents must sum to the divergence, -(σ + 1 + β)
+        assert!((lorenz.spectrum.iter().sum::<f64>() + 10.0 + 1.0 + 8.0 / 3.0).abs() < 0.01);
+        assert!((lorenz.kaplan_yorke - 2.06).abs() < 0.01);
+
+        let henon = diagnose_map(&Henon::default(), &[0.1, 0.1], 50000, 100, &Progress::new()).unwrap();
+        assert!((henon.spectrum[0] - 0.419).abs() < 0.01, "{:?}", henon.spectrum);
+        // constant jacobian determinant -b
+        assert!((henon.spectrum[1] - (0.3_f64.ln() - henon.spectrum[0])).abs() < 1e-6);
+        assert!((henon.kaplan_yorke - 1.26).abs() < 0.01);
+        assert!((henon.ks_entropy - henon.spectrum[0]).abs() < 1e-12);
+
+        assert_eq!(kaplan_yorke_dimension(&[-1.0, -2.0]), 0.0);
+        assert_eq!(kaplan_yorke_dimension(&[1.0, 0.0, -2.0]), 2.5);
+
+        // no steps still averages over one rather than dividing by zero
+        assert!(diagnose(&Lorenz::default(), &[1.0, 1.0, 1.0], 0.01, 0, 0, &Progress::new()).unwrap().spectrum.iter().all(|l| l.is_finite()));
+        // but the cli won't report it
+        assert!(run(&Args::parse(["diagnostics", "--steps", "0"].map(String::from)).unwrap(), &mut Vec::new()).is_err());
+
+        let cancelled = Progress::new();
+        cancelled.cancel();
+        assert!(diagnose(&Lorenz::default(), &[1.0, 1.0, 1.0], 0.01, 50000, 0, &cancelled).is_none());
+    }
+
+    #[test]
+    fn test_two_trajectory_lyapunov() {
+        use crate::{henon::Henon, lorenz::Lorenz, lyapunov::{largest_lyapunov, largest_lyapunov_map, lyapunov, Benettin}};
+
+        // the logistic map at r = 4 through the old 1d function and the general one agree on ln 2
+        let old = lyapunov(0.3, 100000, |x| 4.0 * x * (1.0 - x));
+        let new = largest_lyapunov_map(&LogisticMap { r: 4.0 }, &[0.3], 100000, Benettin { renormalise_every: 1, ..Benettin::default() });
+        assert!((old - 2.0_f64.ln()).abs() < 0.01);
+        assert!((new.last().unwrap() - 2.0_f64.ln()).abs() < 0.01);
+
+        let henon = largest_lyapunov_map(&Henon::default(), &[0.1, 0.1], 50000, Benettin { renormalise_every: 1, ..Benettin::default() });
+        assert!((henon.last().unwrap() - 0.419).abs() < 0.01);
+
+        let running = largest_lyapunov(&Lorenz::default(), &[1.0, 1.0, 1.0], 0.01, 50000, Benettin::default());
+        assert_eq!(running.len(), 5000);
+        assert!((running.last().unwrap() - 0.906).abs() < 0.05, "{}", running.last().unwrap());
+    }
+
+    #[test]
+    fn test_parameter_sweeps() {
+        use crate::{
+            export::{write_plane_csv, write_png}, heatmap::{lyapunov_colour, Heatmap}, henon::Henon, lyapunov::{largest_lyapunov_map, Benettin},
+            runner::Progress, sweep::{bifurcation_map, linspace, sweep, sweep_plane}
+        };
+
+        let exponent = |map: &LogisticMap| {
+            let settings = Benettin { renormalise_every: 1, transient: 500, ..Benettin::default() };
+            *largest_lyapunov_map(map, &[0.3], 5000, settings).last().unwrap()
+        };
+        // stable fixed point, period 2 (multiplier 4 + 2r - r² over two iterations at r = 3.2), chaos
+        let rs = [2.8, 3.2, 4.0];
+        let lambdas = sweep(&LogisticMap { r: 4.0 }, "r", &rs, &Progress::new(), exponent).unwrap();
+        assert!((lambdas[0] - (2.0 - 2.8_f64).abs().ln()).abs() < 0.01, "{:?}", lambdas);
+        assert!((lambdas[1] - (4.0 + 6.4 - 3.2 * 3.2_f64).abs().ln() / 2.0).abs() < 0.01, "{:?}", lambdas);
+        assert!((lambdas[2] - 2.0_f64.ln()).abs() < 0.01, "{:?}", lambdas);
+
+        // after the transient period 2 alternates between two values
+        let diagram = bifurcation_map(&LogisticMap { r: 4.0 }, "r", &[3.2], &[0.3], 1000, 4, &Progress::new()).unwrap();
+        assert!((diagram[0][0] - diagram[0][2]).abs() < 1e-9 && (diagram[0][0] - diagram[0][1]).abs() > 0.1);
+
+        // rows follow b, columns follow a, and keep their order through the threads
+        let a = linspace(0.2, 1.4, 7);
+        let b = [0.1, 0.3];
+        let plane = sweep_plane(&Henon::default(), ("a", &a), ("b", &b), &Progress::new(), |h: &Henon| h.a * 10.0 + h.b).unwrap();
+        assert_eq!(plane.len(), 2);
+        assert!((plane[1][6] - 14.3).abs() < 1e-12 && (plane[0][0] - 2.1).abs() < 1e-12);
+
+        let png_path = std::env::temp_dir().join("chaos_lab_plane_test.png");
+        let heatmap = Heatmap::from_rows_with(&plane, |v| lyapunov_colour(v - 5.0, 10.0));
+        write_png(&png_path, &heatmap).unwrap();
+        let png = std::fs::read(&png_path).unwrap();
+        assert_eq!(&png[1..4], b"PNG");
+        std::fs::remove_file(png_path).unwrap();
+
+        let csv_path = std::env::temp_dir().join("chaos_lab_plane_test.csv");
+        write_plane_csv(&csv_path, ("a", &a), ("b", &b), "lambda", &plane).unwrap();
+        let csv = std::fs::read_to_string(&csv_path).unwrap();
+        assert_eq!(csv.lines().count(), 1 + 14);
+        assert!(csv.starts_with("a,b,lambda\n"));
+        std::fs::remove_file(csv_path).unwrap();
+    }
+
+    #[test]
+    fn test_runner_progress_and_cancel() {
+        use crate::runner::{run, Progress};
+
+        let items: Vec<usize> = (0..1000).collect();
+        let progress = Progress::new();
+        let squares = run(&items, &progress, |i| i * i).unwrap();
+        assert_eq!(squares[999], 999 * 999);
+        assert_eq!((progress.done(), progress.total()), (1000, 1000));
+        assert_eq!(progress.fraction(), 1.0);
+
+        // cancelling from inside the run stops everything not yet started
+        let progress = Progress::new();
+        let result = run(&items, &progress, |i| if *i == 10 { progress.cancel() });
+        assert!(result.is_none());
+        assert!(progress.done() < 1000);
+    }
+
+    #[test]
+    fn test_basins_of_attraction() {
+        use crate::{basins::{duffing_basins, duffing_well, flip_time}, duffing::Duffing, runner::Progress, sweep::linspace};
+
+        // resting at the bottom of a well stays in that well
+        let duffing = Duffing::default();
+        assert_eq!(duffing_well(&duffing, 1.0, 0.0, 20), 1.0);
+        assert_eq!(duffing_well(&duffing, -1.0, 0.0, 20), -1.0);
+
+        // both wells claim a good part of a coarse grid
+        let xs = linspace(-2.0, 2.0, 12);
+        let basins = duffing_basins(&duffing, &xs, &xs, 20, &Progress::new()).unwrap();
+        let right = basins.iter().flatten().filter(|w| **w > 0.0).count();
+        assert!(right > 10 && right < 134, "{}", right);
+
+        // hanging almost straight down can't flip, starting near the top flips quickly
+        let dp = DoublePendulum::default();
+        assert!(flip_time(&dp, 0.3, 0.3, 20.0, 0.01).is_nan());
+        let t = flip_time(&dp, 3.0, 3.0, 20.0, 0.01);
+        assert!(t > 0.0 && t < 5.0, "{}", t);
+    }
+
+    #[test]
+    fn test_ftle_of_a_saddle() {
+        use crate::{ftle::{ftle, ftle_field}, runner::Progress};
+
+        // x′ = x, y′ = -2y stretches at exactly rate 1 everywhere, forward; 2 backward
+        struct Saddle;
+        impl DynamicalSystem for Saddle {
+            fn dimension(&self) -> usize {
+                2
+            }
+            fn rhs(&self, _t: f64, state: &[f64]) -> Vec<f64> {
+                vec![state[0], -2.0 * state[1]]
+            }
+        }
+
+        assert!((ftle(&Saddle, &[0.3, -0.2], (0, 1), 2.0, 0.01, 1e-4) - 1.0).abs() < 1e-6);
+        assert!((ftle(&Saddle, &[0.3, -0.2], (0, 1), -2.0, 0.01, 1e-4) - 2.0).abs() < 1e-6);
+
+        let xs = [-1.0, 0.0, 1.0];
+        let field = ftle_field(&Saddle, &[0.0, 0.0], (0, 1), &xs, &xs[..2], 1.0, 0.01, &Progress::new()).unwrap();
+        assert_eq!((field.len(), field[0].len()), (2, 3));
+        assert!(field.iter().flatten().all(|s| (s - 1.0).abs() < 1e-6));
+    }
+
+    #[test]
+    fn test_recurrence_quantification() {
+        use crate::{dynamical_system::Map, recurrence::{RecurrencePlot, Threshold}};
+        use rand::Rng;
+
+        let orbit = |r: f64| {
+            let map = LogisticMap { r };
+            let mut x = vec![0.3];
+            for _ in 0..500 {
+                x = map.step(&x);
+            }
+            (0..600).map(|_| {
+                x = map.step(&x);
+                x.clone()
+            }).collect::<Vec<Vec<f64>>>()
+        };
+
+        // period 4: every recurrence lies on a full length diagonal
+        let periodic = RecurrencePlot::new(&orbit(3.5), Threshold::Distance(1e-3)).rqa(2, 2, 1);
+        assert!((periodic.recurrence_rate - 0.25).abs() < 0.01, "{}", periodic);
+        assert!(periodic.determinism > 0.999 && periodic.average_diagonal > 100.0, "{}", periodic);
+
+        // chaos is deterministic but its diagonals are short, noise has hardly any diagonals
+        let chaotic = RecurrencePlot::new(&orbit(4.0), Threshold::RecurrenceRate(0.05));
+        let mut rng = crate::rng::rng_generator(1);
+        let noise: Vec<Vec<f64>> = (0..600).map(|_| vec![rng.random::<f64>()]).collect();
+        let noisy = RecurrencePlot::new(&noise, Threshold::RecurrenceRate(0.05));
+        let (chaotic, noisy) = (chaotic.rqa(2, 2, 1), noisy.rqa(2, 2, 1));
+
+        assert!((chaotic.recurrence_rate - 0.05).abs() < 0.005, "{}", chaotic);
+        assert!(chaotic.determinism > 0.6 && noisy.determinism < 0.3, "{} / {}", chaotic, noisy);
+        assert!(chaotic.average_diagonal < periodic.average_diagonal);
+        assert!(chaotic.entropy > noisy.entropy);
+    }
+
+    #[test]
+    fn test_power_spectrum_and_spectrogram() {
+        use crate::{spectrum::{spectrogram, welch, Window}, sweep::ramp_map};
+
+        // a sine at 5 Hz sampled at 100 Hz peaks at 5 Hz, and the density integrates to the variance
+        let signal: Vec<f64> = (0..4096).map(|k| (2.0 * std::f64::consts::PI * 5.0 * k as f64 / 100.0).sin()).collect();
+        for window in [Window::Rectangular, Window::Hann, Window::Blackman] {
+            let spectrum = welch(&signal, 100.0, 512, window);
+            let peak = (0..spectrum.power.len()).max_by(|a, b| spectrum.power[*a].total_cmp(&spectrum.power[*b])).unwrap();
+            assert!((spectrum.frequencies[peak] - 5.0).abs() < 0.2, "{:?}: {}", window, spectrum.frequencies[peak]);
+
+            let df = spectrum.frequencies[1] - spectrum.frequencies[0];
+            let total: f64 = spectrum.power.iter().map(|p| p * df).sum();
+            assert!((total - 0.5).abs() < 0.02, "{:?}: {}", window, total);
+        }
+
+        // logistic map ramped through r = 3: before it one fixed point (no power at 1/2), after it
+        // a period 2 cycle with all its power at 1/2
+        let (_, signal) = ramp_map(&LogisticMap { r: 2.8 }, "r", (2.8, 3.3), 1 << 14, &[0.3]);
+        let spectrogram = spectrogram(&signal, 1.0, 256, 128, Window::Hann);
+        let half = spectrogram.frequencies.len() - 1;
+        let fraction = |p: &Vec<f64>| p[half] / p.iter().sum::<f64>().max(1e-300);
+        assert!((spectrogram.frequencies[half] - 0.5).abs() < 1e-12);
+        assert!(fraction(&spectrogram.power[5]) < 0.1);
+        assert!(fraction(spectrogram.power.last().unwrap()) > 0.5);
+    }
+
+    #[test]
+    fn test_zero_one_test_for_chaos() {
+        use crate::{runner::Progress, trajectory::Trajectory, zero_one::{translation, zero_one_test}};
+
+        let k = |r: f64| {
+            let signal = Trajectory::iterate(&LogisticMap { r }, &[0.3], 2000, 500).component(0);
+            zero_one_test(&signal, 20, 7, &Progress::new()).unwrap().k
+        };
+        // chaotic at r = 4 and 3.9, period 4 at 3.5 (which has a resonance at c = π/2, inside the range)
+        assert!(k(4.0) > 0.9, "{}", k(4.0));
+        assert!(k(3.9) > 0.9, "{}", k(3.9));
+        assert!(k(3.5).abs() < 0.1, "{}", k(3.5));
+
+        // a constant series walks round a circle of radius |φ| / (2 sin(c/2))
+        let (p, q) = translation(&[1.0; 200], 1.0);
+        let radius = 1.0 / (2.0 * 0.5_f64.sin());
+        let centre = (-0.5, 0.5 / 0.5_f64.tan());
+        assert!(p.iter().zip(&q).all(|(p, q)| ((p - centre.0).hypot(q - centre.1) - radius).abs() < 1e-9));
+    }
+
+    #[test]
+    fn test_delay_embedding() {
+        use crate::{embedding::{average_mutual_information, delay_embed, embedding_dimension, false_nearest_neighbours, first_minimum}, henon::Henon, lorenz::Lorenz, trajectory::Trajectory};
+
+        let points = delay_embed(&[0.0, 1.0, 2.0, 3.0, 4.0, 5.0], 3, 2);
+        assert_eq!(points, vec![vec![0.0, 2.0, 4.0], vec![1.0, 3.0, 5.0]]);
+
+        // lorenz x sampled every 0.05: the first minimum of the mutual information is a few samples
+        // in, and the reconstruction needs 3 dimensions
+        let x = Trajectory::integrate(&Lorenz::default(), &[1.0, 1.0, 1.0], 0.01, 5, 2000, 200).component(0);
+        let ami = average_mutual_information(&x, 40, 32);
+        let delay = first_minimum(&ami).unwrap();
+        let fnn = false_nearest_neighbours(&x, delay, 5, 10);
+        assert!((2..=6).contains(&delay), "{}", delay);
+        assert!(fnn[0] > 0.5 && fnn[2] < 0.01, "{:?}", fnn);
+        assert_eq!(embedding_dimension(&fnn, 0.01), Some(3));
+
+        // x_{n+1} = 1 - a x_n² + b x_{n-1}: henon's x with delay 1 needs exactly 2
+        let x = Trajectory::iterate(&Henon::default(), &[0.1, 0.1], 2000, 100).component(0);
+        let fnn = false_nearest_neighbours(&x, 1, 4, 0);
+        assert_eq!(embedding_dimension(&fnn, 0.01), Some(2));
+    }
+
+    #[test]
+    fn test_csv_import() {
+        use crate::{cli::{run, Args}, import::read_csv_trajectory};
+
+        let data = "# seed = 1\nt, angle, velocity\n0.0, 1.0, 0.5\n0.1, 2.0,\n0.2, 3.0, 1.5\n";
+        let trajectory = read_csv_trajectory(data.as_bytes(), &["angle", "2"], Some("t"), 1.0).unwrap();
+        // the row with a missing velocity is skipped
+        assert_eq!(trajectory.times, vec![0.0, 0.2]);
+        assert_eq!(trajectory.states, vec![vec![1.0, 0.5], vec![3.0, 1.5]]);
+
+        let trajectory = read_csv_trajectory(data.as_bytes(), &["angle"], None, 0.5).unwrap();
+        assert_eq!(trajectory.times, vec![0.0, 0.5, 1.0]);
+        assert_eq!(trajectory.component(0), vec![1.0, 2.0, 3.0]);
+
+        assert!(read_csv_trajectory(data.as_bytes(), &["position"], None, 1.0).unwrap_err().to_string().contains("no column 'position'"));
+        let error = read_csv_trajectory("x\n1\none\n".as_bytes(), &["x"], None, 1.0).unwrap_err().to_string();
+        assert!(error.contains("row 2"), "{}", error);
+
+        // the note on measured data follows the list of commands rather than splitting it
+        let mut help = Vec::new();
+        run(&Args::parse(["help"].map(String::from)).unwrap(), &mut help).unwrap();
+        let help = String::from_utf8(help).unwrap();
+        assert!(help.find("    help        print this message").unwrap() < help.find("also work on measured data").unwrap());
+
+        // ten samples leave nothing to fit a correlation dimension to
+        let error = run(&Args::parse(["analyse", "--samples", "10"].map(String::from)).unwrap(), &mut Vec::new()).unwrap_err();
+        assert!(error.contains("not enough points"), "{}", error);
+    }
+
+    #[test]
+    fn test_lyapunov_from_data() {
+        use crate::{embedding::delay_embed, lorenz::Lorenz, lyapunov::{divergence_fit, kantz, rosenstein}, trajectory::Trajectory};
+
+        // lorenz sampled every 0.02, λ1 ≈ 0.906. The divergence curves are only roughly straight,
+        // a manually picked region after the initial alignment gets within ~10%
+        let lorenz = Trajectory::integrate(&Lorenz::default(), &[1.0, 1.0, 1.0], 0.01, 2, 10000, 500);
+        let region = Some((20, 120));
+        let from_states = divergence_fit(&rosenstein(&lorenz.states, 50, 150), 0.02, region).unwrap().slope;
+        let from_x = divergence_fit(&rosenstein(&delay_embed(&lorenz.component(0), 3, 8), 50, 150), 0.02, region).unwrap().slope;
+        let averaged = divergence_fit(&kantz(&lorenz.states, 50, 0.2, 150), 0.02, region).unwrap().slope;
+        for lambda in [from_states, from_x, averaged] {
+            assert!((lambda - 0.906).abs() < 0.12, "{} / {} / {}", from_states, from_x, averaged);
+        }
+
+        // logistic map at r = 4: ln 2 per iteration, straight from the first step so the automatic fit finds it
+        let logistic = Trajectory::iterate(&LogisticMap { r: 4.0 }, &[0.3], 5000, 100);
+        let nearest = divergence_fit(&rosenstein(&logistic.states, 0, 10), 1.0, None).unwrap().slope;
+        let averaged = divergence_fit(&kantz(&logistic.states, 0, 0.001, 10), 1.0, None).unwrap().slope;
+        assert!((nearest - 2.0_f64.ln()).abs() < 0.02, "{}", nearest);
+        assert!((averaged - 2.0_f64.ln()).abs() < 0.02, "{}", averaged);
+    }
+
+    #[test]
+    fn test_kd_tree_matches_brute_force() {
+        use crate::{kd_tree::KdTree, math::distance};
+        use rand::Rng;
+
+        let mut rng = crate::rng::rng_generator(3);
+        let points: Vec<Vec<f64>> = (0..500).map(|_| vec![rng.random::<f64>(), rng.random::<f64>(), rng.random::<f64>()]).collect();
+        let tree = KdTree::new(&points);
+
+        for i in (0..500_usize).step_by(37) {
+            // skipping a window around i, as the theiler window does
+            let skip = |j: usize| i.abs_diff(j) <= 5;
+            let brute = (0..500).filter(|j| !skip(*j)).min_by(|a, b| distance(&points[i], &points[*a]).total_cmp(&distance(&points[i], &points[*b])));
+            assert_eq!(tree.nearest(&points[i], skip).map(|(j, _)| j), brute);
+
+            let mut within = tree.within(&points[i], 0.2, skip);
+            within.sort();
+            let brute: Vec<usize> = (0..500).filter(|j| !skip(*j) && distance(&points[i], &points[*j]) <= 0.2).collect();
+            assert_eq!(within, brute);
+        }
+    }
+
+    #[test]
+    fn test_periodic_orbits() {
+        use crate::{henon::Henon, lorenz::Lorenz, periodic_orbits::{find_flow_orbits, find_map_orbits, map_orbit, Section}, trajectory::Trajectory};
+
+        // the stable 2-cycle of the logistic map at r = 3.2, multiplier 4 + 2r - r²
+        let r = 3.2;
+        let cycle = map_orbit(&LogisticMap { r }, &[0.5], 2).unwrap();
+        let low = (r + 1.0 - ((r - 3.0) * (r + 1.0)).sqrt()) / (2.0 * r);
+        assert_eq!(cycle.n, 2);
+        assert!(cycle.points.iter().any(|p| (p[0] - low).abs() < 1e-9));
+        assert!((cycle.multipliers[0].re - (4.0 + (2.0 * r) - (r * r))).abs() < 1e-5);
+        assert!(cycle.is_stable());
+
+        // at r = 4 there are (2^n - (points of lower period)) / n orbits of period n, all with
+        // multipliers ±2^n apart from the fixed point at 0 on the edge of the interval
+        let chaotic = Trajectory::iterate(&LogisticMap { r: 4.0 }, &[0.3], 5000, 100);
+        let orbits = find_map_orbits(&LogisticMap { r: 4.0 }, &chaotic.states, 5, 0.01);
+        let counts: Vec<usize> = (1..=5).map(|n| orbits.iter().filter(|o| o.n == n).count()).collect();
+        assert_eq!(counts, vec![2, 1, 2, 3, 6]);
+        assert!(orbits.iter().filter(|o| o.points[0][0] > 1e-9).all(|o| (o.multipliers[0].norm() - 2.0_f64.powi(o.n as i32)).abs() < 1e-3));
+
+        // hénon's fixed point on the attractor, a saddle with multipliers multiplying to -b
+        let henon = Henon::default();
+        let attractor = Trajectory::iterate(&henon, &[0.1, 0.1], 2000, 100);
+        let orbits = find_map_orbits(&henon, &attractor.states, 1, 0.05);
+        let x = (-(1.0 - henon.b) + ((1.0 - henon.b).powi(2) + (4.0 * henon.a)).sqrt()) / (2.0 * henon.a);
+        assert_eq!(orbits.len(), 1);
+        assert!((orbits[0].points[0][0] - x).abs() < 1e-9);
+        let product = orbits[0].multipliers[0] * orbits[0].multipliers[1];
+        assert!((product.re + henon.b).abs() < 1e-5 && product.im.abs() < 1e-9);
+
+        // the shortest lorenz orbit, once round each wing (AB): period 1.55865
+        let lorenz = Lorenz::default();
+        let flow = Trajectory::integrate(&lorenz, &[1.0, 1.0, 1.0], 0.01, 5, 1500, 200);
+        let orbits = find_flow_orbits(&lorenz, &flow.states, 25..=40, 2.0, Section { component: 2, value: 27.0 }, 0.01);
+        let ab = orbits.iter().find(|o| o.n == 2).unwrap();
+        assert!((ab.period - 1.55865).abs() < 1e-3, "{}", ab);
+        assert!(ab.multipliers[0].norm() > 1.0 && ab.multipliers[1].norm() < 1e-3, "{}", ab);
+    }
+
+    #[test]
+    fn test_eigenvalues_of_small_matrices() {
+        use crate::math::{characteristic_polynomial, eigenvalues};
+
+        // a rotation by θ scaled by 2: 2e^{±iθ}
+        let (c, s) = (0.6, 0.8);
+        let values = eigenvalues(&[vec![2.0 * c, -2.0 * s], vec![2.0 * s, 2.0 * c]]);
+        assert!(values.iter().all(|v| (v.re - 1.2).abs() < 1e-12 && (v.im.abs() - 1.6).abs() < 1e-12), "{:?}", values);
+
+        // upper triangular, the diagonal
+        let a = vec![vec![3.0, 1.0, 4.0], vec![0.0, -2.0, 5.0], vec![0.0, 0.0, 0.5]];
+        assert_eq!(characteristic_polynomial(&a), vec![3.0, -5.5, -1.5, 1.0]);
+        let values: Vec<f64> = eigenvalues(&a).iter().map(|v| v.re).collect();
+        assert!(values.iter().zip([3.0, -2.0, 0.5]).all(|(v, e)| (v - e).abs() < 1e-12), "{:?}", values);
+    }
+
+    #[test]
+    fn test_continuation_of_equilibria_and_cycles() {
+        use crate::{continuation::{continue_equilibria, continue_flow_orbits, continue_map_orbits, Bifurcation, Continuation}, lorenz::Lorenz, periodic_orbits::{find_flow_orbits, Section}, runner::Progress, trajectory::Trajectory};
+
+        // the origin loses stability in the pitchfork at ρ = 1
+        let lorenz = Lorenz { ro: 0.5, ..Lorenz::default() };
+        let settings = Continuation { range: (0.0, 10.0), ..Continuation::default() };
+        let origin = continue_equilibria(&lorenz, "ro", &[0.0, 0.0, 0.0], settings, &Progress::new()).unwrap();
+        assert_eq!(origin.bifurcations.len(), 1, "{:?}", origin.bifurcations);
+        assert_eq!(origin.bifurcations[0].kind, Bifurcation::BranchPoint);
+        assert!((origin.bifurcations[0].parameter - 1.0).abs() < 1e-6, "{:?}", origin.bifurcations);
+
+        // C+ followed down from ρ = 40: the subcritical hopf at σ(σ + β + 3)/(σ - β - 1), then back to the pitchfork
+        let lorenz = Lorenz { ro: 40.0, ..Lorenz::default() };
+        let c = (lorenz.beta * (lorenz.ro - 1.0)).sqrt();
+        let settings = Continuation { step: -0.05, range: (0.0, 50.0), ..Continuation::default() };
+        let wing = continue_equilibria(&lorenz, "ro", &[c, c, lorenz.ro - 1.0], settings, &Progress::new()).unwrap();
+        let hopf = (lorenz.sigma * (lorenz.sigma + lorenz.beta + 3.0)) / (lorenz.sigma - lorenz.beta - 1.0);
+        let kinds: Vec<Bifurcation> = wing.bifurcations.iter().map(|b| b.kind).collect();
+        assert_eq!(kinds, vec![Bifurcation::Hopf, Bifurcation::BranchPoint, Bifurcation::Hopf], "{:?}", wing.bifurcations);
+        assert!((wing.bifurcations[0].parameter - hopf).abs() < 1e-6, "{:?}", wing.bifurcations);
+        assert!((wing.bifurcations[1].parameter - 1.0).abs() < 1e-6, "{:?}", wing.bifurcations);
+        assert!(wing.points.iter().all(|p| p.stable == (p.parameter < hopf && p.parameter > 1.0)));
+
+        // the logistic map's fixed point doubles at r = 3, its 2-cycle at 1 + √6
+        let settings = Continuation { range: (0.0, 3.5), ..Continuation::default() };
+        let fixed = continue_map_orbits(&LogisticMap { r: 2.0 }, "r", &[0.5], 1, settings, &Progress::new()).unwrap();
+        let doublings: Vec<f64> = fixed.bifurcations.iter().filter(|b| b.kind == Bifurcation::PeriodDoubling).map(|b| b.parameter).collect();
+        assert_eq!(doublings.len(), 1, "{:?}", fixed.bifurcations);
+        assert!((doublings[0] - 3.0).abs() < 1e-6);
+        let cycle = continue_map_orbits(&LogisticMap { r: 3.2 }, "r", &[0.5], 2, settings, &Progress::new()).unwrap();
+        let doublings: Vec<f64> = cycle.bifurcations.iter().filter(|b| b.kind == Bifurcation::PeriodDoubling).map(|b| b.parameter).collect();
+        assert_eq!(doublings.len(), 1, "{:?}", cycle.bifurcations);
+        assert!((doublings[0] - (1.0 + 6.0_f64.sqrt())).abs() < 1e-6);
+        // below r = 3 the only "2-cycle" is the fixed point gone round twice
+        assert!(continue_map_orbits(&LogisticMap { r: 2.0 }, "r", &[0.5], 2, settings, &Progress::new()).is_none());
+
+        // the AB orbit followed down in ρ from 28, its period grows as it heads for the homoclinic orbit at ρ ≈ 13.93
+        let lorenz = Lorenz::default();
+        let section = Section { component: 2, value: 20.0 };
+        let flow = Trajectory::integrate(&lorenz, &[1.0, 1.0, 1.0], 0.01, 5, 1500, 200);
+        let orbits = find_flow_orbits(&lorenz, &flow.states, 25..=40, 2.0, section, 0.01);
+        let seed = &orbits.iter().find(|o| o.n == 2).unwrap().points[0];
+        let settings = Continuation { step: -0.2, max_step: 1.0, max_points: 10, ..Continuation::default() };
+        let ab = continue_flow_orbits(&lorenz, "ro", seed, 2, section, 0.01, settings, &Progress::new()).unwrap();
+        assert_eq!(ab.points.len(), 10);
+        assert!((ab.points[0].period.unwrap() - 1.55865).abs() < 1e-3);
+        assert!(ab.points.windows(2).all(|w| w[1].parameter < w[0].parameter && w[1].period > w[0].period && !w[1].stable));
+    }
+
+    #[test]
+    fn test_feigenbaum_constants() {
+        use crate::{feigenbaum::{feigenbaum, richardson}, sine_map::SineMap};
+
+        // a_n = 1 + 2^-n is exact after one step with ratio 1/2
+        assert!(richardson(&[1.5, 1.25, 1.125], 0.5).iter().all(|a| (a - 1.0).abs() < 1e-15));
+
+        let (delta, alpha) = (4.669201609102991, 2.502907875095893);
+        // R_0 and R_1 alone don't give a single ratio
+        assert!(feigenbaum(&LogisticMap { r: 3.0 }, "r", 0.5, (2.0, 3.2), 1).is_none());
+        let logistic = feigenbaum(&LogisticMap { r: 3.0 }, "r", 0.5, (2.0, 3.2), 12).unwrap();
+        // R_1: r²/4 (1 - r/4) = 1/2 at r = 1 + √5
+        assert!((logistic.steps[1].parameter - (1.0 + 5.0_f64.sqrt())).abs() < 1e-12);
+        assert!((logistic.parameter_infinity - 3.569945671870945).abs() < 1e-9, "{}", logistic);
+        // extrapolating beats the plain ratios
+        let last = &logistic.steps[10];
+        assert!((last.delta_extrapolated.unwrap() - delta).abs() < (last.delta.unwrap() - delta).abs());
+
+        // universal, the sine map has the same constants
+        let sine = feigenbaum(&SineMap { r: 0.8 }, "r", 0.5, (0.5, 0.78), 12).unwrap();
+        for cascade in [logistic, sine] {
+            assert!((cascade.delta - delta).abs() < 1e-6 && (cascade.alpha - alpha).abs() < 1e-6, "{}", cascade);
+        }
+    }
 }
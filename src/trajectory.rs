@@ -0,0 +1,68 @@
+use crate::{dynamical_system::{DynamicalSystem, Map}, integrators::rk4_step};
+
+/// a recorded run: the state at a sequence of times.
+///
+/// the common currency of the analysis tools (dimensions, spectra, recurrence, ...), so they work
+/// the same on any flow, map or measured data
+#[derive(Debug, Clone, Default)]
+pub struct Trajectory {
+    pub times: Vec<f64>,
+    pub states: Vec<Vec<f64>>
+}
+
+impl Trajectory {
+    /// rk4 integration of a flow from `x0`, keeping every `sample_every`-th step.
+    /// The first `transient` samples are thrown away so we're on the attractor
+    pub fn integrate(sys: &impl DynamicalSystem, x0: &[f64], dt: f64, sample_every: usize, samples: usize, transient: usize) -> Self {
+        let mut state = x0.to_vec();
+        let mut t = 0.0;
+        let mut trajectory = Trajectory::default();
+        let sample_every = sample_every.max(1);
+
+        for k in 0..(samples + transient) {
+            for _ in 0..sample_every {
+                rk4_step(sys, &mut state, t, dt);
+                t += dt;
+            }
+            if k >= transient {
+                trajectory.times.push(t);
+                trajectory.states.push(state.clone());
+            }
+        }
+
+        trajectory
+    }
+
+    /// iterates a map from `x0`, `times` are the iteration numbers
+    pub fn iterate(map: &impl Map, x0: &[f64], samples: usize, transient: usize) -> Self {
+        let mut state = x0.to_vec();
+        let mut trajectory = Trajectory::default();
+
+        for k in 0..(samples + transient) {
+            state = map.step(&state);
+            if k >= transient {
+                trajectory.times.push((k + 1) as f64);
+                trajectory.states.push(state.clone());
+            }
+        }
+
+        trajectory
+    }
+
+    pub fn len(&self) -> usize {
+        self.states.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.states.is_empty()
+    }
+
+    pub fn dimension(&self) -> usize {
+        self.states.first().map_or(0, |s| s.len())
+    }
+
+    /// one coordinate over time, as a scalar series
+    pub fn component(&self, i: usize) -> Vec<f64> {
+        self.states.iter().map(|s| s[i]).collect()
+    }
+}
@@ -0,0 +1,74 @@
+use std::{sync::{mpsc::{self, Receiver}, Arc}, thread::JoinHandle};
+
+use chaos_lab::runner::Progress;
+use eframe::egui;
+
+/// a heavy computation running on its own thread so the ui keeps drawing.
+///
+/// the job sends results (whole or partial) back as it goes, `poll` picks them up on the ui
+/// thread. Every send repaints, so partial results show up without waiting for the next input
+pub struct Worker<T> {
+    pub progress: Arc<Progress>,
+    receiver: Receiver<T>,
+    handle: JoinHandle<()>
+}
+
+impl<T: Send + 'static> Worker<T> {
+    /// starts `job` on a new thread. `total` is the number of items the job will tick its
+    /// progress with, 0 when it can't tell (shows a spinner instead of a bar)
+    pub fn spawn(ctx: &egui::Context, total: usize, job: impl FnOnce(&Progress, &dyn Fn(T)) + Send + 'static) -> Self {
+        let progress = Arc::new(if total > 0 { Progress::with_total(total) } else { Progress::new() });
+        let (sender, receiver) = mpsc::channel();
+
+        let ctx = ctx.clone();
+        let job_progress = progress.clone();
+        let handle = std::thread::spawn(move || {
+            let send = |result: T| {
+                // the receiver is gone when the worker was dropped, nobody wants the result anymore
+                let _ = sender.send(result);
+                ctx.request_repaint();
+            };
+            job(&job_progress, &send);
+            ctx.request_repaint();
+        });
+
+        Worker { progress, receiver, handle }
+    }
+
+    /// everything sent since the last poll, in order
+    pub fn poll(&self) -> Vec<T> {
+        self.receiver.try_iter().collect()
+    }
+
+    /// the job returned; poll once more afterwards for whatever it sent last
+    pub fn is_finished(&self) -> bool {
+        self.handle.is_finished()
+    }
+
+    /// progress bar (or spinner) with a cancel button, true when cancel was clicked.
+    /// Cancelling stops sweeps between items; a job that doesn't check `progress` just has its
+    /// results thrown away once the worker is dropped
+    pub fn ui(&self, ui: &mut egui::Ui) -> bool {
+        ui.horizontal(|ui| {
+            if self.progress.total() > 0 {
+                let text = format!("{}/{}", self.progress.done(), self.progress.total());
+                ui.add(egui::ProgressBar::new(self.progress.fraction()).text(text).desired_width(250.0));
+            } else {
+                ui.spinner();
+            }
+            let cancel = ui.button("Cancel").clicked();
+            if cancel {
+                self.progress.cancel();
+            }
+            cancel
+        }).inner
+    }
+}
+
+// a worker that's replaced or thrown away (recomputed, cancelled, switched away from) stops its
+// job rather than leaving it running in the background
+impl<T> Drop for Worker<T> {
+    fn drop(&mut self) {
+        self.progress.cancel();
+    }
+}
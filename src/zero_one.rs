@@ -0,0 +1,90 @@
+use std::f64::consts::PI;
+
+use rand::Rng;
+
+use crate::{math::correlation, rng::rng_generator, runner::{run, Progress}};
+
+/// the translation variables of the 0–1 test: the series drives a walk in the plane, turning by
+/// `c` every step
+/// ```latex
+/// p(n) = Σ_{j=1}^{n} φ(j) cos(jc)
+/// q(n) = Σ_{j=1}^{n} φ(j) sin(jc)
+/// ```
+/// regular motion keeps (p, q) bounded (circles and loops), chaos makes it wander like brownian motion
+pub fn translation(signal: &[f64], c: f64) -> (Vec<f64>, Vec<f64>) {
+    let (mut p, mut q) = (0.0, 0.0);
+    signal.iter().enumerate().map(|(j, phi)| {
+        let angle = (j + 1) as f64 * c;
+        p += phi * angle.cos();
+        q += phi * angle.sin();
+        (p, q)
+    }).unzip()
+}
+
+/// growth rate K of the walk for one `c`, by the correlation method
+/// ```latex
+/// M_c(n) = ⟨[p(j+n) - p(j)]² + [q(j+n) - q(j)]²⟩_j
+/// D_c(n) = M_c(n) - (E φ)² (1 - cos nc) / (1 - cos c)
+/// K_c = corr(n, D_c(n)),    n = 1 .. N/10
+/// ```
+/// the subtracted term is the bounded oscillation the mean of the series alone would cause,
+/// taking it out makes the correlation far sharper. K_c is near 1 when the mean square
+/// displacement grows linearly (chaos) and near 0 when it stays bounded (regular)
+pub fn k_correlation(signal: &[f64], c: f64) -> f64 {
+    let (p, q) = translation(signal, c);
+    let n_total = signal.len();
+    let n_cut = n_total / 10;
+    let mean = signal.iter().sum::<f64>() / n_total as f64;
+
+    let ns: Vec<f64> = (1..=n_cut).map(|n| n as f64).collect();
+    let displacement: Vec<f64> = (1..=n_cut).map(|n| {
+        let msd = (0..(n_total - n)).map(|j| {
+            let (dp, dq) = (p[j + n] - p[j], q[j + n] - q[j]);
+            (dp * dp) + (dq * dq)
+        }).sum::<f64>() / (n_total - n) as f64;
+        msd - (mean * mean * (1.0 - (n as f64 * c).cos()) / (1.0 - c.cos()))
+    }).collect();
+
+    correlation(&ns, &displacement)
+}
+
+/// result of the 0–1 test
+#[derive(Debug, Clone)]
+pub struct ZeroOne {
+    /// median of the K_c, near 0 for regular dynamics and near 1 for chaos
+    pub k: f64,
+    /// (c, K_c) for every c tried, sorted by c
+    pub ks: Vec<(f64, f64)>
+}
+
+impl std::fmt::Display for ZeroOne {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "K = {:.4} (median over {} values of c)", self.k, self.ks.len())
+    }
+}
+
+/// gottwald–melbourne 0–1 test for chaos on a scalar series, re: [`k_correlation`].
+///
+/// model free, it needs no phase space reconstruction, only the one observable. A single c can
+/// land on a resonance with a period of the series and give a spuriously large K_c, so `count`
+/// values of c are drawn uniformly from (π/5, 4π/5) (with `seed`) and the median is taken.
+///
+/// flows should be sampled coarsely (around a quarter of a typical period), an oversampled
+/// series looks regular on short time scales and pulls K towards 0
+pub fn zero_one_test(signal: &[f64], count: usize, seed: u64, progress: &Progress) -> Option<ZeroOne> {
+    let mut rng = rng_generator(seed);
+    let mut cs: Vec<f64> = (0..count).map(|_| rng.random_range((PI / 5.0)..(4.0 * PI / 5.0))).collect();
+    cs.sort_by(|a, b| a.total_cmp(b));
+
+    let ks = run(&cs, progress, |c| k_correlation(signal, *c))?;
+
+    let mut sorted = ks.clone();
+    sorted.sort_by(|a, b| a.total_cmp(b));
+    let k = match sorted.len() {
+        0 => f64::NAN,
+        n if n.is_multiple_of(2) => (sorted[(n / 2) - 1] + sorted[n / 2]) / 2.0,
+        n => sorted[n / 2]
+    };
+
+    Some(ZeroOne { k, ks: cs.into_iter().zip(ks).collect() })
+}